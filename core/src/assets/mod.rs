@@ -0,0 +1,145 @@
+//! Loading of the assets declared by scenes.
+mod placeholder;
+mod server;
+
+pub use placeholder::*;
+pub use server::*;
+
+use crate::scenes::ResultFuture;
+use anyhow::{Context, Result};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use three_d_asset::io::{Deserialize, load_and_deserialize_async};
+
+/// A value that can be loaded from an asset file, either the asset itself or a [Handle] to it
+/// shared through the [AssetServer].
+pub trait LoadAsset: Sized + Send + 'static {
+    fn load<'a>(server: &'a AssetServer, path: &'a Path) -> ResultFuture<'a, Result<Self>>;
+}
+
+impl<T: Deserialize + Send + 'static> LoadAsset for T {
+    fn load<'a>(_: &'a AssetServer, path: &'a Path) -> ResultFuture<'a, Result<Self>> {
+        Box::pin(load_value(path))
+    }
+}
+
+/// Awaits the asset, so declared handles are loaded once the scene is set up.
+impl<T: Deserialize + Send + Sync + 'static> LoadAsset for Handle<T> {
+    fn load<'a>(server: &'a AssetServer, path: &'a Path) -> ResultFuture<'a, Result<Self>> {
+        Box::pin(async move {
+            let handle = server.load(path);
+            handle.loaded().await?;
+            Ok(handle)
+        })
+    }
+}
+
+async fn load_value<T: Deserialize>(path: impl AsRef<Path>) -> Result<T> {
+    let path = path.as_ref();
+    load_and_deserialize_async(path)
+        .await
+        .with_context(|| format!("failed to load asset `{}`", path.display()))
+}
+
+/// Loads the asset at `path`, with an error naming the path if it fails.
+pub async fn load<T: LoadAsset>(server: &AssetServer, path: impl AsRef<Path>) -> Result<T> {
+    T::load(server, path.as_ref()).await
+}
+
+/// Loads the asset at `path`, or returns `None` if there is no file at the path.
+pub async fn load_optional<T: LoadAsset>(
+    server: &AssetServer,
+    path: impl AsRef<Path>,
+) -> Result<Option<T>> {
+    let path = path.as_ref();
+    if is_local(path) && !path.exists() {
+        return Ok(None);
+    }
+
+    load(server, path).await.map(Some)
+}
+
+/// Loads every file matching `pattern`, in the order of their paths, see [matching_paths].
+pub async fn load_many<T: LoadAsset>(
+    server: &AssetServer,
+    pattern: impl AsRef<Path>,
+) -> Result<Vec<T>> {
+    let mut assets = Vec::new();
+    for path in matching_paths(pattern)? {
+        assets.push(load(server, path).await?);
+    }
+
+    Ok(assets)
+}
+
+/// Loads the asset at `path`, or logs the error and returns the fallback if it fails.
+pub async fn load_or<T: LoadAsset>(
+    server: &AssetServer,
+    path: impl AsRef<Path>,
+    fallback: impl FnOnce() -> T,
+) -> T {
+    match load(server, path).await {
+        Ok(asset) => asset,
+        Err(error) => {
+            log::warn!("{error:#}, using a fallback");
+            fallback()
+        }
+    }
+}
+
+/// Files matched by an asset pattern, sorted by path.
+///
+/// A directory matches every file inside it, and `*` or `?` in the file name match any text or
+/// any single character, like `textures/*.png`. Other paths match themselves.
+pub fn matching_paths(pattern: impl AsRef<Path>) -> Result<Vec<PathBuf>> {
+    let pattern = pattern.as_ref();
+    let (directory, file_name) = if pattern.is_dir() {
+        (pattern, "*")
+    } else {
+        match pattern.file_name().and_then(|name| name.to_str()) {
+            Some(name) if name.contains(['*', '?']) => (
+                pattern
+                    .parent()
+                    .filter(|parent| !parent.as_os_str().is_empty())
+                    .unwrap_or(Path::new(".")),
+                name,
+            ),
+            _ => return Ok(vec![pattern.to_path_buf()]),
+        }
+    };
+
+    let entries = fs::read_dir(directory)
+        .with_context(|| format!("failed to read asset directory `{}`", directory.display()))?;
+
+    let file_name: Vec<char> = file_name.chars().collect();
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_file())
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| wildcard_match(&file_name, &name.chars().collect::<Vec<_>>()))
+        })
+        .map(|entry| entry.path())
+        .collect();
+
+    paths.sort();
+    Ok(paths)
+}
+
+fn wildcard_match(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|skip| wildcard_match(rest, &name[skip..])),
+        Some(('?', rest)) => !name.is_empty() && wildcard_match(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && wildcard_match(rest, &name[1..]),
+    }
+}
+
+/// Whether the path is on disk, rather than a URL.
+fn is_local(path: &Path) -> bool {
+    path.to_str().is_none_or(|path| !path.contains("://") && !path.starts_with("data:"))
+}
@@ -0,0 +1,50 @@
+use three_d_asset::{Model, PointCloud, Scene, Texture2D, Texture3D, TextureData, TriMesh};
+
+/// Value of an asset before it is loaded, and the fallback of assets declared with `fallback`.
+pub trait Placeholder {
+    fn placeholder() -> Self;
+}
+
+/// A single magenta pixel, so missing textures stand out.
+impl Placeholder for Texture2D {
+    fn placeholder() -> Self {
+        Self {
+            name: "placeholder".to_string(),
+            data: TextureData::RgbaU8(vec![[255, 0, 255, 255]]),
+            width: 1,
+            height: 1,
+            ..Default::default()
+        }
+    }
+}
+
+impl Placeholder for Texture3D {
+    fn placeholder() -> Self {
+        Self::default()
+    }
+}
+
+/// A model without geometries.
+impl Placeholder for Model {
+    fn placeholder() -> Self {
+        Self { name: "placeholder".to_string(), geometries: Vec::new(), materials: Vec::new() }
+    }
+}
+
+impl Placeholder for Scene {
+    fn placeholder() -> Self {
+        Self::default()
+    }
+}
+
+impl Placeholder for TriMesh {
+    fn placeholder() -> Self {
+        Self::default()
+    }
+}
+
+impl Placeholder for PointCloud {
+    fn placeholder() -> Self {
+        Self::default()
+    }
+}
@@ -0,0 +1,316 @@
+use crate::assets::{Placeholder, load_value, matching_paths};
+use anyhow::{Result, anyhow};
+use futures::executor::block_on;
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt::{Debug, Formatter},
+    future::{Future, poll_fn},
+    path::{Path, PathBuf},
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError, Weak},
+    task::{Poll, Waker},
+};
+use three_d_asset::io::Deserialize;
+
+/// Progress of the asset behind a [Handle].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadState {
+    Loading,
+    Loaded,
+    Failed,
+}
+
+enum State<T> {
+    Loading,
+    Loaded(Arc<T>),
+    Failed(String),
+}
+
+impl<T> State<T> {
+    /// The outcome of the load, `None` while loading.
+    fn result(&self) -> Option<Result<Arc<T>>> {
+        match self {
+            State::Loading => None,
+            State::Loaded(asset) => Some(Ok(Arc::clone(asset))),
+            State::Failed(error) => Some(Err(anyhow!("{error}"))),
+        }
+    }
+}
+
+/// State shared with the background load, which doesn't keep the asset alive.
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    /// Wakes the threads blocked in [Handle::wait].
+    ready: Condvar,
+    /// Wakes the tasks awaiting [Handle::loaded].
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl<T> Shared<T> {
+    fn new(state: State<T>) -> Self {
+        Self { state: Mutex::new(state), ready: Condvar::new(), wakers: Mutex::default() }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn wakers(&self) -> MutexGuard<'_, Vec<Waker>> {
+        self.wakers.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn finish(&self, result: Result<T>) {
+        *self.state() = match result {
+            Ok(asset) => State::Loaded(Arc::new(asset)),
+            Err(error) => State::Failed(format!("{error:#}")),
+        };
+        self.ready.notify_all();
+        self.wakers().drain(..).for_each(Waker::wake);
+    }
+}
+
+struct Slot<T> {
+    path: Option<PathBuf>,
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Slot<T> {
+    fn new(path: Option<PathBuf>, state: State<T>) -> Self {
+        Self { path, shared: Arc::new(Shared::new(state)) }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State<T>> {
+        self.shared.state()
+    }
+}
+
+/// Type erased slot kept by the [AssetServer].
+trait ErasedSlot: Send + Sync {
+    fn path(&self) -> &Path;
+    /// Loads the file again, keeping the previous asset if it fails.
+    fn reload(&self) -> Result<()>;
+    fn into_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync>;
+}
+
+impl<T: Deserialize + Send + Sync + 'static> ErasedSlot for Slot<T> {
+    fn path(&self) -> &Path {
+        self.path.as_deref().unwrap_or(Path::new(""))
+    }
+
+    fn reload(&self) -> Result<()> {
+        let asset = block_on(load_value::<T>(self.path()))?;
+        self.shared.finish(Ok(asset));
+        Ok(())
+    }
+
+    fn into_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync> {
+        self
+    }
+}
+
+/// A shared reference to an asset, loaded by an [AssetServer] or created from a value.
+///
+/// The asset is freed once every handle to it is dropped.
+pub struct Handle<T> {
+    slot: Arc<Slot<T>>,
+}
+
+impl<T> Handle<T> {
+    /// A loaded handle to an asset that doesn't come from a file.
+    pub fn new(asset: T) -> Self {
+        Self { slot: Arc::new(Slot::new(None, State::Loaded(Arc::new(asset)))) }
+    }
+
+    /// Path the asset was loaded from, `None` for handles created with [Handle::new].
+    pub fn path(&self) -> Option<&Path> {
+        self.slot.path.as_deref()
+    }
+
+    pub fn state(&self) -> LoadState {
+        match &*self.slot.state() {
+            State::Loading => LoadState::Loading,
+            State::Loaded(_) => LoadState::Loaded,
+            State::Failed(_) => LoadState::Failed,
+        }
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.state() == LoadState::Loaded
+    }
+
+    /// The asset, if it finished loading.
+    pub fn get(&self) -> Option<Arc<T>> {
+        match &*self.slot.state() {
+            State::Loaded(asset) => Some(Arc::clone(asset)),
+            _ => None,
+        }
+    }
+
+    /// Why the asset failed to load.
+    pub fn error(&self) -> Option<String> {
+        match &*self.slot.state() {
+            State::Failed(error) => Some(error.clone()),
+            _ => None,
+        }
+    }
+
+    /// Blocks until the asset finished loading. Async code awaits [Handle::loaded] instead.
+    pub fn wait(&self) -> Result<Arc<T>> {
+        let mut state = self.slot.state();
+        loop {
+            if let Some(result) = state.result() {
+                return result;
+            }
+
+            state = self.slot.shared.ready.wait(state).unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Resolves once the asset finished loading, without blocking the thread.
+    pub fn loaded(&self) -> impl Future<Output = Result<Arc<T>>> + Send + '_
+    where
+        T: Send + Sync,
+    {
+        poll_fn(|cx| {
+            // The state stays locked while the waker is registered, so the load can't finish
+            // in between and miss it.
+            let state = self.slot.state();
+            if let Some(result) = state.result() {
+                return Poll::Ready(result);
+            }
+
+            let mut wakers = self.slot.shared.wakers();
+            if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                wakers.push(cx.waker().clone());
+            }
+            Poll::Pending
+        })
+    }
+
+    /// Whether both handles point to the same asset.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.slot, &other.slot)
+    }
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        Self { slot: Arc::clone(&self.slot) }
+    }
+}
+
+impl<T> Debug for Handle<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Handle").field("path", &self.path()).field("state", &self.state()).finish()
+    }
+}
+
+/// A loaded handle to the default value of the asset.
+impl<T: Default> Default for Handle<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// A loaded handle to the placeholder of the asset.
+impl<T: Placeholder> Placeholder for Handle<T> {
+    fn placeholder() -> Self {
+        Self::new(T::placeholder())
+    }
+}
+
+type Slots = HashMap<(PathBuf, TypeId), Weak<dyn ErasedSlot>>;
+
+/// Loads assets in the background and shares them between everything loading the same path.
+///
+/// Cloning the server is cheap and gives access to the same assets. Assets are only kept while
+/// a [Handle] to them exists.
+#[derive(Clone, Default)]
+pub struct AssetServer {
+    slots: Arc<Mutex<Slots>>,
+}
+
+impl AssetServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn slots(&self) -> MutexGuard<'_, Slots> {
+        let mut slots = self.slots.lock().unwrap_or_else(PoisonError::into_inner);
+        slots.retain(|_, slot| slot.strong_count() > 0);
+        slots
+    }
+
+    /// Starts loading the asset, or returns the handle of the asset already loaded from the path.
+    pub fn load<T: Deserialize + Send + Sync + 'static>(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Handle<T> {
+        let path = path.as_ref().to_path_buf();
+        let mut slots = self.slots();
+
+        if let Some(handle) = Self::cached(&slots, &path) {
+            return handle;
+        }
+
+        let slot = Arc::new(Slot::new(Some(path.clone()), State::Loading));
+        let erased: Arc<dyn ErasedSlot> = Arc::<Slot<T>>::clone(&slot);
+        slots.insert((path.clone(), TypeId::of::<T>()), Arc::downgrade(&erased));
+        drop(slots);
+
+        let shared = Arc::clone(&slot.shared);
+        rayon::spawn(move || shared.finish(block_on(load_value(path))));
+
+        Handle { slot }
+    }
+
+    /// Handle of the asset loaded from the path, without loading it.
+    pub fn get<T: Send + Sync + 'static>(&self, path: impl AsRef<Path>) -> Option<Handle<T>> {
+        Self::cached(&self.slots(), path.as_ref())
+    }
+
+    fn cached<T: Send + Sync + 'static>(slots: &Slots, path: &Path) -> Option<Handle<T>> {
+        let slot = slots.get(&(path.to_path_buf(), TypeId::of::<T>()))?.upgrade()?;
+        let slot = slot.into_any().downcast::<Slot<T>>().ok()?;
+        Some(Handle { slot })
+    }
+
+    /// Number of assets with at least one handle.
+    pub fn len(&self) -> usize {
+        self.slots().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Reloads the assets loaded from files matching the pattern, see [matching_paths]. Every
+    /// handle sees the new asset, and assets that fail to load keep their previous value.
+    ///
+    /// Returns the number of assets reloaded, or the errors of every asset that failed once the
+    /// others are reloaded.
+    pub fn reload_matching(&self, pattern: impl AsRef<Path>) -> Result<usize> {
+        let pattern = pattern.as_ref();
+        let paths = matching_paths(pattern).unwrap_or_else(|_| vec![pattern.to_path_buf()]);
+        let slots: Vec<Arc<dyn ErasedSlot>> = self
+            .slots()
+            .values()
+            .filter_map(Weak::upgrade)
+            .filter(|slot| paths.iter().any(|path| path == slot.path()))
+            .collect();
+
+        let errors: Vec<String> = slots
+            .iter()
+            .filter_map(|slot| slot.reload().err())
+            .map(|error| format!("{error:#}"))
+            .collect();
+
+        if errors.is_empty() { Ok(slots.len()) } else { Err(anyhow!("{}", errors.join("\n"))) }
+    }
+}
+
+impl Debug for AssetServer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AssetServer").field("len", &self.len()).finish()
+    }
+}
@@ -1,5 +1,9 @@
-use crate::camera::{Camera, CameraType, ControlType};
+use crate::{
+    camera::{Camera, CameraType, ControlType, ViewportRect, post_processing::PostProcessing},
+    scenes::LayerMask,
+};
 use ciri_math::{Transform, Vec3, vector, Quat};
+use three_d::ClearState;
 use three_d_asset::{Viewport, degrees};
 
 pub struct CameraBuilder {
@@ -12,6 +16,11 @@ pub struct CameraBuilder {
     far: f32,
     control: ControlType,
     auto_viewport: bool,
+    culling_mask: LayerMask,
+    viewport_rect: Option<ViewportRect>,
+    render_order: i32,
+    clear_state: ClearState,
+    post_processing: PostProcessing,
 }
 
 impl Default for CameraBuilder {
@@ -26,6 +35,11 @@ impl Default for CameraBuilder {
             far: 1000.0,
             control: ControlType::None,
             auto_viewport: true,
+            culling_mask: LayerMask::ALL,
+            viewport_rect: None,
+            render_order: 0,
+            clear_state: Camera::DEFAULT_CLEAR_STATE,
+            post_processing: PostProcessing::default(),
         }
     }
 }
@@ -138,7 +152,42 @@ impl CameraBuilder {
         self
     }
 
+    /// Only renders objects on these layers.
+    #[must_use]
+    pub fn culling_mask(mut self, mask: LayerMask) -> Self {
+        self.culling_mask = mask;
+        self
+    }
+
+    /// Renders to this part of the screen, every frame even if the camera isn't active.
+    #[must_use]
+    pub fn viewport_rect(mut self, rect: ViewportRect) -> Self {
+        self.viewport_rect = Some(rect);
+        self
+    }
+
+    /// Cameras with a higher order are rendered later, on top of the others.
+    #[must_use]
+    pub fn render_order(mut self, order: i32) -> Self {
+        self.render_order = order;
+        self
+    }
+
+    #[must_use]
+    pub fn clear_state(mut self, clear_state: ClearState) -> Self {
+        self.clear_state = clear_state;
+        self
+    }
+
+    #[must_use]
+    pub fn post_processing(mut self, post_processing: PostProcessing) -> Self {
+        self.post_processing = post_processing;
+        self
+    }
+
+    /// Builds the camera for a screen of the given viewport.
     pub fn build(self, viewport: Viewport) -> Camera {
+        let viewport = self.viewport_rect.map_or(viewport, |rect| rect.to_viewport(viewport));
         let direction = (self.target - self.position).normalize();
         let rotation = Quat::from_rotation_arc(Vec3::Z, direction);
         let transform = Transform::new(self.position, rotation, Vec3::ZERO);
@@ -158,6 +207,11 @@ impl CameraBuilder {
         camera.control = self.control;
         camera.target = self.target;
         camera.auto_viewport = self.auto_viewport;
+        camera.culling_mask = self.culling_mask;
+        camera.viewport_rect = self.viewport_rect;
+        camera.render_order = self.render_order;
+        camera.clear_state = self.clear_state;
+        camera.post_processing = self.post_processing;
 
         camera
     }
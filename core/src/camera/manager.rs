@@ -72,11 +72,10 @@ impl CameraManager {
         self.cameras.keys().copied()
     }
 
+    /// Fits the viewports to the frame and lets the active camera handle the events.
     pub fn handle_events(&mut self, frame: &mut Frame) -> bool {
+        self.update_viewports(frame.viewport());
         if let Some(camera) = self.get_active_camera_mut() {
-            if camera.auto_viewport {
-                camera.set_viewport(frame.viewport());
-            }
             camera.handle_events(&mut frame.input.events)
         } else {
             false
@@ -85,9 +84,24 @@ impl CameraManager {
 
     pub fn update_viewports(&mut self, viewport: Viewport) {
         for camera in self.cameras.values_mut() {
-            if camera.auto_viewport {
-                camera.set_viewport(viewport);
-            }
+            camera.fit_viewport(viewport);
         }
     }
+
+    /// The cameras rendered each frame, in the order they are rendered: the active camera and
+    /// the ones with a [viewport rectangle](Camera::viewport_rect), sorted by
+    /// [render order](Camera::render_order).
+    pub fn rendered_cameras(&self) -> Vec<(CameraId, &Camera)> {
+        let mut cameras: Vec<_> = self
+            .cameras
+            .iter()
+            .filter(|(id, camera)| {
+                Some(**id) == self.active_camera || camera.viewport_rect.is_some()
+            })
+            .map(|(id, camera)| (*id, camera))
+            .collect();
+
+        cameras.sort_by_key(|(id, camera)| (camera.render_order, *id));
+        cameras
+    }
 }
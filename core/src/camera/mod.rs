@@ -1,18 +1,27 @@
 mod builder;
 pub mod manager;
+pub mod post_processing;
 mod presets;
+mod viewport;
 
-use three_d::{ColorMapping, Event, FrameOutput, Mat4, Radians, ToneMapping, Viewer, Viewport};
+use three_d::{
+    ClearState, ColorMapping, Event, FrameOutput, Mat4, Radians, ToneMapping, Viewer, Viewport,
+};
+use three_d_asset::{PixelPoint, ProjectionType};
 
 use crate::{
-    camera::manager::{CameraId, CameraManager},
+    camera::{
+        manager::{CameraId, CameraManager},
+        post_processing::PostProcessing,
+    },
     engine::Engine,
     frame::Frame,
-    scenes::Scene,
+    scenes::{LayerMask, Scene},
 };
 pub use builder::*;
-use ciri_math::{Transform, Vec3, from_glam_vec, to_glam_vec, vector};
+use ciri_math::{Frustum, Ray, Transform, Vec3, from_glam_vec, to_glam_mat4, to_glam_vec, vector};
 pub use presets::*;
+pub use viewport::ViewportRect;
 
 #[derive(Debug, Clone, Copy)]
 pub enum CameraType {
@@ -33,6 +42,17 @@ pub struct Camera {
     pub control: ControlType,
     pub target: Vec3,
     pub auto_viewport: bool,
+    /// Layers of the objects rendered by the camera.
+    pub culling_mask: LayerMask,
+    /// Part of the screen the camera renders to. Cameras with a rectangle are rendered every
+    /// frame, the active camera is also rendered without one, to the whole screen.
+    pub viewport_rect: Option<ViewportRect>,
+    /// Cameras are rendered from the lowest to the highest order, so later ones are drawn on top.
+    pub render_order: i32,
+    /// How the viewport of the camera is cleared before rendering to it.
+    pub clear_state: ClearState,
+    /// Effects applied to the rendered image, which can be enabled and disabled at any time.
+    pub post_processing: PostProcessing,
 }
 
 impl Viewer for Camera {
@@ -70,6 +90,8 @@ impl Viewer for Camera {
 }
 
 impl Camera {
+    pub const DEFAULT_CLEAR_STATE: ClearState = ClearState::color_and_depth(0.5, 0.5, 0.5, 1.0, 1.0);
+
     pub fn new_2d(viewport: Viewport) -> Self {
         let center = vector!(viewport.width as f32 / 2.0, viewport.height as f32 / 2.0, 1.0);
         let target = center - vector!(0.0, 0.0, 1.0);
@@ -89,6 +111,11 @@ impl Camera {
             control: ControlType::None,
             target,
             auto_viewport: true,
+            culling_mask: LayerMask::ALL,
+            viewport_rect: None,
+            render_order: 0,
+            clear_state: Camera::DEFAULT_CLEAR_STATE,
+            post_processing: PostProcessing::default(),
         }
     }
 
@@ -118,6 +145,11 @@ impl Camera {
             control: ControlType::None,
             target,
             auto_viewport: true,
+            culling_mask: LayerMask::ALL,
+            viewport_rect: None,
+            render_order: 0,
+            clear_state: Camera::DEFAULT_CLEAR_STATE,
+            post_processing: PostProcessing::default(),
         }
     }
 
@@ -133,6 +165,35 @@ impl Camera {
         self.inner.set_viewport(viewport)
     }
 
+    /// Fits the viewport to the [viewport rectangle](Camera::viewport_rect) of the screen, if the
+    /// camera has an automatic viewport.
+    pub fn fit_viewport(&mut self, screen: Viewport) -> bool {
+        if !self.auto_viewport {
+            return false;
+        }
+
+        self.set_viewport(self.viewport_rect.unwrap_or_default().to_viewport(screen))
+    }
+
+    /// World space ray through the pixel, starting at the near plane. Pixels are measured from the
+    /// bottom left corner, like the positions of mouse events.
+    pub fn screen_ray(&self, pixel: impl Into<PixelPoint>) -> Ray {
+        let pixel = pixel.into();
+        let direction = to_glam_vec(self.inner.view_direction_at_pixel(pixel));
+        let origin = to_glam_vec(self.inner.position_at_pixel(pixel)) + direction * self.z_near();
+        Ray::new(origin, direction)
+    }
+
+    /// The volume visible to the camera, in world space.
+    pub fn frustum(&self) -> Frustum {
+        Frustum::from_view_projection(to_glam_mat4(self.inner.projection() * self.inner.view()))
+    }
+
+    /// Whether the camera has an orthographic projection, like the ones from [Camera::new_2d].
+    pub fn is_2d(&self) -> bool {
+        matches!(self.inner.projection_type(), ProjectionType::Orthographic { .. })
+    }
+
     pub fn handle_events(&mut self, events: &mut Vec<Event>) -> bool {
         match self.control {
             ControlType::Orbit { min_distance, max_distance } => {
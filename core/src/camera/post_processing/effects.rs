@@ -0,0 +1,193 @@
+use crate::camera::post_processing::{PostContext, PostEffect};
+use std::{
+    fmt::{Debug, Formatter},
+    sync::Arc,
+};
+use three_d::{
+    ColorTarget, ColorTexture, Context, CpuTexture3D, FxaaEffect, Program, Texture2D, Texture3D,
+    vec2,
+};
+
+/// Smooths jagged edges, see [FxaaEffect].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Fxaa;
+
+impl PostEffect for Fxaa {
+    fn apply(&self, post: &PostContext<'_>, input: &Texture2D, target: &ColorTarget<'_>) {
+        target.apply_screen_effect(
+            &FxaaEffect {},
+            post.viewer(target),
+            &[],
+            Some(ColorTexture::Single(input)),
+            None,
+        );
+    }
+}
+
+/// Makes bright parts of the image glow into their surroundings.
+#[derive(Debug, Clone, Copy)]
+pub struct Bloom {
+    /// Brightness above which colors glow, in linear color before tone mapping.
+    pub threshold: f32,
+    /// How strongly the glow is added to the image.
+    pub intensity: f32,
+    /// Spread of the glow, in texels of the half size glow texture.
+    pub radius: f32,
+}
+
+impl Default for Bloom {
+    fn default() -> Self {
+        Self { threshold: 1.0, intensity: 0.5, radius: 1.0 }
+    }
+}
+
+impl PostEffect for Bloom {
+    fn apply(&self, post: &PostContext<'_>, input: &Texture2D, target: &ColorTarget<'_>) {
+        let (width, height) = ((input.width() / 2).max(1), (input.height() / 2).max(1));
+        let mut glow = post.new_texture(width, height);
+        let mut blurred = post.new_texture(width, height);
+
+        post.apply_shader(
+            include_str!("shaders/bloom_bright.frag"),
+            input,
+            &glow.as_color_target(None),
+            |program| program.use_uniform("threshold", self.threshold),
+        );
+
+        let blur = include_str!("shaders/blur.frag");
+        post.apply_shader(blur, &glow, &blurred.as_color_target(None), |program| {
+            program.use_uniform("direction", vec2(self.radius / width as f32, 0.0));
+        });
+        post.apply_shader(blur, &blurred, &glow.as_color_target(None), |program| {
+            program.use_uniform("direction", vec2(0.0, self.radius / height as f32));
+        });
+
+        post.apply_shader(include_str!("shaders/bloom_combine.frag"), input, target, |program| {
+            program.use_texture("bloomTexture", &glow);
+            program.use_uniform("intensity", self.intensity);
+        });
+    }
+}
+
+/// Darkens the image towards its corners.
+#[derive(Debug, Clone, Copy)]
+pub struct Vignette {
+    /// How much the corners are darkened, from 0 to 1.
+    pub intensity: f32,
+    /// Distance from the center where the darkening starts, 1 being the corners.
+    pub radius: f32,
+    /// Distance over which the darkening fades in.
+    pub smoothness: f32,
+}
+
+impl Default for Vignette {
+    fn default() -> Self {
+        Self { intensity: 0.4, radius: 0.5, smoothness: 0.5 }
+    }
+}
+
+impl PostEffect for Vignette {
+    fn apply(&self, post: &PostContext<'_>, input: &Texture2D, target: &ColorTarget<'_>) {
+        post.apply_shader(include_str!("shaders/vignette.frag"), input, target, |program| {
+            program.use_uniform("intensity", self.intensity);
+            program.use_uniform("radius", self.radius);
+            program.use_uniform("smoothness", self.smoothness);
+        });
+    }
+}
+
+/// Remaps colors through a 3D lookup table, indexed by red, green and blue.
+#[derive(Clone)]
+pub struct ColorGrading {
+    pub lut: Arc<Texture3D>,
+    /// Blend between the original colors at 0 and the graded ones at 1.
+    pub intensity: f32,
+}
+
+impl ColorGrading {
+    pub fn new(context: &Context, lut: &CpuTexture3D) -> Self {
+        Self { lut: Arc::new(Texture3D::new(context, lut)), intensity: 1.0 }
+    }
+
+    #[must_use]
+    pub fn with_intensity(mut self, intensity: f32) -> Self {
+        self.intensity = intensity;
+        self
+    }
+}
+
+impl Debug for ColorGrading {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ColorGrading")
+            .field("lut_size", &self.lut.width())
+            .field("intensity", &self.intensity)
+            .finish()
+    }
+}
+
+impl PostEffect for ColorGrading {
+    fn apply(&self, post: &PostContext<'_>, input: &Texture2D, target: &ColorTarget<'_>) {
+        post.apply_shader(include_str!("shaders/color_grading.frag"), input, target, |program| {
+            program.use_texture_3d("lut", &self.lut);
+            program.use_uniform("lutSize", self.lut.width() as f32);
+            program.use_uniform("intensity", self.intensity);
+        });
+    }
+}
+
+/// Scales the brightness of the image and adjusts its gamma curve.
+#[derive(Debug, Clone, Copy)]
+pub struct Exposure {
+    /// Factor the linear colors are multiplied with.
+    pub exposure: f32,
+    /// Colors are raised to the power of `1 / gamma`, so 1 keeps the curve.
+    pub gamma: f32,
+}
+
+impl Default for Exposure {
+    fn default() -> Self {
+        Self { exposure: 1.0, gamma: 1.0 }
+    }
+}
+
+impl PostEffect for Exposure {
+    fn apply(&self, post: &PostContext<'_>, input: &Texture2D, target: &ColorTarget<'_>) {
+        post.apply_shader(include_str!("shaders/exposure.frag"), input, target, |program| {
+            program.use_uniform("exposure", self.exposure);
+            program.use_uniform("gamma", self.gamma);
+        });
+    }
+}
+
+type Uniforms = Box<dyn Fn(&Program) + Send + Sync>;
+
+/// An effect from a fragment shader, see [PostContext::apply_shader] for what it can use.
+pub struct ShaderEffect {
+    pub source: String,
+    uniforms: Uniforms,
+}
+
+impl ShaderEffect {
+    pub fn new(source: impl Into<String>) -> Self {
+        Self { source: source.into(), uniforms: Box::new(|_| {}) }
+    }
+
+    /// Sends the uniforms declared by the shader each time it is applied.
+    #[must_use]
+    pub fn with_uniforms(mut self, uniforms: impl Fn(&Program) + Send + Sync + 'static) -> Self {
+        self.uniforms = Box::new(uniforms);
+        self
+    }
+}
+
+impl Debug for ShaderEffect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ShaderEffect").field("source", &self.source).finish_non_exhaustive()
+    }
+}
+
+impl PostEffect for ShaderEffect {
+    fn apply(&self, post: &PostContext<'_>, input: &Texture2D, target: &ColorTarget<'_>) {
+        post.apply_shader(&self.source, input, target, &self.uniforms);
+    }
+}
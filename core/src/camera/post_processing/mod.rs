@@ -0,0 +1,428 @@
+//! Full screen effects applied to the image rendered by a camera.
+mod effects;
+
+pub use effects::*;
+
+use crate::camera::Camera;
+use std::{
+    any::{Any, type_name},
+    collections::HashMap,
+    fmt::{Debug, Formatter},
+    mem,
+    sync::{LazyLock, Mutex, PoisonError},
+};
+use three_d::{
+    Blend, ClearState, ColorMapping, ColorTarget, ColorTexture, Context, Cull, DepthTest,
+    DepthTexture, DepthTexture2D, Effect, EffectMaterialId, GeometryId, Interpolation, Light, Mat4,
+    Program, RenderStates, RenderTarget, ScreenEffect, Texture2D, ToneMapping, Vec3, Viewer,
+    Viewport, Wrapping, WriteMask, f16,
+};
+
+/// A full screen effect in the [PostProcessing] of a camera.
+pub trait PostEffect: Any + Send + Sync {
+    /// Renders `input`, the image after the previous effects, into `target` with the effect
+    /// applied. Most effects are a single [PostContext::apply_shader].
+    fn apply(&self, post: &PostContext<'_>, input: &Texture2D, target: &ColorTarget<'_>);
+}
+
+struct Entry {
+    effect: Box<dyn PostEffect>,
+    name: &'static str,
+    enabled: bool,
+}
+
+/// Effects applied in order to the image rendered by a camera.
+///
+/// While an effect is enabled, the camera renders the scene into an offscreen texture in linear
+/// color, applies the effects and then its tone and color mapping when copying the image to its
+/// viewport.
+#[derive(Default)]
+pub struct PostProcessing {
+    effects: Vec<Entry>,
+    targets: Mutex<Option<Targets>>,
+}
+
+/// Textures the camera renders into offscreen, kept until the size of its viewport changes.
+struct Targets {
+    width: u32,
+    height: u32,
+    color: Texture2D,
+    output: Texture2D,
+    depth: DepthTexture2D,
+}
+
+impl Targets {
+    fn new(context: &Context, width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            color: new_texture(context, width, height),
+            output: new_texture(context, width, height),
+            depth: DepthTexture2D::new::<f32>(
+                context,
+                width,
+                height,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            ),
+        }
+    }
+}
+
+impl PostProcessing {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn with_effect(mut self, effect: impl PostEffect) -> Self {
+        self.add(effect);
+        self
+    }
+
+    /// Adds an enabled effect after the others, returning its index.
+    pub fn add<T: PostEffect>(&mut self, effect: T) -> usize {
+        self.effects.push(Entry {
+            effect: Box::new(effect),
+            name: type_name::<T>(),
+            enabled: true,
+        });
+        self.effects.len() - 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.effects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    /// Whether any effect is enabled, so the camera renders offscreen.
+    pub fn is_active(&self) -> bool {
+        self.effects.iter().any(|entry| entry.enabled)
+    }
+
+    /// The first effect of type `T`.
+    pub fn get<T: PostEffect>(&self) -> Option<&T> {
+        self.effects.iter().find_map(|entry| (&*entry.effect as &dyn Any).downcast_ref())
+    }
+
+    pub fn get_mut<T: PostEffect>(&mut self) -> Option<&mut T> {
+        self.effects
+            .iter_mut()
+            .find_map(|entry| (&mut *entry.effect as &mut dyn Any).downcast_mut())
+    }
+
+    /// Enables or disables every effect of type `T`, returning whether there is one.
+    pub fn set_enabled<T: PostEffect>(&mut self, enabled: bool) -> bool {
+        let mut found = false;
+        for entry in &mut self.effects {
+            if (&*entry.effect as &dyn Any).is::<T>() {
+                entry.enabled = enabled;
+                found = true;
+            }
+        }
+        found
+    }
+
+    /// Whether the first effect of type `T` is enabled.
+    pub fn is_enabled<T: PostEffect>(&self) -> bool {
+        self.effects
+            .iter()
+            .find(|entry| (&*entry.effect as &dyn Any).is::<T>())
+            .is_some_and(|entry| entry.enabled)
+    }
+
+    /// Enables or disables the effect at `index`, returning whether there is one.
+    pub fn set_enabled_at(&mut self, index: usize, enabled: bool) -> bool {
+        self.effects.get_mut(index).map(|entry| entry.enabled = enabled).is_some()
+    }
+
+    pub fn is_enabled_at(&self, index: usize) -> bool {
+        self.effects.get(index).is_some_and(|entry| entry.enabled)
+    }
+
+    /// Renders the scene with `draw` into an offscreen texture the size of the camera's viewport,
+    /// applies the enabled effects and copies the result into the viewport on `screen`.
+    pub(crate) fn render(
+        &self,
+        context: &Context,
+        camera: &Camera,
+        screen: &RenderTarget<'_>,
+        draw: impl FnOnce(&RenderTarget<'_>, &dyn Viewer),
+    ) {
+        let viewport = camera.viewport();
+        let mut targets = self.targets.lock().unwrap_or_else(PoisonError::into_inner);
+        let Targets { color, output, depth, .. } = match targets.take() {
+            Some(cached) if (cached.width, cached.height) == (viewport.width, viewport.height) => {
+                targets.insert(cached)
+            }
+            _ => targets.insert(Targets::new(context, viewport.width, viewport.height)),
+        };
+
+        let clear = camera.clear_state;
+        let clears_color = clear.red.is_some();
+        {
+            let target = RenderTarget::new(color.as_color_target(None), depth.as_depth_target());
+            // Cameras that don't clear the color draw over the ones before through the alpha.
+            target.clear(ClearState {
+                red: Some(clear.red.unwrap_or(0.0)),
+                green: Some(clear.green.unwrap_or(0.0)),
+                blue: Some(clear.blue.unwrap_or(0.0)),
+                alpha: Some(clear.alpha.unwrap_or(0.0)),
+                depth: Some(clear.depth.unwrap_or(1.0)),
+            });
+            draw(&target, &Offscreen::new(camera, viewport.width, viewport.height));
+        }
+
+        let post = PostContext { context, camera, depth };
+        for entry in self.effects.iter().filter(|entry| entry.enabled) {
+            entry.effect.apply(&post, color, &output.as_color_target(None));
+            mem::swap(color, output);
+        }
+
+        let blend = if clears_color { Blend::Disabled } else { Blend::TRANSPARENCY };
+        screen.apply_screen_effect_partially(
+            viewport.into(),
+            &ScreenEffect { write_mask: WriteMask::COLOR, blend },
+            camera,
+            &[],
+            Some(ColorTexture::Single(color)),
+            None,
+        );
+    }
+}
+
+impl Debug for PostProcessing {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.effects.iter().map(|entry| (entry.name, entry.enabled))).finish()
+    }
+}
+
+/// What a [PostEffect] renders with.
+pub struct PostContext<'a> {
+    pub context: &'a Context,
+    pub camera: &'a Camera,
+    /// Depth of the rendered scene.
+    pub depth: &'a DepthTexture2D,
+}
+
+impl PostContext<'_> {
+    /// An empty texture like the ones effects render into, with half float colors so values
+    /// brighter than white are kept.
+    pub fn new_texture(&self, width: u32, height: u32) -> Texture2D {
+        new_texture(self.context, width, height)
+    }
+
+    /// Renders a full screen fragment shader into the target.
+    ///
+    /// The shader reads the input with `sample_color(uvs)` and the depth of the scene with
+    /// `sample_depth(uvs)`, and writes `outColor`; both inputs and outputs are already declared.
+    /// `uniforms` sends the uniforms the shader declares itself.
+    pub fn apply_shader(
+        &self,
+        source: &str,
+        input: &Texture2D,
+        target: &ColorTarget<'_>,
+        uniforms: impl Fn(&Program),
+    ) {
+        // Unused textures are optimized out of the shader, so they can't be sent.
+        let color = source.contains("sample_color").then_some(ColorTexture::Single(input));
+        let depth = source.contains("sample_depth").then_some(DepthTexture::Single(self.depth));
+
+        target.apply_screen_effect(
+            &ShaderPass { source, id: shader_id(self.context, source), uniforms },
+            self.viewer(target),
+            &[],
+            color,
+            depth,
+        );
+    }
+
+    /// The camera rendering to the whole target.
+    fn viewer(&self, target: &ColorTarget<'_>) -> Offscreen<'_> {
+        Offscreen::new(self.camera, target.width(), target.height())
+    }
+}
+
+fn new_texture(context: &Context, width: u32, height: u32) -> Texture2D {
+    Texture2D::new_empty::<[f16; 4]>(
+        context,
+        width,
+        height,
+        Interpolation::Linear,
+        Interpolation::Linear,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    )
+}
+
+/// The camera rendering into a texture of its own, without tone and color mapping so the colors
+/// stay linear.
+struct Offscreen<'a> {
+    camera: &'a Camera,
+    viewport: Viewport,
+}
+
+impl<'a> Offscreen<'a> {
+    fn new(camera: &'a Camera, width: u32, height: u32) -> Self {
+        Self { camera, viewport: Viewport::new_at_origo(width, height) }
+    }
+}
+
+impl Viewer for Offscreen<'_> {
+    fn position(&self) -> Vec3 {
+        self.camera.position()
+    }
+
+    fn view(&self) -> Mat4 {
+        self.camera.view()
+    }
+
+    fn projection(&self) -> Mat4 {
+        self.camera.projection()
+    }
+
+    fn viewport(&self) -> Viewport {
+        self.viewport
+    }
+
+    fn z_near(&self) -> f32 {
+        self.camera.z_near()
+    }
+
+    fn z_far(&self) -> f32 {
+        self.camera.z_far()
+    }
+
+    fn color_mapping(&self) -> ColorMapping {
+        ColorMapping::None
+    }
+
+    fn tone_mapping(&self) -> ToneMapping {
+        ToneMapping::None
+    }
+}
+
+/// A fragment shader of [PostContext::apply_shader] as a three-d effect.
+struct ShaderPass<'a, F> {
+    source: &'a str,
+    id: u16,
+    uniforms: F,
+}
+
+impl<F: Fn(&Program)> Effect for ShaderPass<'_, F> {
+    fn fragment_shader_source(
+        &self,
+        _: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> String {
+        format!(
+            "{}\n{}\nin vec2 uvs;\nlayout (location = 0) out vec4 outColor;\n\n{}",
+            color_texture.map(|texture| texture.fragment_shader_source()).unwrap_or_default(),
+            depth_texture.map(|texture| texture.fragment_shader_source()).unwrap_or_default(),
+            self.source,
+        )
+    }
+
+    fn id(
+        &self,
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> EffectMaterialId {
+        EffectMaterialId(
+            self.id
+                | color_texture.map_or(0, |texture| texture.id())
+                | depth_texture.map_or(0, |texture| texture.id()),
+        )
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        _: &dyn Viewer,
+        _: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        if let Some(texture) = color_texture {
+            texture.use_uniforms(program);
+        }
+        if let Some(texture) = depth_texture {
+            texture.use_uniforms(program);
+        }
+        (self.uniforms)(program);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            cull: Cull::Back,
+            ..Default::default()
+        }
+    }
+}
+
+/// Ids three-d leaves for effects outside of it, below the sky material of the engine. The low
+/// bits are taken by the texture ids.
+const SHADER_IDS: std::ops::Range<u16> = 0x0000..0x4F00;
+const SHADER_ID_STEP: u16 = 0x80;
+
+/// Effect ids handed out to post-processing shaders, with the use count they were last used at.
+#[derive(Default)]
+struct ShaderIds {
+    ids: HashMap<String, (u16, u64)>,
+    uses: u64,
+}
+
+/// Effect id of a post-processing shader, which three-d uses to cache its program.
+///
+/// Once every id is taken, the least recently used shader gives its id up and its programs are
+/// removed from the cache of `context`.
+fn shader_id(context: &Context, source: &str) -> u16 {
+    static IDS: LazyLock<Mutex<ShaderIds>> = LazyLock::new(Mutex::default);
+
+    let mut shaders = IDS.lock().unwrap_or_else(PoisonError::into_inner);
+    shaders.uses += 1;
+    let used = shaders.uses;
+
+    if let Some((id, last_used)) = shaders.ids.get_mut(source) {
+        *last_used = used;
+        return *id;
+    }
+
+    let next = u16::try_from(shaders.ids.len())
+        .ok()
+        .and_then(|index| index.checked_mul(SHADER_ID_STEP))
+        .filter(|id| SHADER_IDS.contains(id));
+
+    let id = next.unwrap_or_else(|| {
+        let (source, id) = shaders
+            .ids
+            .iter()
+            .min_by_key(|(_, (_, last_used))| *last_used)
+            .map(|(source, (id, _))| (source.clone(), *id))
+            .expect("every shader id is taken");
+        shaders.ids.remove(&source);
+        forget_programs(context, id);
+        id
+    });
+
+    shaders.ids.insert(source.to_owned(), (id, used));
+    id
+}
+
+/// Removes the cached programs of the screen effect with the id, whatever its textures.
+fn forget_programs(context: &Context, id: u16) {
+    let screen = GeometryId::Screen.0.to_le_bytes();
+
+    context.programs.write().unwrap_or_else(PoisonError::into_inner).retain(|key, _| {
+        let effect = key.get(2..4).map(|effect| u16::from_le_bytes([effect[0], effect[1]]));
+        !(key.starts_with(&screen)
+            && effect.is_some_and(|effect| effect & !(SHADER_ID_STEP - 1) == id))
+    });
+}
@@ -0,0 +1,9 @@
+uniform float threshold;
+
+void main()
+{
+    vec4 color = sample_color(uvs);
+    float brightness = max(color.r, max(color.g, color.b));
+    float contribution = max(brightness - threshold, 0.0) / max(brightness, 0.0001);
+    outColor = vec4(color.rgb * contribution, 1.0);
+}
@@ -0,0 +1,8 @@
+uniform sampler2D bloomTexture;
+uniform float intensity;
+
+void main()
+{
+    vec4 color = sample_color(uvs);
+    outColor = vec4(color.rgb + texture(bloomTexture, uvs).rgb * intensity, color.a);
+}
@@ -0,0 +1,15 @@
+uniform vec2 direction;
+
+const float weights[5] = float[](0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216);
+
+void main()
+{
+    vec3 sum = sample_color(uvs).rgb * weights[0];
+    for (int i = 1; i < 5; i++)
+    {
+        vec2 offset = direction * float(i);
+        sum += sample_color(uvs + offset).rgb * weights[i];
+        sum += sample_color(uvs - offset).rgb * weights[i];
+    }
+    outColor = vec4(sum, 1.0);
+}
@@ -0,0 +1,12 @@
+uniform sampler3D lut;
+uniform float lutSize;
+uniform float intensity;
+
+void main()
+{
+    vec4 color = sample_color(uvs);
+    // Samples the centers of the first and last texels for black and white.
+    vec3 coordinates = clamp(color.rgb, 0.0, 1.0) * (lutSize - 1.0) / lutSize + 0.5 / lutSize;
+    vec3 graded = texture(lut, coordinates).rgb;
+    outColor = vec4(mix(color.rgb, graded, intensity), color.a);
+}
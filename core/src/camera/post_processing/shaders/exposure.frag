@@ -0,0 +1,8 @@
+uniform float exposure;
+uniform float gamma;
+
+void main()
+{
+    vec4 color = sample_color(uvs);
+    outColor = vec4(pow(max(color.rgb * exposure, vec3(0.0)), vec3(1.0 / gamma)), color.a);
+}
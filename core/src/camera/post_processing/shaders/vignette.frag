@@ -0,0 +1,12 @@
+uniform float intensity;
+uniform float radius;
+uniform float smoothness;
+
+void main()
+{
+    vec4 color = sample_color(uvs);
+    // Distance from the center, 1 in the corners.
+    float distance = length(uvs - 0.5) * 1.41421356;
+    float vignette = 1.0 - intensity * smoothstep(radius, radius + smoothness, distance);
+    outColor = vec4(color.rgb * vignette, color.a);
+}
@@ -0,0 +1,51 @@
+use three_d_asset::Viewport;
+
+/// The part of the screen a camera renders to, as fractions of the screen size measured from
+/// its bottom left corner.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewportRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Default for ViewportRect {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+impl ViewportRect {
+    pub const BOTTOM_HALF: Self = Self::new(0.0, 0.0, 1.0, 0.5);
+    pub const FULL: Self = Self::new(0.0, 0.0, 1.0, 1.0);
+    pub const LEFT_HALF: Self = Self::new(0.0, 0.0, 0.5, 1.0);
+    pub const RIGHT_HALF: Self = Self::new(0.5, 0.0, 0.5, 1.0);
+    pub const TOP_HALF: Self = Self::new(0.0, 0.5, 1.0, 0.5);
+
+    pub const fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    /// The rectangle in pixels of the screen. Edges are rounded to whole pixels, so rectangles
+    /// sharing an edge never overlap or leave a gap.
+    pub fn to_viewport(&self, screen: Viewport) -> Viewport {
+        #[expect(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            reason = "fractions are clamped, so edges are within the screen"
+        )]
+        let edge =
+            |fraction: f32, size: u32| (fraction.clamp(0.0, 1.0) * size as f32).round() as u32;
+        let (left, right) = (edge(self.x, screen.width), edge(self.x + self.width, screen.width));
+        let (bottom, top) =
+            (edge(self.y, screen.height), edge(self.y + self.height, screen.height));
+
+        Viewport {
+            x: screen.x + left.cast_signed(),
+            y: screen.y + bottom.cast_signed(),
+            width: right.saturating_sub(left),
+            height: top.saturating_sub(bottom),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
+    assets::AssetServer,
     frame::Frame,
-    scenes::{SceneTrait, UpdateResult, manager::SceneManager},
+    scenes::{UpdateResult, manager::SceneManager},
 };
 use three_d::{Context, FrameInput};
 
@@ -10,18 +11,22 @@ pub struct Engine {
 
 impl Engine {
     fn update_inner(&mut self, input: FrameInput) -> UpdateResult {
-        let scene = self
-            .scenes
-            .active_scene_mut()
-            .expect("no active scene found. use engine.scenes.set_active::<T>() to set a scene");
+        self.scenes.reload_changed_assets()?;
 
-        scene.full_update(&mut Frame::new(input))
+        self.scenes
+            .with_active(|scene| scene.full_update(&mut Frame::new(input)))
+            .expect("no active scene found. use engine.scenes.set_active::<T>() to set a scene")
     }
 
     pub fn update(&mut self, input: FrameInput) -> UpdateResult {
         self.update_inner(input)
     }
 
+    /// Server loading the assets of every scene.
+    pub fn assets(&self) -> &AssetServer {
+        self.scenes.assets()
+    }
+
     pub fn new(context: Context) -> Self {
         Self { scenes: SceneManager::new(context.clone()) }
     }
@@ -30,6 +30,12 @@ impl Frame {
         self.input.screen().render(camera, objects, lights);
     }
 
+    /// Clears only the part of the screen inside the viewport.
+    pub fn clear_viewport(&self, viewport: Viewport, state: ClearState) -> &Self {
+        self.input.screen().clear_partially(viewport.into(), state);
+        self
+    }
+
     pub fn viewport(&self) -> Viewport {
         self.input.screen().viewport()
     }
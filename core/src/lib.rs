@@ -1,5 +1,7 @@
 extern crate core;
+extern crate self as ciri;
 
+pub mod assets;
 mod bounding_box;
 pub mod camera;
 pub mod engine;
@@ -11,7 +13,14 @@ pub mod macros;
 pub mod model;
 mod object;
 pub mod options;
+pub mod resources;
 pub mod scenes;
+pub mod sprites;
 
 pub use bounding_box::*;
 pub use ciri_math as math;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use anyhow;
+}
@@ -1,16 +1,27 @@
-use ciri_math::{Mat4, Vec3, from_glam_vec};
-use three_d::{Context, DepthTexture2D};
+use crate::{
+    camera::Camera,
+    lights::{
+        WithoutShadows,
+        shadows::{Cascades, ShadowMap, ShadowSettings, render_shadow_maps},
+    },
+};
+use ciri_math::{Vec3, from_glam_vec};
+use std::{
+    fmt::Write,
+    mem,
+    sync::{PoisonError, RwLock, RwLockReadGuard},
+};
+use three_d::{Context, Geometry, Light, LightId, Program, Viewer};
 use three_d_asset::Srgba;
 
 pub struct DirectionalLightBuilder {
-    shadow_texture: Option<DepthTexture2D>,
-    shadow_matrix: Mat4,
     /// The intensity of the light. This allows for higher intensity than 1 which can be used to simulate high-intensity light sources like the sun.
     pub intensity: f32,
     /// The base color of the light.
     pub color: Srgba,
     /// The direction the light shines.
     pub direction: Vec3,
+    pub shadows: Option<ShadowSettings>,
 }
 
 impl DirectionalLightBuilder {
@@ -32,34 +43,265 @@ impl DirectionalLightBuilder {
         self
     }
 
-    pub fn build(&self, context: &Context) -> three_d::DirectionalLight {
-        three_d::DirectionalLight::new(
-            context,
-            self.intensity,
-            self.color,
-            from_glam_vec(self.direction),
-        )
+    /// Casts shadows with shadow maps of `resolution` texels in width and height.
+    #[must_use]
+    pub fn shadows(mut self, resolution: u32) -> Self {
+        self.shadows.get_or_insert_default().resolution = resolution;
+        self
     }
-}
 
-pub struct DirectionalLight {
-    pub opts: DirectionalLightBuilder,
+    /// Casts shadows with the given bias, see [ShadowSettings::bias].
+    #[must_use]
+    pub fn shadow_bias(mut self, bias: f32, slope_bias: f32) -> Self {
+        let shadows = self.shadows.get_or_insert_default();
+        shadows.bias = bias;
+        shadows.slope_bias = slope_bias;
+        self
+    }
+
+    /// Casts shadows split into `count` cascades, up to `max_distance` from the camera.
+    #[must_use]
+    pub fn cascades(mut self, count: u32, max_distance: f32) -> Self {
+        self.shadows.get_or_insert_default().cascades = Some(Cascades::new(count, max_distance));
+        self
+    }
+
+    pub fn build(&self, context: &Context) -> DirectionalLight {
+        DirectionalLight {
+            context: context.clone(),
+            intensity: self.intensity,
+            color: self.color,
+            direction: self.direction,
+            shadows: self.shadows,
+            shadow_state: RwLock::default(),
+        }
+    }
 }
 
 impl Default for DirectionalLightBuilder {
     fn default() -> Self {
         Self {
-            shadow_texture: None,
-            shadow_matrix: Mat4::IDENTITY,
             intensity: 1.0,
             color: Srgba::WHITE,
             direction: Vec3::new(0.0, -1.0, 0.0),
+            shadows: None,
         }
     }
 }
 
+#[derive(Default)]
+struct ShadowState {
+    maps: Vec<ShadowMap>,
+    /// Position and view direction of the camera the maps were rendered for.
+    eye: Vec3,
+    forward: Vec3,
+}
+
+/// A light shining in one direction, like the sun, which can cast shadows.
+pub struct DirectionalLight {
+    context: Context,
+    pub intensity: f32,
+    pub color: Srgba,
+    pub direction: Vec3,
+    /// How the light casts shadows, `None` if it doesn't.
+    pub shadows: Option<ShadowSettings>,
+    shadow_state: RwLock<ShadowState>,
+}
+
 impl DirectionalLight {
     pub fn builder() -> DirectionalLightBuilder {
         DirectionalLightBuilder::default()
     }
+
+    fn shadow_state(&self) -> RwLockReadGuard<'_, ShadowState> {
+        self.shadow_state.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Renders the shadow maps of the light again for what the camera sees, with `casters`
+    /// casting the shadows. Scenes call this before rendering each camera.
+    pub fn update_shadows(&self, camera: &Camera, casters: &[&dyn Geometry]) {
+        let Some(settings) = &self.shadows else {
+            return;
+        };
+
+        // The textures of the previous maps are rendered into again while the resolution stays.
+        let previous =
+            mem::take(&mut *self.shadow_state.write().unwrap_or_else(PoisonError::into_inner));
+        let textures = previous.maps.into_iter().map(|map| map.texture).collect();
+        let maps =
+            render_shadow_maps(&self.context, settings, self.direction, camera, casters, textures);
+        *self.shadow_state.write().unwrap_or_else(PoisonError::into_inner) = ShadowState {
+            maps,
+            eye: ciri_math::to_glam_vec(camera.position()),
+            forward: ciri_math::to_glam_vec(camera.inner.view_direction()),
+        };
+    }
+
+    /// Removes the shadow maps until they are updated again.
+    pub fn clear_shadows(&self) {
+        *self.shadow_state.write().unwrap_or_else(PoisonError::into_inner) = ShadowState::default();
+    }
+
+    /// Number of shadow maps rendered by the last update, one per cascade.
+    pub fn shadow_map_count(&self) -> usize {
+        self.shadow_state().maps.len()
+    }
+
+    /// The light without its shadows, for objects which don't receive shadows.
+    pub fn without_shadows(&self) -> WithoutShadows<'_, Self> {
+        WithoutShadows(self)
+    }
+
+    fn shader_source(i: u32, cascades: usize) -> String {
+        let mut source = format!("uniform vec3 color{i};\nuniform vec3 direction{i};\n");
+        if cascades == 0 {
+            let _ = write!(
+                source,
+                "
+                vec3 calculate_lighting{i}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
+                {{
+                    return calculate_light(color{i}, -direction{i}, surface_color, view_direction, normal, metallic, roughness);
+                }}
+                "
+            );
+            return source;
+        }
+
+        let _ = write!(
+            source,
+            "
+            uniform vec3 shadowEye{i};
+            uniform vec3 shadowForward{i};
+
+            float shadow_visibility{i}(sampler2D shadow_map, mat4 shadow_matrix, vec2 bias, vec3 position, vec3 normal)
+            {{
+                vec4 coordinates = shadow_matrix * vec4(position, 1.0);
+                vec3 uvz = coordinates.xyz / coordinates.w;
+                if (uvz.x < 0.0 || uvz.x > 1.0 || uvz.y < 0.0 || uvz.y > 1.0 || uvz.z > 1.0) {{
+                    return 1.0;
+                }}
+
+                float slope = 1.0 - clamp(dot(normal, -direction{i}), 0.0, 1.0);
+                float depth = uvz.z - bias.x - bias.y * slope;
+                vec2 texel = 1.0 / vec2(textureSize(shadow_map, 0));
+                float visibility = 0.0;
+                for (int x = -1; x <= 1; x++) {{
+                    for (int y = -1; y <= 1; y++) {{
+                        visibility += texture(shadow_map, uvz.xy + vec2(x, y) * texel).x < depth ? 0.0 : 1.0;
+                    }}
+                }}
+                return visibility / 9.0;
+            }}
+            "
+        );
+
+        let mut select = String::new();
+        for c in 0..cascades {
+            let _ = write!(
+                source,
+                "
+                uniform sampler2D shadowMap{i}_{c};
+                uniform mat4 shadowMatrix{i}_{c};
+                uniform float shadowDistance{i}_{c};
+                uniform vec2 shadowBias{i}_{c};
+                "
+            );
+            let _ = write!(
+                select,
+                "
+                if (distance < shadowDistance{i}_{c}) {{
+                    return shadow_visibility{i}(shadowMap{i}_{c}, shadowMatrix{i}_{c}, shadowBias{i}_{c}, position, normal);
+                }}
+                "
+            );
+        }
+
+        let _ = write!(
+            source,
+            "
+            float shadow{i}(vec3 position, vec3 normal)
+            {{
+                float distance = dot(position - shadowEye{i}, shadowForward{i});
+                {select}
+                return 1.0;
+            }}
+
+            vec3 calculate_lighting{i}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
+            {{
+                return calculate_light(color{i}, -direction{i}, surface_color, view_direction, normal, metallic, roughness)
+                    * shadow{i}(position, normal);
+            }}
+            "
+        );
+        source
+    }
+
+    fn use_uniforms(&self, program: &Program, i: u32, shadows: Option<&ShadowState>) {
+        program.use_uniform(
+            &format!("color{i}"),
+            self.color.to_linear_srgb().truncate() * self.intensity,
+        );
+        program.use_uniform(&format!("direction{i}"), from_glam_vec(self.direction.normalize()));
+
+        let Some(shadows) = shadows.filter(|shadows| !shadows.maps.is_empty()) else {
+            return;
+        };
+        program.use_uniform(&format!("shadowEye{i}"), from_glam_vec(shadows.eye));
+        program.use_uniform(&format!("shadowForward{i}"), from_glam_vec(shadows.forward));
+        for (c, map) in shadows.maps.iter().enumerate() {
+            program.use_depth_texture(&format!("shadowMap{i}_{c}"), &map.texture);
+            program.use_uniform(&format!("shadowMatrix{i}_{c}"), map.matrix);
+            program.use_uniform(&format!("shadowDistance{i}_{c}"), map.distance);
+            program.use_uniform(
+                &format!("shadowBias{i}_{c}"),
+                three_d::vec2(map.bias[0], map.bias[1]),
+            );
+        }
+    }
+}
+
+/// Light ids of directional lights, offset by their number of shadow maps.
+const LIGHT_ID: u8 = 0x40;
+
+/// The clone has no shadow maps until its shadows are updated.
+impl Clone for DirectionalLight {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+            intensity: self.intensity,
+            color: self.color,
+            direction: self.direction,
+            shadows: self.shadows,
+            shadow_state: RwLock::default(),
+        }
+    }
+}
+
+impl Light for DirectionalLight {
+    fn shader_source(&self, i: u32) -> String {
+        Self::shader_source(i, self.shadow_map_count())
+    }
+
+    fn use_uniforms(&self, program: &Program, i: u32) {
+        self.use_uniforms(program, i, Some(&self.shadow_state()));
+    }
+
+    #[expect(clippy::cast_possible_truncation, reason = "there are at most MAX_CASCADES maps")]
+    fn id(&self) -> LightId {
+        LightId(LIGHT_ID + self.shadow_map_count() as u8)
+    }
+}
+
+impl Light for WithoutShadows<'_, DirectionalLight> {
+    fn shader_source(&self, i: u32) -> String {
+        DirectionalLight::shader_source(i, 0)
+    }
+
+    fn use_uniforms(&self, program: &Program, i: u32) {
+        self.0.use_uniforms(program, i, None);
+    }
+
+    fn id(&self) -> LightId {
+        LightId(LIGHT_ID)
+    }
 }
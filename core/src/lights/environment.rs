@@ -0,0 +1,294 @@
+use crate::{
+    impl_component,
+    scenes::{Component, Scene},
+};
+use ciri_math::Vec3;
+use std::{any::Any, fmt::Debug, sync::Arc};
+use three_d::{
+    ColorMapping, Context, CpuTexture, Cull, DepthTest, EffectMaterialId, Geometry, Light, LightId,
+    Mat3, Material, MaterialType, Program, RenderStates, Skybox, TextureCubeMap, TextureData,
+    ToneMapping, Viewer, radians,
+};
+use three_d_asset::Srgba;
+
+/// Texels in width and height of each face of the cube maps of procedural skies.
+const SKY_FACE_SIZE: u32 = 32;
+
+/// What the sky of an [Environment] looks like.
+#[derive(Debug, Clone)]
+pub enum Sky {
+    /// An equirectangular image, usually a HDR one for realistic lighting.
+    Image(CpuTexture),
+    Color(Srgba),
+    /// Colors blending from the top of the sky to the horizon and then to the bottom.
+    Gradient {
+        top: Srgba,
+        horizon: Srgba,
+        bottom: Srgba,
+    },
+}
+
+impl Sky {
+    pub fn gradient(top: Srgba, horizon: Srgba, bottom: Srgba) -> Self {
+        Self::Gradient { top, horizon, bottom }
+    }
+
+    /// Linear color of the sky in the direction, `None` for images.
+    pub fn linear_color(&self, direction: Vec3) -> Option<Vec3> {
+        let linear = |color: &Srgba| {
+            let color = color.to_linear_srgb();
+            Vec3::new(color.x, color.y, color.z)
+        };
+
+        match self {
+            Self::Image(_) => None,
+            Self::Color(color) => Some(linear(color)),
+            Self::Gradient { top, horizon, bottom } => {
+                let height = direction.normalize_or_zero().y;
+                Some(if height >= 0.0 {
+                    linear(horizon).lerp(linear(top), height)
+                } else {
+                    linear(horizon).lerp(linear(bottom), -height)
+                })
+            }
+        }
+    }
+
+    fn to_skybox(&self, context: &Context) -> Skybox {
+        if let Self::Image(image) = self {
+            return Skybox::new_from_equirectangular(context, image);
+        }
+
+        // Directions of the texels of each face, in the order and orientation of OpenGL cube maps.
+        let faces: [fn(f32, f32) -> Vec3; 6] = [
+            |s, t| Vec3::new(1.0, -t, -s),
+            |s, t| Vec3::new(-1.0, -t, s),
+            |s, t| Vec3::new(s, 1.0, t),
+            |s, t| Vec3::new(s, -1.0, -t),
+            |s, t| Vec3::new(s, -t, 1.0),
+            |s, t| Vec3::new(-s, -t, -1.0),
+        ];
+        let coordinate = |texel: u32| 2.0 * (texel as f32 + 0.5) / SKY_FACE_SIZE as f32 - 1.0;
+        let [right, left, top, bottom, front, back] = faces.map(|face| {
+            let data = (0..SKY_FACE_SIZE)
+                .flat_map(|row| (0..SKY_FACE_SIZE).map(move |column| (column, row)))
+                .map(|(column, row)| {
+                    let color = self
+                        .linear_color(face(coordinate(column), coordinate(row)))
+                        .unwrap_or_default();
+                    [color.x, color.y, color.z, 1.0]
+                })
+                .collect();
+
+            CpuTexture {
+                data: TextureData::RgbaF32(data),
+                width: SKY_FACE_SIZE,
+                height: SKY_FACE_SIZE,
+                ..Default::default()
+            }
+        });
+
+        Skybox::new(context, &right, &left, &top, &bottom, &front, &back)
+    }
+}
+
+struct EnvironmentMaps {
+    skybox: Skybox,
+    lighting: three_d::Environment,
+}
+
+/// A sky drawn behind the scene which also lights it, through image-based lighting.
+///
+/// Scenes use the environment of their first active object with one. Clones of the component
+/// share the same sky, but not its rotation and intensity.
+#[derive(Clone)]
+pub struct Environment {
+    maps: Arc<EnvironmentMaps>,
+    /// Rotation of the sky and its light around the up axis, in radians.
+    pub rotation: f32,
+    /// Brightness of the sky and of the light it shines on the scene.
+    pub intensity: f32,
+    /// Whether the sky is drawn, otherwise it only lights the scene.
+    pub show_sky: bool,
+}
+
+impl Environment {
+    /// Creates the cube map of the sky and the maps lighting the scene from it, which takes a
+    /// while for large images.
+    pub fn new(context: &Context, sky: &Sky) -> Self {
+        let skybox = sky.to_skybox(context);
+        let lighting = three_d::Environment::new(context, skybox.texture());
+        Self {
+            maps: Arc::new(EnvironmentMaps { skybox, lighting }),
+            rotation: 0.0,
+            intensity: 1.0,
+            show_sky: true,
+        }
+    }
+
+    #[must_use]
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    #[must_use]
+    pub fn with_intensity(mut self, intensity: f32) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    /// Only lights the scene, without drawing the sky.
+    #[must_use]
+    pub fn without_sky(mut self) -> Self {
+        self.show_sky = false;
+        self
+    }
+
+    /// Cube map of the sky.
+    pub fn texture(&self) -> &Arc<TextureCubeMap> {
+        self.maps.skybox.texture()
+    }
+
+    /// The ambient light the environment shines on the scene.
+    pub fn light(&self) -> EnvironmentLight {
+        EnvironmentLight {
+            maps: Arc::clone(&self.maps),
+            rotation: self.rotation,
+            intensity: self.intensity,
+        }
+    }
+
+    /// Draws the sky where nothing has been drawn yet.
+    pub(crate) fn render_sky(&self, viewer: &dyn Viewer) {
+        if !self.show_sky {
+            return;
+        }
+
+        let material = SkyMaterial {
+            texture: self.texture(),
+            rotation: rotation_matrix(self.rotation),
+            intensity: self.intensity,
+        };
+        self.maps.skybox.render_with_material(&material, viewer, &[]);
+    }
+}
+
+impl_component!(Environment);
+
+/// Rotates world directions into the unrotated sky.
+fn rotation_matrix(rotation: f32) -> Mat3 {
+    Mat3::from_angle_y(radians(-rotation))
+}
+
+/// Material id of the sky, the last of the ids three-d leaves for materials outside of it. The
+/// post-processing shaders take the ones below.
+const SKY_MATERIAL_ID: u16 = 0x4F00;
+
+struct SkyMaterial<'a> {
+    texture: &'a TextureCubeMap,
+    rotation: Mat3,
+    intensity: f32,
+}
+
+impl Material for SkyMaterial<'_> {
+    fn id(&self) -> EffectMaterialId {
+        EffectMaterialId(SKY_MATERIAL_ID)
+    }
+
+    fn fragment_shader_source(&self, _: &[&dyn Light]) -> String {
+        format!(
+            "{}{}{}",
+            ToneMapping::fragment_shader_source(),
+            ColorMapping::fragment_shader_source(),
+            include_str!("shaders/sky.frag"),
+        )
+    }
+
+    fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer, _: &[&dyn Light]) {
+        viewer.tone_mapping().use_uniforms(program);
+        viewer.color_mapping().use_uniforms(program);
+        program.use_texture_cube("skyTexture", self.texture);
+        program.use_uniform("rotation", self.rotation);
+        program.use_uniform("intensity", self.intensity);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates { depth_test: DepthTest::LessOrEqual, cull: Cull::Front, ..Default::default() }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
+
+/// Light id of environment lights.
+const LIGHT_ID: u8 = 0x60;
+
+/// Ambient light shining from an [Environment], following its rotation.
+pub struct EnvironmentLight {
+    maps: Arc<EnvironmentMaps>,
+    pub rotation: f32,
+    pub intensity: f32,
+}
+
+impl Light for EnvironmentLight {
+    fn shader_source(&self, i: u32) -> String {
+        format!(
+            "
+            uniform samplerCube irradianceMap{i};
+            uniform samplerCube prefilterMap{i};
+            uniform sampler2D brdfLUT{i};
+            uniform mat3 environmentRotation{i};
+            uniform float environmentIntensity{i};
+
+            vec3 calculate_lighting{i}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
+            {{
+                vec3 N = environmentRotation{i} * normal;
+                vec3 V = environmentRotation{i} * view_direction;
+                vec3 R = reflect(-V, N);
+                float NdV = max(0.001, dot(N, V));
+
+                vec3 F0 = mix(vec3(0.04), surface_color, metallic);
+                vec3 specular_fresnel = fresnel_schlick_roughness(F0, NdV, roughness);
+                vec3 diffuse_fresnel = 1.0 - specular_fresnel;
+
+                vec3 irradiance = texture(irradianceMap{i}, N).rgb;
+                vec3 diffuse = diffuse_fresnel * mix(surface_color, vec3(0.0), metallic) * irradiance;
+
+                const float MAX_REFLECTION_LOD = 4.0;
+                vec3 prefiltered = textureLod(prefilterMap{i}, R, roughness * MAX_REFLECTION_LOD).rgb;
+                vec2 brdf = texture(brdfLUT{i}, vec2(NdV, roughness)).rg;
+                vec3 specular = prefiltered * (specular_fresnel * brdf.x + brdf.y);
+
+                return (diffuse + specular) * occlusion * environmentIntensity{i};
+            }}
+            "
+        )
+    }
+
+    fn use_uniforms(&self, program: &Program, i: u32) {
+        let lighting = &self.maps.lighting;
+        program.use_texture_cube(&format!("irradianceMap{i}"), &lighting.irradiance_map);
+        program.use_texture_cube(&format!("prefilterMap{i}"), &lighting.prefilter_map);
+        program.use_texture(&format!("brdfLUT{i}"), &lighting.brdf_map);
+        program.use_uniform(&format!("environmentRotation{i}"), rotation_matrix(self.rotation));
+        program.use_uniform(&format!("environmentIntensity{i}"), self.intensity);
+    }
+
+    fn id(&self) -> LightId {
+        LightId(LIGHT_ID)
+    }
+}
+
+impl Scene {
+    /// The [Environment] of the first active object with one, by id.
+    pub fn environment(&self) -> Option<&Environment> {
+        self.objects
+            .iter()
+            .filter(|(_, object)| object.is_active())
+            .filter_map(|(id, object)| Some((id, object.get_component::<Environment>()?)))
+            .min_by_key(|(id, _)| **id)
+            .map(|(_, environment)| environment)
+    }
+}
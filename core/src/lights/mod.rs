@@ -1,14 +1,130 @@
 mod ambient;
 mod directional;
+mod environment;
+mod point;
+mod shadows;
+mod source;
+mod spot;
 
-use crate::scenes::Scene;
+use crate::{
+    camera::Camera,
+    lights::source::SceneLightRef,
+    scenes::{GameObjectId, Renderer, Scene, components::PlacedRenderable},
+};
 pub use ambient::*;
 pub use directional::*;
-use std::sync::Arc;
-use three_d::Light;
+pub use environment::{Environment, EnvironmentLight, Sky};
+pub use point::*;
+pub use shadows::{Cascades, MAX_CASCADES, ShadowSettings};
+pub use source::{AttachableLight, LightSource};
+pub use spot::*;
+use std::{any::Any, sync::Arc};
+use three_d::{Attenuation, Geometry, Light};
+
+/// A light that can be added to a scene, any three-d light which can be shared between threads.
+pub trait SceneLight: Light + Send + Sync + Any {}
+
+impl<T: Light + Send + Sync + Any> SceneLight for T {}
+
+/// A light lighting without its shadows, for objects which don't receive shadows.
+pub struct WithoutShadows<'a, L>(pub &'a L);
+
+/// Attenuation fading a light out over `range` world units, leaving about one percent of it at
+/// that distance.
+pub fn range_attenuation(range: f32) -> Attenuation {
+    let range = range.max(f32::EPSILON);
+    Attenuation { constant: 1.0, linear: 4.5 / range, quadratic: 75.0 / (range * range) }
+}
+
+/// The lights of a scene which cast shadows.
+enum ShadowLight<'a> {
+    Directional(&'a DirectionalLight),
+    Spot(&'a SpotLight),
+}
+
+impl<'a> ShadowLight<'a> {
+    fn new(light: &'a dyn SceneLight) -> Option<Self> {
+        let light = light as &dyn Any;
+        light
+            .downcast_ref()
+            .map(ShadowLight::Directional)
+            .or_else(|| light.downcast_ref().map(ShadowLight::Spot))
+    }
+
+    fn casts_shadows(&self) -> bool {
+        match self {
+            Self::Directional(light) => light.shadows.is_some(),
+            Self::Spot(light) => light.shadows.is_some(),
+        }
+    }
+
+    fn update_shadows(&self, camera: &Camera, casters: &[&dyn Geometry]) {
+        match self {
+            Self::Directional(light) => light.update_shadows(camera, casters),
+            Self::Spot(light) => light.update_shadows(casters),
+        }
+    }
+
+    fn without_shadows(&self) -> Box<dyn Light + 'a> {
+        match self {
+            Self::Directional(light) => Box::new(light.without_shadows()),
+            Self::Spot(light) => Box::new(light.without_shadows()),
+        }
+    }
+}
 
 impl Scene {
-    pub fn add_light(&mut self, light: impl Light + Send + Sync + 'static) {
+    pub fn add_light(&mut self, light: impl SceneLight) {
         self.lights.push(Arc::new(light));
     }
+
+    /// Active objects with a [Renderer], or an
+    /// [InstancedRenderer](crate::scenes::components::InstancedRenderer), casting shadows.
+    pub fn shadow_casters(&self) -> impl Iterator<Item = (GameObjectId, &Renderer)> + '_ {
+        self.objects.iter().filter_map(|(id, object)| {
+            let renderer = object.renderer()?;
+            (object.is_active() && renderer.cast_shadows).then_some((*id, renderer))
+        })
+    }
+
+    /// Renders the shadow maps of the lights for what the camera sees. Mesh batches cast shadows
+    /// once a camera has drawn them.
+    pub fn update_shadows(&self, camera: &Camera) {
+        let all_lights = self.all_lights();
+        let lights: Vec<_> = all_lights
+            .iter()
+            .filter_map(|light| ShadowLight::new(&**light))
+            .filter(ShadowLight::casts_shadows)
+            .collect();
+        if lights.is_empty() {
+            return;
+        }
+
+        // Skyboxes and other renderables without bounds can't cast shadows.
+        let instancing = self.instancing();
+        let casters: Vec<_> = self
+            .shadow_casters()
+            .map(|(id, renderer)| self.placed(id, renderer))
+            .chain(instancing.renderers().map(|(renderer, _)| PlacedRenderable::from(renderer)))
+            .filter(|caster| caster.world_aabb().is_some())
+            .collect();
+        let geometries: Vec<&dyn Geometry> =
+            casters.iter().map(|caster| caster as &dyn Geometry).collect();
+
+        for light in lights {
+            light.update_shadows(camera, &geometries);
+        }
+    }
+}
+
+/// The lights with the ones casting shadows replaced by their
+/// [version without shadows](WithoutShadows).
+pub(crate) fn without_shadows<'a>(lights: &'a [SceneLightRef<'_>]) -> Vec<Box<dyn Light + 'a>> {
+    lights
+        .iter()
+        .map(|light| match ShadowLight::new(&**light) {
+            Some(light) => light.without_shadows(),
+            None => Box::new(&**light as &dyn Light),
+        })
+        .collect()
 }
@@ -0,0 +1,108 @@
+use crate::lights::range_attenuation;
+use ciri_math::{Vec3, from_glam_vec};
+use three_d::{Attenuation, Light, LightId, Program};
+use three_d_asset::Srgba;
+
+pub struct PointLightBuilder {
+    /// The intensity of the light. This allows for higher intensity than 1 which can be used to simulate high-intensity light sources like the sun.
+    pub intensity: f32,
+    /// The base color of the light.
+    pub color: Srgba,
+    /// Where the light shines from.
+    pub position: Vec3,
+    /// How the light fades with the distance.
+    pub attenuation: Attenuation,
+}
+
+impl PointLightBuilder {
+    #[must_use]
+    pub fn intensity(mut self, intensity: f32) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    #[must_use]
+    pub fn color(mut self, color: Srgba) -> Self {
+        self.color = color;
+        self
+    }
+
+    #[must_use]
+    pub fn position(mut self, position: Vec3) -> Self {
+        self.position = position;
+        self
+    }
+
+    #[must_use]
+    pub fn attenuation(mut self, attenuation: Attenuation) -> Self {
+        self.attenuation = attenuation;
+        self
+    }
+
+    /// Fades the light out over `range` world units, see [range_attenuation].
+    #[must_use]
+    pub fn range(mut self, range: f32) -> Self {
+        self.attenuation = range_attenuation(range);
+        self
+    }
+
+    pub fn build(&self) -> PointLight {
+        PointLight {
+            intensity: self.intensity,
+            color: self.color,
+            position: self.position,
+            attenuation: self.attenuation,
+        }
+    }
+}
+
+impl Default for PointLightBuilder {
+    fn default() -> Self {
+        Self {
+            intensity: 1.0,
+            color: Srgba::WHITE,
+            position: Vec3::ZERO,
+            attenuation: Attenuation::default(),
+        }
+    }
+}
+
+/// A light shining from a position in all directions, like a lamp or a torch. Point lights don't
+/// cast shadows.
+#[derive(Clone)]
+pub struct PointLight {
+    pub intensity: f32,
+    pub color: Srgba,
+    pub position: Vec3,
+    pub attenuation: Attenuation,
+}
+
+impl PointLight {
+    pub fn builder() -> PointLightBuilder {
+        PointLightBuilder::default()
+    }
+
+    /// The same light as three-d's, which draws it.
+    fn to_three_d(&self) -> three_d::PointLight {
+        three_d::PointLight {
+            intensity: self.intensity,
+            color: self.color,
+            position: from_glam_vec(self.position),
+            attenuation: self.attenuation,
+        }
+    }
+}
+
+impl Light for PointLight {
+    fn shader_source(&self, i: u32) -> String {
+        self.to_three_d().shader_source(i)
+    }
+
+    fn use_uniforms(&self, program: &Program, i: u32) {
+        self.to_three_d().use_uniforms(program, i);
+    }
+
+    fn id(&self) -> LightId {
+        LightId::PointLight
+    }
+}
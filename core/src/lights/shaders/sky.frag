@@ -0,0 +1,14 @@
+uniform samplerCube skyTexture;
+uniform mat3 rotation;
+uniform float intensity;
+
+in vec3 coords;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    outColor = vec4(texture(skyTexture, rotation * coords).rgb * intensity, 1.0);
+    outColor.rgb = tone_mapping(outColor.rgb);
+    outColor.rgb = color_mapping(outColor.rgb);
+}
@@ -0,0 +1,283 @@
+use crate::camera::Camera;
+use ciri_math::{Aabb, Mat4, Vec3, from_glam_mat4, from_glam_vec, to_glam_mat4, to_glam_vec};
+use std::convert::Infallible;
+use three_d::{
+    ClearState, Context, DepthMaterial, DepthTexture2D, Geometry, RenderStates, Viewer, Viewport,
+    Wrapping, WriteMask, render_with_material,
+};
+
+/// Most cascades a light can split its shadows into.
+pub const MAX_CASCADES: u32 = 4;
+
+/// How a light renders its shadow maps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShadowSettings {
+    /// Width and height of each shadow map in texels.
+    pub resolution: u32,
+    /// Distance in world units surfaces are moved towards the light before testing if they are
+    /// in shadow, which removes stripes of wrong shadows on lit surfaces.
+    pub bias: f32,
+    /// Additional bias for surfaces at a grazing angle to the light, in world units.
+    pub slope_bias: f32,
+    /// Splits of the camera's view with a shadow map each, `None` for a single map fitted around
+    /// every shadow caster.
+    pub cascades: Option<Cascades>,
+}
+
+impl Default for ShadowSettings {
+    fn default() -> Self {
+        Self { resolution: 2048, bias: 0.02, slope_bias: 0.05, cascades: None }
+    }
+}
+
+/// Splits of the view of a camera, closer ones cover less space so their shadows are sharper.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cascades {
+    /// Number of splits, up to [MAX_CASCADES].
+    pub count: u32,
+    /// Distance from the camera after which nothing receives shadows.
+    pub max_distance: f32,
+    /// Blend between evenly spaced splits at 0 and splits growing with the distance at 1.
+    pub split_lambda: f32,
+}
+
+impl Cascades {
+    pub fn new(count: u32, max_distance: f32) -> Self {
+        Self { count: count.clamp(1, MAX_CASCADES), max_distance, split_lambda: 0.75 }
+    }
+
+    /// Distances from the camera where each cascade ends, for a camera seeing from `near` to
+    /// `far`.
+    pub fn splits(&self, near: f32, far: f32) -> Vec<f32> {
+        let far = far.min(self.max_distance).max(near);
+        let count = self.count.clamp(1, MAX_CASCADES);
+        (1..=count)
+            .map(|index| {
+                let t = index as f32 / count as f32;
+                let logarithmic = near.max(f32::EPSILON) * (far / near.max(f32::EPSILON)).powf(t);
+                let uniform = (far - near).mul_add(t, near);
+                self.split_lambda.mul_add(logarithmic, (1.0 - self.split_lambda) * uniform)
+            })
+            .collect()
+    }
+}
+
+/// A rendered shadow map, covering the view of the camera up to `distance`.
+pub struct ShadowMap {
+    pub texture: DepthTexture2D,
+    /// Transforms world positions to texture coordinates and depth of the map.
+    pub matrix: three_d::Mat4,
+    pub distance: f32,
+    /// Constant and slope bias, in the depth range of orthographic maps and in world units for
+    /// perspective ones.
+    pub bias: [f32; 2],
+}
+
+/// Renders the shadow maps of a light shining in `direction` for what `camera` sees, into
+/// `textures` where they have the resolution of the maps.
+pub fn render_shadow_maps(
+    context: &Context,
+    settings: &ShadowSettings,
+    direction: Vec3,
+    camera: &Camera,
+    casters: &[&dyn Geometry],
+    mut textures: Vec<DepthTexture2D>,
+) -> Vec<ShadowMap> {
+    let Some(bounds) = casters
+        .iter()
+        .map(Geometry::aabb)
+        .filter(|aabb| !aabb.is_empty() && !aabb.is_infinite())
+        .map(|aabb| Aabb::new(to_glam_vec(aabb.min()), to_glam_vec(aabb.max())))
+        .reduce(|a, b| a.union(&b))
+    else {
+        return Vec::new();
+    };
+
+    let slices = match settings.cascades {
+        Some(cascades) => {
+            let mut near = camera.z_near();
+            cascades
+                .splits(camera.z_near(), camera.z_far())
+                .into_iter()
+                .map(|far| {
+                    let slice = (frustum_slice(camera, near, far), far);
+                    near = far;
+                    slice
+                })
+                .collect()
+        }
+        None => vec![(bounds.corners(), f32::MAX)],
+    };
+
+    slices
+        .into_iter()
+        .map(|(points, distance)| {
+            let shadow_camera = fit_shadow_camera(direction, &points, &bounds, settings.resolution);
+            let depth_range = shadow_camera.z_far() - shadow_camera.z_near();
+            ShadowMap {
+                texture: render_depth(context, &shadow_camera, casters, textures.pop()),
+                matrix: shadow_matrix(&shadow_camera),
+                distance,
+                bias: [settings.bias / depth_range, settings.slope_bias / depth_range],
+            }
+        })
+        .collect()
+}
+
+/// Renders the shadow map of a spot light at `position` shining in `direction`, in a cone of
+/// `angle` radians from its direction. `texture` is reused if it has the resolution of the map.
+pub fn render_spot_shadow_map(
+    context: &Context,
+    settings: &ShadowSettings,
+    position: Vec3,
+    direction: Vec3,
+    angle: f32,
+    casters: &[&dyn Geometry],
+    texture: Option<DepthTexture2D>,
+) -> Option<ShadowMap> {
+    let (z_near, z_far) = casters
+        .iter()
+        .map(Geometry::aabb)
+        .filter(|aabb| !aabb.is_empty() && !aabb.is_infinite())
+        .map(|aabb| Aabb::new(to_glam_vec(aabb.min()), to_glam_vec(aabb.max())))
+        .fold(None, |range: Option<(f32, f32)>, aabb| {
+            let near = aabb.distance_squared(position).sqrt();
+            let far = aabb
+                .corners()
+                .into_iter()
+                .map(|corner| corner.distance(position))
+                .fold(0.0, f32::max);
+            Some(range.map_or((near, far), |(min, max)| (min.min(near), max.max(far))))
+        })?;
+
+    let direction = direction.normalize();
+    let shadow_camera = three_d::Camera::new_perspective(
+        Viewport::new_at_origo(settings.resolution, settings.resolution),
+        from_glam_vec(position),
+        from_glam_vec(position + direction),
+        from_glam_vec(up_for(direction)),
+        three_d::radians(2.0 * angle.min(MAX_SPOT_ANGLE)),
+        z_near.max(MIN_SPOT_NEAR),
+        z_far.max(MIN_SPOT_NEAR * 2.0),
+    );
+
+    Some(ShadowMap {
+        texture: render_depth(context, &shadow_camera, casters, texture),
+        matrix: shadow_matrix(&shadow_camera),
+        distance: f32::MAX,
+        bias: [settings.bias, settings.slope_bias],
+    })
+}
+
+/// Widest cone of a spot light shadow, in radians from its direction, as perspective projections
+/// can't see half of all directions.
+const MAX_SPOT_ANGLE: f32 = 1.5;
+const MIN_SPOT_NEAR: f32 = 0.01;
+
+fn up_for(direction: Vec3) -> Vec3 {
+    if direction.dot(Vec3::Y).abs() > 0.99 { Vec3::X } else { Vec3::Y }
+}
+
+/// Corners of the part of the camera's view between the distances `near` and `far`.
+fn frustum_slice(camera: &Camera, near: f32, far: f32) -> [Vec3; 8] {
+    let inverse = to_glam_mat4(camera.projection() * camera.view()).inverse();
+    let (z_near, z_far) = (camera.z_near(), camera.z_far());
+    let (near, far) = ((near - z_near) / (z_far - z_near), (far - z_near) / (z_far - z_near));
+
+    let mut corners = [Vec3::ZERO; 8];
+    for (index, (x, y)) in
+        [(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)].into_iter().enumerate()
+    {
+        // View depth changes linearly along the edges of the view, in both projections.
+        let start = inverse.project_point3(Vec3::new(x, y, -1.0));
+        let end = inverse.project_point3(Vec3::new(x, y, 1.0));
+        corners[index] = start.lerp(end, near);
+        corners[index + 4] = start.lerp(end, far);
+    }
+    corners
+}
+
+/// An orthographic camera looking along the light which sees every point, and every caster
+/// between them and the light.
+fn fit_shadow_camera(
+    direction: Vec3,
+    points: &[Vec3],
+    casters: &Aabb,
+    resolution: u32,
+) -> three_d::Camera {
+    let direction = direction.normalize();
+    let center = points.iter().sum::<Vec3>() / points.len() as f32;
+    let radius = points.iter().map(|point| point.distance(center)).fold(f32::EPSILON, f32::max);
+    let up = up_for(direction);
+
+    // Snaps the center to whole texels, so shadows don't shimmer while the camera moves.
+    let view = Mat4::look_to_rh(Vec3::ZERO, direction, up);
+    let texel = 2.0 * radius / resolution as f32;
+    let light_center = view.transform_point3(center);
+    let snapped = Vec3::new(
+        (light_center.x / texel).round() * texel,
+        (light_center.y / texel).round() * texel,
+        light_center.z,
+    );
+    let position = view.inverse().transform_point3(snapped);
+
+    let towards_light = casters
+        .corners()
+        .into_iter()
+        .map(|corner| (position - corner).dot(direction))
+        .fold(radius, f32::max);
+
+    three_d::Camera::new_orthographic(
+        Viewport::new_at_origo(resolution, resolution),
+        from_glam_vec(position),
+        from_glam_vec(position + direction),
+        from_glam_vec(up),
+        2.0 * radius,
+        -towards_light,
+        radius,
+    )
+}
+
+/// Renders the depth of the casters seen by the camera, into `texture` if it has the size of its
+/// viewport.
+fn render_depth(
+    context: &Context,
+    camera: &three_d::Camera,
+    casters: &[&dyn Geometry],
+    texture: Option<DepthTexture2D>,
+) -> DepthTexture2D {
+    let viewport = camera.viewport();
+    let reused = texture
+        .filter(|texture| (texture.width(), texture.height()) == (viewport.width, viewport.height));
+    let mut texture = reused.unwrap_or_else(|| {
+        DepthTexture2D::new::<f32>(
+            context,
+            viewport.width,
+            viewport.height,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        )
+    });
+
+    let material = DepthMaterial {
+        render_states: RenderStates { write_mask: WriteMask::DEPTH, ..Default::default() },
+        ..Default::default()
+    };
+    let frustum = camera.frustum();
+    let Ok(_) = texture.as_depth_target().clear(ClearState::default()).write::<Infallible>(|| {
+        for caster in casters.iter().filter(|caster| frustum.contains(caster.aabb())) {
+            render_with_material(context, camera, caster, &material, &[]);
+        }
+        Ok(())
+    });
+
+    texture
+}
+
+/// Maps world positions into the texture coordinates and depth of the shadow map.
+fn shadow_matrix(camera: &three_d::Camera) -> three_d::Mat4 {
+    let bias = Mat4::from_cols_array(&[
+        0.5, 0.0, 0.0, 0.0, 0.0, 0.5, 0.0, 0.0, 0.0, 0.0, 0.5, 0.0, 0.5, 0.5, 0.5, 1.0,
+    ]);
+    from_glam_mat4(bias) * camera.projection() * camera.view()
+}
@@ -0,0 +1,159 @@
+use crate::{
+    impl_component,
+    lights::{DirectionalLight, EnvironmentLight, PointLight, SceneLight, SpotLight},
+    scenes::{Component, GameObjectId, Scene},
+};
+use ciri_math::Transform;
+use std::{
+    any::Any,
+    fmt::Debug,
+    ops::Deref,
+    sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+/// A light a [LightSource] can place at the transform of its object.
+pub trait AttachableLight: SceneLight {
+    /// Moves the light to the world transform of its object. Lights shine along the
+    /// [forward](Transform::forward) direction of the object.
+    fn set_transform(&mut self, transform: &Transform);
+
+    /// A copy of the light for a clone of its [LightSource], `None` to share the light with the
+    /// clone instead.
+    fn clone_light(&self) -> Option<LightSource>;
+}
+
+impl AttachableLight for DirectionalLight {
+    fn set_transform(&mut self, transform: &Transform) {
+        self.direction = transform.forward();
+    }
+
+    fn clone_light(&self) -> Option<LightSource> {
+        Some(LightSource::new(self.clone()))
+    }
+}
+
+impl AttachableLight for PointLight {
+    fn set_transform(&mut self, transform: &Transform) {
+        self.position = transform.translation;
+    }
+
+    fn clone_light(&self) -> Option<LightSource> {
+        Some(LightSource::new(self.clone()))
+    }
+}
+
+impl AttachableLight for SpotLight {
+    fn set_transform(&mut self, transform: &Transform) {
+        self.position = transform.translation;
+        self.direction = transform.forward();
+    }
+
+    fn clone_light(&self) -> Option<LightSource> {
+        Some(LightSource::new(self.clone()))
+    }
+}
+
+/// Ambient light lights everything the same, so it ignores the transform and is shared between
+/// clones.
+impl AttachableLight for three_d::AmbientLight {
+    fn set_transform(&mut self, _: &Transform) {}
+
+    fn clone_light(&self) -> Option<LightSource> {
+        None
+    }
+}
+
+/// Lights the scene with an [AttachableLight] at the transform of its object, while the object
+/// is active.
+///
+/// Clones of the component get a [copy](AttachableLight::clone_light) of the light, so each one
+/// follows its own object.
+pub struct LightSource {
+    pub light: Arc<RwLock<dyn AttachableLight>>,
+    /// Whether the light shines while its object is active.
+    pub enabled: bool,
+}
+
+impl LightSource {
+    pub fn new(light: impl AttachableLight) -> Self {
+        Self { light: Arc::new(RwLock::new(light)), enabled: true }
+    }
+
+    /// A light source which doesn't shine until it is enabled.
+    #[must_use]
+    pub fn disabled(mut self) -> Self {
+        self.enabled = false;
+        self
+    }
+
+    pub fn get(&self) -> RwLockReadGuard<'_, dyn AttachableLight + 'static> {
+        self.light.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn get_mut(&self) -> RwLockWriteGuard<'_, dyn AttachableLight + 'static> {
+        self.light.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Clone for LightSource {
+    fn clone(&self) -> Self {
+        let light =
+            self.get().clone_light().map_or_else(|| Arc::clone(&self.light), |source| source.light);
+        Self { light, enabled: self.enabled }
+    }
+}
+
+impl_component!(LightSource);
+
+/// A light of a scene, added with [Scene::add_light], from a [LightSource] or from the
+/// [environment](Scene::environment).
+pub enum SceneLightRef<'a> {
+    Added(&'a dyn SceneLight),
+    Source(RwLockReadGuard<'a, dyn AttachableLight>),
+    Environment(EnvironmentLight),
+}
+
+impl Deref for SceneLightRef<'_> {
+    type Target = dyn SceneLight;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Added(light) => *light,
+            Self::Source(light) => &**light,
+            Self::Environment(light) => light,
+        }
+    }
+}
+
+impl Scene {
+    /// Moves the light of every [LightSource] to the transform of its object. Called at the end
+    /// of [Scene::update], call it again after moving objects outside of the update.
+    pub fn sync_lights(&self) {
+        for object in self.objects.values().filter(|object| object.is_active()) {
+            if let Some(source) = object.get_component::<LightSource>() {
+                source.get_mut().set_transform(&object.transform);
+            }
+        }
+    }
+
+    /// Enabled [light sources](LightSource) of active objects.
+    pub fn light_sources(&self) -> impl Iterator<Item = (GameObjectId, &LightSource)> + '_ {
+        self.objects.iter().filter_map(|(id, object)| {
+            let source = object.get_component::<LightSource>()?;
+            (object.is_active() && source.enabled).then_some((*id, source))
+        })
+    }
+
+    /// Every light shining in the scene, the added ones first.
+    pub(crate) fn all_lights(&self) -> Vec<SceneLightRef<'_>> {
+        self.lights
+            .iter()
+            .map(|light| SceneLightRef::Added(&**light))
+            .chain(self.light_sources().map(|(_, source)| SceneLightRef::Source(source.get())))
+            .chain(
+                self.environment()
+                    .map(|environment| SceneLightRef::Environment(environment.light())),
+            )
+            .collect()
+    }
+}
@@ -0,0 +1,321 @@
+use crate::lights::{
+    WithoutShadows, range_attenuation,
+    shadows::{ShadowMap, ShadowSettings, render_spot_shadow_map},
+};
+use ciri_math::{Vec3, from_glam_vec};
+use std::{
+    fmt::Write,
+    sync::{PoisonError, RwLock, RwLockReadGuard},
+};
+use three_d::{Attenuation, Context, Geometry, Light, LightId, Program, vec2, vec3};
+use three_d_asset::Srgba;
+
+pub struct SpotLightBuilder {
+    /// The intensity of the light. This allows for higher intensity than 1 which can be used to simulate high-intensity light sources like the sun.
+    pub intensity: f32,
+    /// The base color of the light.
+    pub color: Srgba,
+    /// Where the light shines from.
+    pub position: Vec3,
+    /// The direction the light shines.
+    pub direction: Vec3,
+    /// How the light fades with the distance.
+    pub attenuation: Attenuation,
+    /// Angle in degrees from the direction where the light starts to fade out.
+    pub inner_angle: f32,
+    /// Angle in degrees from the direction where the light has faded out.
+    pub outer_angle: f32,
+    pub shadows: Option<ShadowSettings>,
+}
+
+impl SpotLightBuilder {
+    #[must_use]
+    pub fn intensity(mut self, intensity: f32) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    #[must_use]
+    pub fn color(mut self, color: Srgba) -> Self {
+        self.color = color;
+        self
+    }
+
+    #[must_use]
+    pub fn position(mut self, position: Vec3) -> Self {
+        self.position = position;
+        self
+    }
+
+    #[must_use]
+    pub fn direction(mut self, direction: Vec3) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    #[must_use]
+    pub fn attenuation(mut self, attenuation: Attenuation) -> Self {
+        self.attenuation = attenuation;
+        self
+    }
+
+    /// Fades the light out over `range` world units, see [range_attenuation].
+    #[must_use]
+    pub fn range(mut self, range: f32) -> Self {
+        self.attenuation = range_attenuation(range);
+        self
+    }
+
+    /// Lights the cone up to `inner` degrees from the direction fully, then fades out until
+    /// `outer` degrees.
+    #[must_use]
+    pub fn cone(mut self, inner: f32, outer: f32) -> Self {
+        self.inner_angle = inner.min(outer);
+        self.outer_angle = outer;
+        self
+    }
+
+    /// Casts shadows with a shadow map of `resolution` texels in width and height.
+    #[must_use]
+    pub fn shadows(mut self, resolution: u32) -> Self {
+        self.shadows.get_or_insert_default().resolution = resolution;
+        self
+    }
+
+    /// Casts shadows with the given bias, see [ShadowSettings::bias].
+    #[must_use]
+    pub fn shadow_bias(mut self, bias: f32, slope_bias: f32) -> Self {
+        let shadows = self.shadows.get_or_insert_default();
+        shadows.bias = bias;
+        shadows.slope_bias = slope_bias;
+        self
+    }
+
+    pub fn build(&self, context: &Context) -> SpotLight {
+        SpotLight {
+            context: context.clone(),
+            intensity: self.intensity,
+            color: self.color,
+            position: self.position,
+            direction: self.direction,
+            attenuation: self.attenuation,
+            inner_angle: self.inner_angle,
+            outer_angle: self.outer_angle,
+            shadows: self.shadows,
+            shadow_map: RwLock::default(),
+        }
+    }
+}
+
+impl Default for SpotLightBuilder {
+    fn default() -> Self {
+        Self {
+            intensity: 1.0,
+            color: Srgba::WHITE,
+            position: Vec3::ZERO,
+            direction: Vec3::new(0.0, -1.0, 0.0),
+            attenuation: Attenuation::default(),
+            inner_angle: 35.0,
+            outer_angle: 45.0,
+            shadows: None,
+        }
+    }
+}
+
+/// A light shining from a position in a cone, like a flashlight or a street lamp, which can cast
+/// shadows.
+pub struct SpotLight {
+    context: Context,
+    pub intensity: f32,
+    pub color: Srgba,
+    pub position: Vec3,
+    pub direction: Vec3,
+    pub attenuation: Attenuation,
+    pub inner_angle: f32,
+    pub outer_angle: f32,
+    /// How the light casts shadows, `None` if it doesn't. Cascades don't apply to spot lights.
+    pub shadows: Option<ShadowSettings>,
+    shadow_map: RwLock<Option<ShadowMap>>,
+}
+
+impl SpotLight {
+    pub fn builder() -> SpotLightBuilder {
+        SpotLightBuilder::default()
+    }
+
+    fn shadow_map(&self) -> RwLockReadGuard<'_, Option<ShadowMap>> {
+        self.shadow_map.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Renders the shadow map of the light again, with `casters` casting the shadows. Scenes
+    /// call this before rendering each camera.
+    pub fn update_shadows(&self, casters: &[&dyn Geometry]) {
+        let Some(settings) = &self.shadows else {
+            return;
+        };
+
+        let previous = self.shadow_map.write().unwrap_or_else(PoisonError::into_inner).take();
+        let map = render_spot_shadow_map(
+            &self.context,
+            settings,
+            self.position,
+            self.direction,
+            self.outer_angle.to_radians(),
+            casters,
+            previous.map(|map| map.texture),
+        );
+        *self.shadow_map.write().unwrap_or_else(PoisonError::into_inner) = map;
+    }
+
+    /// Removes the shadow map until it is updated again.
+    pub fn clear_shadows(&self) {
+        *self.shadow_map.write().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+
+    pub fn has_shadow_map(&self) -> bool {
+        self.shadow_map().is_some()
+    }
+
+    /// The light without its shadows, for objects which don't receive shadows.
+    pub fn without_shadows(&self) -> WithoutShadows<'_, Self> {
+        WithoutShadows(self)
+    }
+
+    fn shader_source(i: u32, shadows: bool) -> String {
+        let mut source = format!(
+            "
+            uniform vec3 color{i};
+            uniform vec3 attenuation{i};
+            uniform vec3 position{i};
+            uniform vec3 direction{i};
+            uniform vec2 cone{i};
+            "
+        );
+
+        let mut shadow = String::new();
+        if shadows {
+            let _ = write!(
+                source,
+                "
+                uniform sampler2D shadowMap{i};
+                uniform mat4 shadowMatrix{i};
+                uniform vec2 shadowBias{i};
+
+                float shadow{i}(vec3 position, vec3 normal, vec3 light_direction)
+                {{
+                    float slope = 1.0 - clamp(dot(normal, light_direction), 0.0, 1.0);
+                    vec3 biased = position + light_direction * (shadowBias{i}.x + shadowBias{i}.y * slope);
+                    vec4 coordinates = shadowMatrix{i} * vec4(biased, 1.0);
+                    vec3 uvz = coordinates.xyz / coordinates.w;
+                    if (coordinates.w <= 0.0 || uvz.x < 0.0 || uvz.x > 1.0 || uvz.y < 0.0 || uvz.y > 1.0 || uvz.z > 1.0) {{
+                        return 1.0;
+                    }}
+
+                    vec2 texel = 1.0 / vec2(textureSize(shadowMap{i}, 0));
+                    float visibility = 0.0;
+                    for (int x = -1; x <= 1; x++) {{
+                        for (int y = -1; y <= 1; y++) {{
+                            visibility += texture(shadowMap{i}, uvz.xy + vec2(x, y) * texel).x < uvz.z ? 0.0 : 1.0;
+                        }}
+                    }}
+                    return visibility / 9.0;
+                }}
+                "
+            );
+            shadow = format!(" * shadow{i}(position, normal, light_direction)");
+        }
+
+        let _ = write!(
+            source,
+            "
+            vec3 calculate_lighting{i}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
+            {{
+                vec3 light_direction = position{i} - position;
+                float distance = length(light_direction);
+                light_direction = light_direction / distance;
+
+                float spot = smoothstep(cone{i}.x, cone{i}.y, dot(-light_direction, direction{i}));
+                if (spot <= 0.0) {{
+                    return vec3(0.0);
+                }}
+
+                vec3 light_color = attenuate(color{i}, attenuation{i}, distance) * spot;
+                return calculate_light(light_color, light_direction, surface_color, view_direction, normal, metallic, roughness){shadow};
+            }}
+            "
+        );
+        source
+    }
+
+    fn use_uniforms(&self, program: &Program, i: u32, shadow_map: Option<&ShadowMap>) {
+        program.use_uniform(
+            &format!("color{i}"),
+            self.color.to_linear_srgb().truncate() * self.intensity,
+        );
+        program.use_uniform(
+            &format!("attenuation{i}"),
+            vec3(self.attenuation.constant, self.attenuation.linear, self.attenuation.quadratic),
+        );
+        program.use_uniform(&format!("position{i}"), from_glam_vec(self.position));
+        program.use_uniform(&format!("direction{i}"), from_glam_vec(self.direction.normalize()));
+        program.use_uniform(
+            &format!("cone{i}"),
+            vec2(self.outer_angle.to_radians().cos(), self.inner_angle.to_radians().cos()),
+        );
+
+        if let Some(map) = shadow_map {
+            program.use_depth_texture(&format!("shadowMap{i}"), &map.texture);
+            program.use_uniform(&format!("shadowMatrix{i}"), map.matrix);
+            program.use_uniform(&format!("shadowBias{i}"), vec2(map.bias[0], map.bias[1]));
+        }
+    }
+}
+
+/// Light ids of spot lights, plus one with a shadow map.
+const LIGHT_ID: u8 = 0x50;
+
+/// The clone has no shadow map until its shadows are updated.
+impl Clone for SpotLight {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+            intensity: self.intensity,
+            color: self.color,
+            position: self.position,
+            direction: self.direction,
+            attenuation: self.attenuation,
+            inner_angle: self.inner_angle,
+            outer_angle: self.outer_angle,
+            shadows: self.shadows,
+            shadow_map: RwLock::default(),
+        }
+    }
+}
+
+impl Light for SpotLight {
+    fn shader_source(&self, i: u32) -> String {
+        Self::shader_source(i, self.has_shadow_map())
+    }
+
+    fn use_uniforms(&self, program: &Program, i: u32) {
+        self.use_uniforms(program, i, self.shadow_map().as_ref());
+    }
+
+    fn id(&self) -> LightId {
+        LightId(LIGHT_ID + u8::from(self.has_shadow_map()))
+    }
+}
+
+impl Light for WithoutShadows<'_, SpotLight> {
+    fn shader_source(&self, i: u32) -> String {
+        SpotLight::shader_source(i, false)
+    }
+
+    fn use_uniforms(&self, program: &Program, i: u32) {
+        self.0.use_uniforms(program, i, None);
+    }
+
+    fn id(&self) -> LightId {
+        LightId(LIGHT_ID)
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{engine::Engine, scenes::Scene};
+use std::{
+    any::{Any, TypeId, type_name},
+    cell::RefCell,
+    collections::HashMap,
+    fmt::Debug,
+};
+
+type Resource = Box<dyn Any + Send + Sync>;
+
+/// Type-keyed map of singleton values, such as the score or the game settings.
+#[derive(Default)]
+pub struct Resources {
+    values: HashMap<TypeId, (&'static str, Resource)>,
+}
+
+impl Debug for Resources {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.values.values().map(|(name, _)| name)).finish()
+    }
+}
+
+impl Resources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts the resource, returning the previous value of the same type.
+    pub fn insert<T: Any + Send + Sync>(&mut self, value: T) -> Option<T> {
+        let previous = self.values.insert(TypeId::of::<T>(), (type_name::<T>(), Box::new(value)));
+        previous.and_then(|(_, previous)| previous.downcast().ok()).map(|previous| *previous)
+    }
+
+    pub fn get<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.values.get(&TypeId::of::<T>())?.1.downcast_ref()
+    }
+
+    pub fn get_mut<T: Any + Send + Sync>(&mut self) -> Option<&mut T> {
+        self.values.get_mut(&TypeId::of::<T>())?.1.downcast_mut()
+    }
+
+    pub fn remove<T: Any + Send + Sync>(&mut self) -> Option<T> {
+        let (_, value) = self.values.remove(&TypeId::of::<T>())?;
+        value.downcast().ok().map(|value| *value)
+    }
+
+    pub fn contains<T: Any + Send + Sync>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Wraps every resource in a [RefCell], so systems can borrow different resources at once.
+    pub(crate) fn into_cells(self) -> ResourceCells {
+        ResourceCells {
+            values: self
+                .values
+                .into_iter()
+                .map(|(type_id, (name, value))| (type_id, (name, RefCell::new(value))))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct ResourceCells {
+    values: HashMap<TypeId, (&'static str, RefCell<Resource>)>,
+}
+
+impl ResourceCells {
+    pub(crate) fn get<T: Any>(&self) -> Option<&RefCell<Resource>> {
+        self.values.get(&TypeId::of::<T>()).map(|(_, cell)| cell)
+    }
+
+    pub(crate) fn into_resources(self) -> Resources {
+        Resources {
+            values: self
+                .values
+                .into_iter()
+                .map(|(type_id, (name, cell))| (type_id, (name, cell.into_inner())))
+                .collect(),
+        }
+    }
+}
+
+impl Scene {
+    /// Inserts a resource owned by this scene, returning the previous value of the same type.
+    pub fn insert_resource<T: Any + Send + Sync>(&mut self, value: T) -> Option<T> {
+        self.resources.insert(value)
+    }
+
+    /// Gets a resource of the scene, falling back to the engine's resources while the [Engine]
+    /// updates, sets up or reloads the assets of the scene.
+    pub fn resource<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.resources.get().or_else(|| self.engine_resources.get())
+    }
+
+    /// Mutable counterpart of [Scene::resource].
+    pub fn resource_mut<T: Any + Send + Sync>(&mut self) -> Option<&mut T> {
+        if self.resources.contains::<T>() {
+            self.resources.get_mut()
+        } else {
+            self.engine_resources.get_mut()
+        }
+    }
+
+    pub fn remove_resource<T: Any + Send + Sync>(&mut self) -> Option<T> {
+        self.resources.remove()
+    }
+
+    pub fn resources(&self) -> &Resources {
+        &self.resources
+    }
+
+    pub fn resources_mut(&mut self) -> &mut Resources {
+        &mut self.resources
+    }
+}
+
+impl Engine {
+    /// Inserts a resource shared by all scenes, returning the previous value of the same type.
+    pub fn insert_resource<T: Any + Send + Sync>(&mut self, value: T) -> Option<T> {
+        self.scenes.resources.insert(value)
+    }
+
+    pub fn resource<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.scenes.resources.get()
+    }
+
+    pub fn resource_mut<T: Any + Send + Sync>(&mut self) -> Option<&mut T> {
+        self.scenes.resources.get_mut()
+    }
+
+    pub fn remove_resource<T: Any + Send + Sync>(&mut self) -> Option<T> {
+        self.scenes.resources.remove()
+    }
+}
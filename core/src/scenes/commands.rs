@@ -0,0 +1,76 @@
+use crate::scenes::{GameObject, GameObjectId, PoolId, Scene};
+use ciri_math::Transform;
+use std::fmt::Debug;
+
+type Command = Box<dyn FnOnce(&mut Scene) + Send>;
+
+/// Buffer of structural scene changes that are applied once the current update is finished.
+#[derive(Default)]
+pub struct Commands {
+    queue: Vec<Command>,
+}
+
+impl Debug for Commands {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Commands").field("len", &self.queue.len()).finish()
+    }
+}
+
+impl Commands {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the object to the scene.
+    pub fn spawn(&mut self, object: GameObject) {
+        self.add(move |scene| {
+            scene.add_object(object);
+        });
+    }
+
+    /// Removes the object from the scene.
+    pub fn despawn(&mut self, id: GameObjectId) {
+        self.add(move |scene| {
+            scene.remove_object(id);
+        });
+    }
+
+    /// Spawns an object of the pool, see [Scene::spawn_pooled].
+    pub fn spawn_pooled(&mut self, pool: PoolId, transform: Transform) {
+        self.add(move |scene| {
+            scene.spawn_pooled(pool, transform);
+        });
+    }
+
+    /// Returns the object to its pool, see [Scene::release_pooled].
+    pub fn release_pooled(&mut self, id: GameObjectId) {
+        self.add(move |scene| {
+            scene.release_pooled(id);
+        });
+    }
+
+    /// Queues an arbitrary change to the scene.
+    pub fn add(&mut self, command: impl FnOnce(&mut Scene) + Send + 'static) {
+        self.queue.push(Box::new(command));
+    }
+
+    /// Moves all commands of `other` to the end of this buffer.
+    pub fn append(&mut self, other: &mut Commands) {
+        self.queue.append(&mut other.queue);
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Applies the queued commands in the order they were added.
+    pub fn apply(self, scene: &mut Scene) {
+        for command in self.queue {
+            command(scene);
+        }
+    }
+}
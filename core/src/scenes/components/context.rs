@@ -0,0 +1,298 @@
+use crate::{
+    camera::Camera,
+    frame::Frame,
+    resources::Resources,
+    scenes::{
+        Commands, GameObject, GameObjectId, Scene, SpatialIndex, Time,
+        components::{Component, ComponentError, ComponentRequirements, ComponentType},
+        scheduler::{Coroutine, TaskId},
+    },
+};
+use ciri_math::Transform;
+use std::{any::Any, collections::HashMap};
+use three_d::Event;
+
+/// Stands in for the component being updated, so the object still reports it as present and
+/// requirement checks keep working while the component itself is borrowed.
+#[derive(Debug, Clone)]
+pub struct Detached {
+    pub(crate) component: ComponentType,
+    pub(crate) requirements: ComponentRequirements,
+}
+
+impl Component for Detached {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_component(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+
+    fn get_requirements(&self) -> ComponentRequirements {
+        self.requirements.clone()
+    }
+
+    fn component_name(&self) -> &'static str {
+        self.component.name
+    }
+}
+
+/// Everything an [Updateable](crate::scenes::components::Updateable) component can access during
+/// its update.
+///
+/// The component being updated is taken out of its object for the duration of the call, so the
+/// object and its other components can be borrowed mutably without aliasing the component itself.
+/// The object still reports the component as present, but [UpdateContext::get_component] returns
+/// `None` for its type. Likewise, the object being updated is not part of [UpdateContext::objects].
+///
+/// During parallel updates other objects are being updated at the same time, so
+/// [UpdateContext::objects] is empty, resources are read-only and changes to the rest of the scene
+/// go through the [UpdateContext::commands].
+pub struct UpdateContext<'a> {
+    pub(crate) game_object: &'a mut GameObject,
+    pub(crate) current: ComponentType,
+    pub(crate) objects: &'a HashMap<GameObjectId, GameObject>,
+    pub(crate) frame: Option<&'a Frame>,
+    pub(crate) time: &'a Time,
+    pub(crate) camera: Option<&'a Camera>,
+    pub(crate) spatial: &'a SpatialIndex,
+    pub(crate) commands: &'a mut Commands,
+    pub(crate) resources: ResourceAccess<'a>,
+}
+
+/// Resources of the scene and the engine, shared read-only between parallel updates.
+pub enum ResourceAccess<'a> {
+    Exclusive { scene: &'a mut Resources, engine: &'a mut Resources },
+    Shared { scene: &'a Resources, engine: &'a Resources },
+}
+
+impl ResourceAccess<'_> {
+    pub(crate) fn reborrow(&mut self) -> ResourceAccess<'_> {
+        match self {
+            Self::Exclusive { scene, engine } => ResourceAccess::Exclusive { scene, engine },
+            Self::Shared { scene, engine } => ResourceAccess::Shared { scene, engine },
+        }
+    }
+}
+
+impl<'a> UpdateContext<'a> {
+    /// Id of the object being updated.
+    pub fn id(&self) -> Option<GameObjectId> {
+        self.game_object.id
+    }
+
+    /// The object being updated. The component being updated is missing from clones of it.
+    pub fn game_object(&self) -> &GameObject {
+        self.game_object
+    }
+
+    pub fn transform(&self) -> &Transform {
+        &self.game_object.transform
+    }
+
+    pub fn transform_mut(&mut self) -> &mut Transform {
+        &mut self.game_object.transform
+    }
+
+    /// Gets another component of the object being updated.
+    ///
+    /// Returns `None` for the type of the component being updated.
+    pub fn get_component<T: Component + 'static>(&self) -> Option<&T> {
+        self.game_object.get_component::<T>()
+    }
+
+    /// Mutably gets another component of the object being updated.
+    ///
+    /// Returns `None` for the type of the component being updated.
+    pub fn get_component_mut<T: Component + 'static>(&mut self) -> Option<&mut T> {
+        self.game_object.get_component_mut::<T>()
+    }
+
+    pub fn has_component<T: Component + 'static>(&self) -> bool {
+        self.game_object.has_component::<T>()
+    }
+
+    /// Adds a component to the object being updated, see [GameObject::add_component].
+    ///
+    /// Adding the type of the component being updated replaces it once its update returns.
+    pub fn add_component<T: Component + 'static>(&mut self, component: T) {
+        self.game_object.add_component(component);
+    }
+
+    /// See [GameObject::try_add_component].
+    pub fn try_add_component<T: Component + 'static>(
+        &mut self,
+        component: T,
+    ) -> Result<(), ComponentError> {
+        self.game_object.try_add_component(component).map(|_| ())
+    }
+
+    /// Removes a component from the object being updated, see [GameObject::remove_component].
+    ///
+    /// Removing the type of the component being updated drops it once its update returns.
+    pub fn remove_component<T: Component + 'static>(&mut self) {
+        self.game_object.remove_component::<T>();
+    }
+
+    /// See [GameObject::try_remove_component].
+    pub fn try_remove_component<T: Component + 'static>(&mut self) -> Result<bool, ComponentError> {
+        self.game_object.try_remove_component::<T>()
+    }
+
+    /// Removes the component being updated once its update returns.
+    ///
+    /// # Panics
+    ///
+    /// Panics if another component of the object requires it.
+    pub fn remove_self(&mut self) {
+        if let Err(err) = self.game_object.check_removal(self.current) {
+            panic!("{err}");
+        }
+
+        self.game_object.components.remove(&self.current.id);
+    }
+
+    pub fn enable(&mut self) {
+        self.game_object.enable();
+    }
+
+    pub fn disable(&mut self) {
+        self.game_object.disable();
+    }
+
+    /// Gets another object of the scene.
+    pub fn get_object(&self, id: GameObjectId) -> Option<&'a GameObject> {
+        self.objects.get(&id)
+    }
+
+    /// Iterates over all other objects of the scene.
+    pub fn objects(&self) -> impl Iterator<Item = (&'a GameObjectId, &'a GameObject)> + 'a {
+        self.objects.iter()
+    }
+
+    /// The frame currently being processed, if the scene is updated from the render loop.
+    pub fn frame(&self) -> Option<&'a Frame> {
+        self.frame
+    }
+
+    /// Input events of the current frame.
+    pub fn events(&self) -> &'a [Event] {
+        self.frame.map_or(&[], |frame| &frame.input.events)
+    }
+
+    pub fn time(&self) -> &'a Time {
+        self.time
+    }
+
+    pub fn delta_time(&self) -> f32 {
+        self.time.delta()
+    }
+
+    pub fn camera(&self) -> Option<&'a Camera> {
+        self.camera
+    }
+
+    /// Spatial index of the scene, as of the end of the previous update.
+    pub fn spatial(&self) -> &'a SpatialIndex {
+        self.spatial
+    }
+
+    /// Structural changes to the scene, applied after all components are updated.
+    pub fn commands(&mut self) -> &mut Commands {
+        self.commands
+    }
+
+    /// Calls `callback` once after `seconds`, cancelled if the object is removed first.
+    ///
+    /// The timer is scheduled together with the [UpdateContext::commands].
+    pub fn after(
+        &mut self,
+        seconds: f32,
+        callback: impl FnOnce(&mut Scene) + Send + Sync + 'static,
+    ) -> TaskId {
+        let owner = self.id();
+        let id = TaskId::next();
+        self.commands.add(move |scene| scene.schedule_once(id, owner, seconds, callback));
+        id
+    }
+
+    /// Calls `callback` every `seconds` while the object is part of the scene.
+    ///
+    /// The timer is scheduled together with the [UpdateContext::commands].
+    pub fn every(
+        &mut self,
+        seconds: f32,
+        callback: impl FnMut(&mut Scene) + Send + Sync + 'static,
+    ) -> TaskId {
+        let owner = self.id();
+        let id = TaskId::next();
+        self.commands.add(move |scene| scene.schedule_repeating(id, owner, seconds, callback));
+        id
+    }
+
+    /// Starts a coroutine owned by the object, see [Scene::start_coroutine].
+    pub fn start_coroutine<Fut: Future<Output = ()> + Send + 'static>(
+        &mut self,
+        coroutine: impl FnOnce(Coroutine) -> Fut + Send + 'static,
+    ) -> TaskId {
+        let owner = self.id();
+        let id = TaskId::next();
+        self.commands.add(move |scene| scene.schedule_coroutine(id, owner, coroutine));
+        id
+    }
+
+    /// Cancels a timer or coroutine together with the [UpdateContext::commands].
+    pub fn cancel_task(&mut self, id: TaskId) {
+        self.commands.add(move |scene| {
+            scene.cancel_task(id);
+        });
+    }
+
+    /// Whether the component is updated in the parallel stage, see
+    /// [Updateable::parallel_safe](crate::scenes::components::Updateable::parallel_safe).
+    pub fn is_parallel(&self) -> bool {
+        matches!(self.resources, ResourceAccess::Shared { .. })
+    }
+
+    /// Gets a resource of the scene, falling back to the engine's resources.
+    pub fn resource<T: Any + Send + Sync>(&self) -> Option<&T> {
+        let (scene, engine) = match &self.resources {
+            ResourceAccess::Exclusive { scene, engine } => (&**scene, &**engine),
+            ResourceAccess::Shared { scene, engine } => (*scene, *engine),
+        };
+
+        scene.get().or_else(|| engine.get())
+    }
+
+    /// Mutable counterpart of [UpdateContext::resource].
+    ///
+    /// Always `None` during parallel updates, where resources are shared between threads.
+    pub fn resource_mut<T: Any + Send + Sync>(&mut self) -> Option<&mut T> {
+        let ResourceAccess::Exclusive { scene, engine } = &mut self.resources else {
+            return None;
+        };
+
+        if scene.contains::<T>() { scene.get_mut() } else { engine.get_mut() }
+    }
+
+    /// Inserts a resource owned by the scene, returning the previous value of the same type.
+    ///
+    /// During parallel updates the insertion is deferred to the scene commands and `None` is
+    /// returned.
+    pub fn insert_resource<T: Any + Send + Sync>(&mut self, value: T) -> Option<T> {
+        match &mut self.resources {
+            ResourceAccess::Exclusive { scene, .. } => scene.insert(value),
+            ResourceAccess::Shared { .. } => {
+                self.commands.add(move |scene| {
+                    scene.insert_resource(value);
+                });
+                None
+            }
+        }
+    }
+}
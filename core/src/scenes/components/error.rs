@@ -0,0 +1,36 @@
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+};
+
+/// Error returned when adding or removing a component would break the requirements of the object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComponentError {
+    /// `component` requires `required`, which the object does not have.
+    MissingRequired { component: &'static str, required: &'static str },
+    /// `component` cannot be added to an object that has `conflicting`.
+    Conflict { component: &'static str, conflicting: &'static str },
+    /// `component` cannot be removed, because `required_by` depends on it.
+    RequiredBy { component: &'static str, required_by: &'static str },
+}
+
+impl Display for ComponentError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingRequired { component, required } => {
+                write!(f, "component `{component}` requires missing component `{required}`")
+            }
+            Self::Conflict { component, conflicting } => {
+                write!(
+                    f,
+                    "component `{component}` conflicts with existing component `{conflicting}`"
+                )
+            }
+            Self::RequiredBy { component, required_by } => {
+                write!(f, "component `{component}` is required by component `{required_by}`")
+            }
+        }
+    }
+}
+
+impl Error for ComponentError {}
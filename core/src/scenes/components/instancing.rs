@@ -0,0 +1,253 @@
+use crate::{
+    assets::Handle,
+    camera::Camera,
+    impl_component,
+    scenes::{Any, Component, GameObject, GameObjectId, Renderer, Scene},
+};
+use ciri_math::{Aabb, Transform, from_glam_mat4, to_glam_vec};
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+use three_d::{Context, CpuMaterial, CpuMesh, Gm, InstancedMesh, Instances, PhysicalMaterial};
+
+/// Draws a mesh with a material at the transform of its object.
+///
+/// Every active object drawing the same mesh and material handles is drawn in a single instanced
+/// draw call, so share the handles of repeated meshes, like the trees of a forest. Batches are
+/// culled as a whole, and cast and receive shadows. Raycasts and the spatial index use the bounds
+/// of the mesh.
+#[derive(Clone)]
+pub struct MeshRenderer {
+    pub mesh: Handle<CpuMesh>,
+    pub material: Handle<CpuMaterial>,
+    bounds: Arc<Mutex<MeshBounds>>,
+}
+
+/// Bounds of a mesh and the loaded mesh they were computed from, shared by clones.
+type MeshBounds = Option<(Arc<CpuMesh>, Option<Aabb>)>;
+
+impl MeshRenderer {
+    pub fn new(mesh: Handle<CpuMesh>, material: Handle<CpuMaterial>) -> Self {
+        Self { mesh, material, bounds: Arc::default() }
+    }
+
+    /// Bounds of the mesh in object space, `None` until it is loaded or if it has no positions.
+    pub fn bounds(&self) -> Option<Aabb> {
+        let mesh = self.mesh.get()?;
+        let mut bounds = self.bounds.lock().unwrap_or_else(PoisonError::into_inner);
+        match &*bounds {
+            Some((computed, aabb)) if Arc::ptr_eq(computed, &mesh) => *aabb,
+            _ => {
+                let aabb = mesh.compute_aabb();
+                let aabb = (!aabb.is_empty())
+                    .then(|| Aabb::new(to_glam_vec(aabb.min()), to_glam_vec(aabb.max())));
+                *bounds = Some((mesh, aabb));
+                aabb
+            }
+        }
+    }
+
+    fn batches_with(&self, batch: &MeshBatch) -> bool {
+        self.mesh.ptr_eq(&batch.mesh) && self.material.ptr_eq(&batch.material)
+    }
+}
+
+impl_component!(MeshRenderer, conflicts(Renderer, InstancedRenderer));
+
+/// Objects with a [MeshRenderer] drawing the same mesh and material.
+#[derive(Debug, Clone)]
+pub struct MeshBatch {
+    pub mesh: Handle<CpuMesh>,
+    pub material: Handle<CpuMaterial>,
+    /// Objects in the batch, by id.
+    pub objects: Vec<GameObjectId>,
+}
+
+type InstancedObject = Gm<InstancedMesh, PhysicalMaterial>;
+
+/// Draws many instances of a mesh with a material, at transforms relative to its object, in a
+/// single draw call.
+///
+/// The instances are drawn by a [Renderer] of their own, which culls them as a whole. Clones of
+/// the component draw the same instances.
+#[derive(Clone)]
+pub struct InstancedRenderer {
+    renderer: Renderer,
+    object: Arc<RwLock<InstancedObject>>,
+}
+
+impl InstancedRenderer {
+    pub fn new(
+        context: &Context,
+        mesh: &CpuMesh,
+        material: &CpuMaterial,
+        instances: &[Transform],
+    ) -> Self {
+        let object = Arc::new(RwLock::new(Gm::new(
+            InstancedMesh::new(context, &to_instances(instances), mesh),
+            PhysicalMaterial::new(context, material),
+        )));
+        Self { renderer: Renderer::shared(Arc::<RwLock<InstancedObject>>::clone(&object)), object }
+    }
+
+    /// The renderer drawing the instances, with its culling, shadow and sort settings.
+    pub fn renderer(&self) -> &Renderer {
+        &self.renderer
+    }
+
+    pub fn renderer_mut(&mut self) -> &mut Renderer {
+        &mut self.renderer
+    }
+
+    /// Replaces the instances, each transform being relative to the object.
+    pub fn set_instances(&self, instances: &[Transform]) {
+        self.get_mut().set_instances(&to_instances(instances));
+    }
+
+    pub fn instance_count(&self) -> u32 {
+        self.get().instance_count()
+    }
+
+    pub fn get(&self) -> RwLockReadGuard<'_, InstancedObject> {
+        self.object.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn get_mut(&self) -> RwLockWriteGuard<'_, InstancedObject> {
+        self.object.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl_component!(InstancedRenderer, conflicts(Renderer));
+
+fn to_instances(transforms: &[Transform]) -> Instances {
+    Instances {
+        transformations: transforms
+            .iter()
+            .map(|transform| from_glam_mat4(transform.to_matrix()))
+            .collect(),
+        ..Default::default()
+    }
+}
+
+/// Instanced objects drawing the [mesh batches](MeshBatch) of a scene, kept between frames.
+#[derive(Default)]
+pub struct InstanceCache {
+    batches: Vec<CachedBatch>,
+}
+
+struct CachedBatch {
+    mesh: Handle<CpuMesh>,
+    material: Handle<CpuMaterial>,
+    /// The assets the object was created from, which change when the handles are reloaded.
+    assets: (Arc<CpuMesh>, Arc<CpuMaterial>),
+    renderer: Renderer,
+    object: Arc<RwLock<InstancedObject>>,
+    /// Number of objects in the batch.
+    count: usize,
+}
+
+impl InstanceCache {
+    /// Updates the instances of the batches, creating the instanced objects of new batches and
+    /// dropping the ones of batches which are gone. Batches with handles still loading are
+    /// skipped.
+    pub fn update(&mut self, context: &Context, scene: &Scene, batches: &[MeshBatch]) {
+        let mut previous = std::mem::take(&mut self.batches);
+
+        for batch in batches {
+            let (Some(mesh), Some(material)) = (batch.mesh.get(), batch.material.get()) else {
+                continue;
+            };
+
+            let cached = previous
+                .iter()
+                .position(|cached| {
+                    cached.mesh.ptr_eq(&batch.mesh)
+                        && cached.material.ptr_eq(&batch.material)
+                        && Arc::ptr_eq(&cached.assets.0, &mesh)
+                        && Arc::ptr_eq(&cached.assets.1, &material)
+                })
+                .map(|index| previous.swap_remove(index));
+
+            let transforms: Vec<_> = batch
+                .objects
+                .iter()
+                .filter_map(|id| scene.objects.get(id))
+                .map(|object| object.transform)
+                .collect();
+            let instances = to_instances(&transforms);
+
+            let cached = if let Some(cached) = cached {
+                cached
+                    .object
+                    .write()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .set_instances(&instances);
+                cached
+            } else {
+                let object = Arc::new(RwLock::new(Gm::new(
+                    InstancedMesh::new(context, &instances, &mesh),
+                    PhysicalMaterial::new(context, &material),
+                )));
+                CachedBatch {
+                    mesh: batch.mesh.clone(),
+                    material: batch.material.clone(),
+                    assets: (mesh, material),
+                    renderer: Renderer::shared(Arc::<RwLock<InstancedObject>>::clone(&object)),
+                    object,
+                    count: 0,
+                }
+            };
+
+            self.batches.push(CachedBatch { count: transforms.len(), ..cached });
+        }
+    }
+
+    /// Renderers of the batches with the number of objects they draw.
+    pub fn renderers(&self) -> impl Iterator<Item = (&Renderer, usize)> {
+        self.batches.iter().map(|batch| (&batch.renderer, batch.count))
+    }
+}
+
+impl Scene {
+    pub(crate) fn instancing(&self) -> MutexGuard<'_, InstanceCache> {
+        self.instancing.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Active objects with a [MeshRenderer], batched by their mesh and material.
+    pub fn mesh_batches(&self) -> Vec<MeshBatch> {
+        Self::batch(self.objects.iter().filter(|(_, object)| object.is_active()))
+    }
+
+    /// [Scene::mesh_batches] of the objects on the layers of the camera's culling mask.
+    pub fn mesh_batches_for(&self, camera: &Camera) -> Vec<MeshBatch> {
+        let mask = camera.culling_mask;
+        Self::batch(
+            self.objects
+                .iter()
+                .filter(|(_, object)| object.is_active() && object.layers().intersects(mask)),
+        )
+    }
+
+    fn batch<'a>(
+        objects: impl Iterator<Item = (&'a GameObjectId, &'a GameObject)>,
+    ) -> Vec<MeshBatch> {
+        let mut objects: Vec<_> = objects
+            .filter_map(|(id, object)| Some((*id, object.get_component::<MeshRenderer>()?)))
+            .collect();
+        objects.sort_by_key(|(id, _)| *id);
+
+        let mut batches: Vec<MeshBatch> = Vec::new();
+        for (id, renderer) in objects {
+            match batches.iter_mut().find(|batch| renderer.batches_with(batch)) {
+                Some(batch) => batch.objects.push(id),
+                None => batches.push(MeshBatch {
+                    mesh: renderer.mesh.clone(),
+                    material: renderer.material.clone(),
+                    objects: vec![id],
+                }),
+            }
+        }
+        batches
+    }
+}
@@ -1,19 +1,39 @@
+mod context;
+mod error;
+mod instancing;
 mod renderer;
 mod traits;
+
+pub(crate) use context::Detached;
+use context::ResourceAccess;
+pub use context::UpdateContext;
+pub use error::*;
+pub(crate) use instancing::InstanceCache;
+pub use instancing::{InstancedRenderer, MeshBatch, MeshRenderer};
 pub use traits::*;
 
-use crate::scenes::game_object::GameObject;
+use crate::{
+    camera::Camera,
+    scenes::{
+        Commands, SpatialIndex, Time,
+        game_object::{GameObject, GameObjectId},
+        systems::Stage,
+    },
+};
 pub use crate::{
     frame::Frame,
     scenes::{
         Scene,
-        components::traits::{ComponentRequirements, Updateable},
+        components::traits::{ComponentLifecycle, ComponentRequirements, Updateable},
     },
 };
+pub use ciri_derive::Component;
+use rayon::prelude::*;
 pub use renderer::*;
 use std::{
-    any::{Any, TypeId},
+    any::{Any, TypeId, type_name},
     cell::RefCell,
+    collections::HashMap,
     fmt::Debug,
     ptr::NonNull,
 };
@@ -28,29 +48,120 @@ pub trait Component: Any + Debug + Send + Sync {
         None
     }
 
+    fn as_lifecycle(&mut self) -> Option<&mut dyn ComponentLifecycle> {
+        None
+    }
+
     fn get_requirements(&self) -> ComponentRequirements {
         ComponentRequirements::default()
     }
+
+    /// Name of the component type, used in error messages.
+    fn component_name(&self) -> &'static str {
+        type_name::<Self>()
+    }
 }
 
 impl GameObject {
+    /// Adds the component, replacing an existing component of the same type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the component's requirements are not met, see [GameObject::try_add_component].
     pub fn add_component<T: Component + 'static>(&mut self, component: T) -> &mut Self {
+        if let Err(err) = self.try_add_component(component) {
+            panic!("{err}");
+        }
+
+        self
+    }
+
+    /// Adds the component, replacing an existing component of the same type.
+    ///
+    /// Missing requirements declared with [ComponentRequirements::require_default] are inserted
+    /// first, components requiring each other are all inserted. Nothing is added if any
+    /// requirement cannot be met.
+    pub fn try_add_component<T: Component + 'static>(
+        &mut self,
+        component: T,
+    ) -> Result<&mut Self, ComponentError> {
+        let added: Vec<TypeId> = Vec::new();
+
+        match self.insert_component(Box::new(component), added, &mut Vec::new()) {
+            Ok(added) => {
+                for type_id in added {
+                    if let Some(lifecycle) =
+                        self.components.get_mut(&type_id).and_then(|c| c.as_lifecycle())
+                    {
+                        lifecycle.on_add();
+                    }
+                }
+
+                Ok(self)
+            }
+            Err((err, added)) => {
+                for type_id in added {
+                    self.components.remove(&type_id);
+                }
+
+                Err(err)
+            }
+        }
+    }
+
+    /// Inserts the component and its default requirements, tracking every inserted type in `added`
+    /// so they can be rolled back on error.
+    ///
+    /// `pending` holds the types whose requirements are being inserted. They are inserted once
+    /// their requirements are, so requiring them again doesn't insert another default.
+    fn insert_component(
+        &mut self,
+        component: Box<dyn Component>,
+        mut added: Vec<TypeId>,
+        pending: &mut Vec<TypeId>,
+    ) -> Result<Vec<TypeId>, (ComponentError, Vec<TypeId>)> {
+        let type_id = component.as_any().type_id();
+        let name = component.component_name();
         let requirements = component.get_requirements();
 
-        for required in requirements.required {
-            if !self.has_component_by_id(required) {
-                panic!("missing required component");
+        for conflict in &requirements.conflicts {
+            if self.has_component_by_id(conflict.id) {
+                let err = ComponentError::Conflict { component: name, conflicting: conflict.name };
+                return Err((err, added));
             }
         }
 
-        for conflict in requirements.conflicts {
-            if self.has_component_by_id(conflict) {
-                panic!("conflicting component exists");
+        for existing in self.components.values() {
+            if existing.get_requirements().conflicts_with(type_id) {
+                let conflicting = existing.component_name();
+                return Err((ComponentError::Conflict { component: name, conflicting }, added));
             }
         }
 
-        self.components.insert(TypeId::of::<T>(), component.clone_component());
-        self
+        pending.push(type_id);
+        for requirement in &requirements.required {
+            let required = requirement.component.id;
+            if self.has_component_by_id(required) || pending.contains(&required) {
+                continue;
+            }
+
+            let Some(default) = requirement.default else {
+                let required = requirement.component.name;
+                return Err((ComponentError::MissingRequired { component: name, required }, added));
+            };
+
+            added = self.insert_component(default(), added, pending)?;
+        }
+        pending.pop();
+
+        if let Some(mut replaced) = self.components.insert(type_id, component)
+            && let Some(lifecycle) = replaced.as_lifecycle()
+        {
+            lifecycle.on_remove();
+        }
+
+        added.push(type_id);
+        Ok(added)
     }
 
     pub fn get_component<T: Component + 'static>(&self) -> Option<&T> {
@@ -67,9 +178,47 @@ impl GameObject {
         self.components.contains_key(&type_id)
     }
 
+    /// Removes the component.
+    ///
+    /// # Panics
+    ///
+    /// Panics if another component requires it, see [GameObject::try_remove_component].
     pub fn remove_component<T: Component + 'static>(&mut self) {
-        let type_id = TypeId::of::<T>();
-        self.components.remove(&type_id);
+        if let Err(err) = self.try_remove_component::<T>() {
+            panic!("{err}");
+        }
+    }
+
+    /// Removes the component, unless another component of the object requires it.
+    ///
+    /// Returns whether the component was present.
+    pub fn try_remove_component<T: Component + 'static>(&mut self) -> Result<bool, ComponentError> {
+        let removed = ComponentType::of::<T>();
+        self.check_removal(removed)?;
+
+        let Some(mut component) = self.components.remove(&removed.id) else {
+            return Ok(false);
+        };
+
+        if let Some(lifecycle) = component.as_lifecycle() {
+            lifecycle.on_remove();
+        }
+
+        Ok(true)
+    }
+
+    /// Checks that none of the components depend on `removed`.
+    pub(crate) fn check_removal(&self, removed: ComponentType) -> Result<(), ComponentError> {
+        for (type_id, component) in &self.components {
+            if *type_id != removed.id && component.get_requirements().requires(removed.id) {
+                return Err(ComponentError::RequiredBy {
+                    component: removed.name,
+                    required_by: component.component_name(),
+                });
+            }
+        }
+
+        Ok(())
     }
 
     pub fn has_component<T: Component + 'static>(&self) -> bool {
@@ -87,9 +236,53 @@ impl GameObject {
     }
 }
 
+/// Implements [Component] for a `Clone` type.
+///
+/// Optional flags after the type:
+/// - `updateable`: the type implements [Updateable] and is updated every frame.
+/// - `lifecycle`: the type implements [ComponentLifecycle] and is notified when added or removed.
+/// - `requires(A, B)`: `A` and `B` must be added to the object first.
+/// - `requires_default(A)`: `A` is inserted with its `Default` value when missing.
+/// - `conflicts(A)`: the component cannot be on the same object as `A`.
+///
+/// ```ignore
+/// impl_component!(Rotator, updateable, requires(Renderer));
+/// ```
 #[macro_export]
 macro_rules! impl_component {
-    ($type:ty) => {
+    (@method updateable) => {
+        fn as_updateable(&mut self) -> Option<&mut dyn Updateable> {
+            Some(self)
+        }
+    };
+
+    (@method lifecycle) => {
+        fn as_lifecycle(
+            &mut self,
+        ) -> Option<&mut dyn $crate::scenes::components::ComponentLifecycle> {
+            Some(self)
+        }
+    };
+
+    (@method $flag:ident) => {};
+
+    (@requirements $requirements:ident, requires($($required:ty),* $(,)?)) => {
+        $requirements$(.require::<$required>())*
+    };
+
+    (@requirements $requirements:ident, requires_default($($required:ty),* $(,)?)) => {
+        $requirements$(.require_default::<$required>())*
+    };
+
+    (@requirements $requirements:ident, conflicts($($conflict:ty),* $(,)?)) => {
+        $requirements$(.conflict::<$conflict>())*
+    };
+
+    (@requirements $requirements:ident, $flag:ident) => {
+        $requirements
+    };
+
+    ($type:ty $(, $flag:ident $(($($arg:ty),* $(,)?))?)* $(,)?) => {
         impl Component for $type {
             fn as_any(&self) -> &dyn Any {
                 self
@@ -102,6 +295,18 @@ macro_rules! impl_component {
             fn clone_component(&self) -> Box<dyn Component> {
                 Box::new(self.clone())
             }
+
+            fn get_requirements(&self) -> $crate::scenes::components::ComponentRequirements {
+                let requirements = $crate::scenes::components::ComponentRequirements::new();
+                $(
+                    let requirements = $crate::impl_component!(
+                        @requirements requirements, $flag $(($($arg),*))?
+                    );
+                )*
+                requirements
+            }
+
+            $($crate::impl_component!(@method $flag);)*
         }
 
         impl Debug for $type {
@@ -110,54 +315,180 @@ macro_rules! impl_component {
             }
         }
     };
+}
 
-    ($type:ty, updateable) => {
-        impl Component for $type {
-            fn as_any(&self) -> &dyn Any {
-                self
-            }
+/// Scene state shared by all components updated in one stage.
+struct UpdateStage<'a> {
+    objects: &'a HashMap<GameObjectId, GameObject>,
+    frame: Option<&'a Frame>,
+    time: &'a Time,
+    camera: Option<&'a Camera>,
+    spatial: &'a SpatialIndex,
+    /// Which components the stage updates, `None` for all of them.
+    parallel: Option<bool>,
+}
 
-            fn as_any_mut(&mut self) -> &mut dyn Any {
-                self
-            }
+impl UpdateStage<'_> {
+    fn update_object(
+        &self,
+        object: &mut GameObject,
+        commands: &mut Commands,
+        mut resources: ResourceAccess<'_>,
+    ) {
+        let mut updateables: Vec<(i32, TypeId)> = object
+            .components
+            .iter_mut()
+            .filter_map(|(type_id, component)| {
+                let updateable = component.as_updateable()?;
+                let included =
+                    self.parallel.is_none_or(|parallel| updateable.parallel_safe() == parallel);
+                included.then(|| (updateable.update_priority(), *type_id))
+            })
+            .collect();
+        updateables.sort_by_key(|(priority, _)| *priority);
 
-            fn clone_component(&self) -> Box<dyn Component> {
-                Box::new(self.clone())
-            }
+        for (_, type_id) in updateables {
+            // Components removed by an earlier update of this object are skipped.
+            let Some(mut component) = object.components.remove(&type_id) else {
+                continue;
+            };
+
+            let current = ComponentType { id: type_id, name: component.component_name() };
+            let requirements = component.get_requirements();
+            object
+                .components
+                .insert(type_id, Box::new(Detached { component: current, requirements }));
 
-            fn as_updateable(&mut self) -> Option<&mut dyn Updateable> {
-                Some(self)
+            let mut ctx = UpdateContext {
+                game_object: object,
+                current,
+                objects: self.objects,
+                frame: self.frame,
+                time: self.time,
+                camera: self.camera,
+                spatial: self.spatial,
+                commands,
+                resources: resources.reborrow(),
+            };
+
+            if let Some(updateable) = component.as_updateable() {
+                updateable.update(&mut ctx);
             }
-        }
 
-        impl Debug for $type {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                write!(f, "{:?}", stringify!($type))
+            // The component was either removed or replaced by a new one if the placeholder is gone.
+            match object.components.get_mut(&type_id) {
+                Some(slot) if slot.as_any().is::<Detached>() => *slot = component,
+                _ => {
+                    if let Some(lifecycle) = component.as_lifecycle() {
+                        lifecycle.on_remove();
+                    }
+                }
             }
         }
-    };
+    }
 }
 
 impl Scene {
+    /// Updates every [Updateable] component of the active objects, running the systems of each
+    /// [Stage] around it.
+    ///
+    /// With [Scene::set_parallel_updates] enabled, parallel-safe components are updated after the
+    /// others, spread over a thread pool.
+    ///
+    /// Objects are updated in the order they were added to the scene, and changes queued through
+    /// [UpdateContext::commands] are applied in that order after all objects are updated. The
+    /// timers and coroutines of the scene run right after.
     pub fn update(&mut self, delta_time: f32) {
-        for (_, object) in self.objects.iter_mut() {
-            if !object.active {
+        self.time.advance(delta_time);
+        self.run_systems(Stage::PreUpdate);
+
+        let mut commands = Commands::new();
+        let mut ids: Vec<GameObjectId> = self.objects.keys().copied().collect();
+        ids.sort_unstable_by_key(GameObjectId::index);
+
+        for id in ids {
+            let Some(mut object) = self.objects.remove(&id) else {
                 continue;
+            };
+
+            if object.active {
+                let stage = UpdateStage {
+                    objects: &self.objects,
+                    frame: self.frame.as_ref(),
+                    time: &self.time,
+                    camera: self.camera_manager.get_active_camera(),
+                    spatial: &self.spatial,
+                    parallel: self.parallel_updates.then_some(false),
+                };
+                let resources = ResourceAccess::Exclusive {
+                    scene: &mut self.resources,
+                    engine: &mut self.engine_resources,
+                };
+                stage.update_object(&mut object, &mut commands, resources);
             }
 
-            let object_ptr = object as *mut GameObject;
+            self.objects.insert(id, object);
+        }
 
-            let updateables: Vec<_> = object
-                .components
-                .values_mut()
-                .filter_map(|wrapper| wrapper.as_updateable())
-                .collect();
+        if self.parallel_updates {
+            self.update_parallel(&mut commands);
+        }
 
-            for updateable in updateables {
-                unsafe {
-                    updateable.update(delta_time, &mut *object_ptr);
-                }
-            }
+        commands.apply(self);
+        self.run_scheduler();
+
+        self.run_systems(Stage::Update);
+        self.run_systems(Stage::PostUpdate);
+        self.sync_renderers();
+        self.sync_lights();
+        self.update_spatial_index();
+    }
+
+    /// Enables the parallel update stage for components that are
+    /// [parallel-safe](Updateable::parallel_safe).
+    pub fn set_parallel_updates(&mut self, enabled: bool) {
+        self.parallel_updates = enabled;
+    }
+
+    pub fn parallel_updates(&self) -> bool {
+        self.parallel_updates
+    }
+
+    /// Updates the parallel-safe components, each object on its own task with its own command
+    /// buffer. The buffers are appended in object order, so the result does not depend on
+    /// scheduling.
+    fn update_parallel(&mut self, commands: &mut Commands) {
+        let mut objects: Vec<(GameObjectId, GameObject)> = self.objects.drain().collect();
+        objects.sort_unstable_by_key(|(id, _)| id.index());
+
+        let others = HashMap::new();
+        let stage = UpdateStage {
+            objects: &others,
+            frame: self.frame.as_ref(),
+            time: &self.time,
+            camera: self.camera_manager.get_active_camera(),
+            spatial: &self.spatial,
+            parallel: Some(true),
+        };
+        let (scene, engine) = (&self.resources, &self.engine_resources);
+
+        let buffers: Vec<Commands> = objects
+            .par_iter_mut()
+            .filter(|(_, object)| object.active)
+            .map(|(_, object)| {
+                let mut commands = Commands::new();
+                stage.update_object(
+                    object,
+                    &mut commands,
+                    ResourceAccess::Shared { scene, engine },
+                );
+                commands
+            })
+            .collect();
+
+        self.objects.extend(objects);
+        for mut buffer in buffers {
+            commands.append(&mut buffer);
         }
     }
 }
@@ -1,20 +1,514 @@
 use crate::{
+    camera::Camera,
+    frame::Frame,
     impl_component,
-    scenes::{Any, Component},
+    lights::{Environment, without_shadows},
+    scenes::{Any, Component, GameObject, GameObjectId, Scene, components::InstancedRenderer},
+    sprites::Sprite,
 };
-use three_d::Object;
-use std::fmt::Debug;
-use std::sync::Arc;
+use ciri_math::{Aabb, Transform, from_glam_mat4, to_glam_vec};
+use std::{
+    cmp::Ordering,
+    convert::Infallible,
+    fmt::Debug,
+    sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+use three_d::{
+    Axes, AxisAlignedBoundingBox, ColorTexture, DepthTexture, Effect, Geometry, GeometryId, Gm,
+    InstancedMesh, InstancedModelPart, Light, Mat4, Material, MaterialType, Mesh, ModelPart,
+    Object, ParticleSystem, Program, RenderStates, RenderTarget, Skybox, Sprites, SquareMatrix,
+    Viewer,
+};
+
+/// Something a [Renderer] can draw at the transform of its object.
+pub trait Renderable: Object + Send + Sync {
+    /// Moves the renderable to the world transformation of its object.
+    fn set_transformation(&mut self, transformation: Mat4);
+
+    /// Bounds of the renderable if it was moved to the world transformation, without moving it.
+    /// Defaults to its bounds where it is.
+    fn aabb_at(&self, transformation: Mat4) -> AxisAlignedBoundingBox {
+        let _ = transformation;
+        self.aabb()
+    }
+}
+
+/// Bounds of a geometry at `transformation`, from its bounds at the `current` one.
+fn moved_aabb(
+    aabb: AxisAlignedBoundingBox,
+    current: Mat4,
+    transformation: Mat4,
+) -> AxisAlignedBoundingBox {
+    match current.invert() {
+        Some(inverse) => aabb.transformed(transformation * inverse),
+        None => aabb,
+    }
+}
+
+macro_rules! impl_renderable {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl<M: Material + Send + Sync> Renderable for $ty {
+                fn set_transformation(&mut self, transformation: Mat4) {
+                    (**self).set_transformation(transformation);
+                }
+
+                fn aabb_at(&self, transformation: Mat4) -> AxisAlignedBoundingBox {
+                    moved_aabb(self.aabb(), (**self).transformation(), transformation)
+                }
+            }
+        )*
+    };
+}
+
+impl_renderable!(
+    Gm<Mesh, M>,
+    Gm<InstancedMesh, M>,
+    Gm<Sprites, M>,
+    Gm<ParticleSystem, M>,
+    ModelPart<M>,
+    InstancedModelPart<M>,
+);
+
+impl Renderable for Axes {
+    fn set_transformation(&mut self, transformation: Mat4) {
+        (**self).set_transformation(transformation);
+    }
 
+    fn aabb_at(&self, transformation: Mat4) -> AxisAlignedBoundingBox {
+        moved_aabb(self.aabb(), (**self).transformation(), transformation)
+    }
+}
+
+/// Skyboxes surround the camera, so they ignore the transform.
+impl Renderable for Skybox {
+    fn set_transformation(&mut self, _: Mat4) {}
+}
+
+/// Draws a [Renderable] at the transform of its object.
+///
+/// Clones of the component draw the same renderable, each at the transform of its own object,
+/// since the renderable is [placed](Renderer::at) right before it is used for an object.
 #[derive(Clone)]
 pub struct Renderer {
-    pub to_render: Arc<dyn Object + Send + Sync> ,
+    pub to_render: Arc<RwLock<dyn Renderable>>,
+    /// Whether the renderable is skipped when its bounds are outside the view of the camera.
+    /// Renderables without finite bounds, like skyboxes, are always drawn.
+    pub frustum_culling: bool,
+    /// Draw order between 2D renderers at the same z, lower keys are drawn first.
+    pub sort_key: i32,
+    /// Whether the renderable casts shadows of lights with shadows.
+    pub cast_shadows: bool,
+    /// Whether shadows are cast onto the renderable. Renderables with deferred materials always
+    /// receive shadows, since three-d lights them all at once.
+    pub receive_shadows: bool,
 }
 
 impl Renderer {
-    pub fn new(to_render: impl Object + Send + Sync + 'static) -> Self {
-        Self { to_render: Arc::new(to_render) }
+    pub fn new(to_render: impl Renderable + 'static) -> Self {
+        Self::shared(Arc::new(RwLock::new(to_render)))
+    }
+
+    /// Draws a renderable which is also used elsewhere.
+    pub fn shared(to_render: Arc<RwLock<dyn Renderable>>) -> Self {
+        Self {
+            to_render,
+            frustum_culling: true,
+            sort_key: 0,
+            cast_shadows: true,
+            receive_shadows: true,
+        }
+    }
+
+    /// Always draws the renderable, even when it seems to be out of view.
+    #[must_use]
+    pub fn without_frustum_culling(mut self) -> Self {
+        self.frustum_culling = false;
+        self
+    }
+
+    #[must_use]
+    pub fn without_shadow_casting(mut self) -> Self {
+        self.cast_shadows = false;
+        self
+    }
+
+    #[must_use]
+    pub fn without_shadow_receiving(mut self) -> Self {
+        self.receive_shadows = false;
+        self
+    }
+
+    #[must_use]
+    pub fn with_sort_key(mut self, sort_key: i32) -> Self {
+        self.sort_key = sort_key;
+        self
+    }
+
+    /// World space bounds of the renderable, `None` if they are empty or infinite.
+    pub fn world_aabb(&self) -> Option<Aabb> {
+        to_world_aabb(&self.get().aabb())
+    }
+
+    /// World space bounds of the renderable at the transform of an object, without moving it
+    /// there. `None` if they are empty or infinite.
+    pub fn world_aabb_at(&self, transform: &Transform) -> Option<Aabb> {
+        to_world_aabb(&self.get().aabb_at(from_glam_mat4(transform.to_matrix())))
+    }
+
+    /// The renderable at the transform of an object.
+    pub fn at(&self, transform: &Transform) -> PlacedRenderable<'_> {
+        PlacedRenderable {
+            renderer: self,
+            transformation: Some(from_glam_mat4(transform.to_matrix())),
+        }
+    }
+
+    pub fn get(&self) -> RwLockReadGuard<'_, dyn Renderable + 'static> {
+        self.to_render.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn get_mut(&self) -> RwLockWriteGuard<'_, dyn Renderable + 'static> {
+        self.to_render.write().unwrap_or_else(PoisonError::into_inner)
     }
 }
 
 impl_component!(Renderer);
+
+fn to_world_aabb(aabb: &AxisAlignedBoundingBox) -> Option<Aabb> {
+    if aabb.is_empty() || aabb.is_infinite() {
+        return None;
+    }
+
+    Some(Aabb::new(to_glam_vec(aabb.min()), to_glam_vec(aabb.max())))
+}
+
+/// The renderable of a [Renderer] at the transform of one of the objects sharing it. The
+/// renderable is moved there whenever it is used through the placed renderable.
+pub struct PlacedRenderable<'a> {
+    renderer: &'a Renderer,
+    transformation: Option<Mat4>,
+}
+
+impl PlacedRenderable<'_> {
+    pub fn renderer(&self) -> &Renderer {
+        self.renderer
+    }
+
+    /// World space bounds of the renderable at the transform, `None` if they are empty or
+    /// infinite.
+    pub fn world_aabb(&self) -> Option<Aabb> {
+        to_world_aabb(&self.get().aabb())
+    }
+
+    fn get(&self) -> RwLockWriteGuard<'_, dyn Renderable + 'static> {
+        let mut renderable = self.renderer.get_mut();
+        if let Some(transformation) = self.transformation {
+            renderable.set_transformation(transformation);
+        }
+        renderable
+    }
+}
+
+/// The renderable where it was last moved to.
+impl<'a> From<&'a Renderer> for PlacedRenderable<'a> {
+    fn from(renderer: &'a Renderer) -> Self {
+        Self { renderer, transformation: None }
+    }
+}
+
+impl Geometry for PlacedRenderable<'_> {
+    fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
+        self.get().draw(viewer, program, render_states);
+    }
+
+    fn vertex_shader_source(&self) -> String {
+        self.get().vertex_shader_source()
+    }
+
+    fn id(&self) -> GeometryId {
+        self.get().id()
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+    ) {
+        self.get().render_with_material(material, viewer, lights);
+    }
+
+    fn render_with_effect(
+        &self,
+        material: &dyn Effect,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        self.get().render_with_effect(material, viewer, lights, color_texture, depth_texture);
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.get().aabb()
+    }
+}
+
+impl Object for PlacedRenderable<'_> {
+    fn render(&self, viewer: &dyn Viewer, lights: &[&dyn Light]) {
+        self.get().render(viewer, lights);
+    }
+
+    fn material_type(&self) -> MaterialType {
+        self.get().material_type()
+    }
+}
+
+impl GameObject {
+    /// The [Renderer] of the object, or the one of its [InstancedRenderer] or [Sprite].
+    pub fn renderer(&self) -> Option<&Renderer> {
+        self.get_component::<Renderer>()
+            .or_else(|| self.get_component::<InstancedRenderer>().map(InstancedRenderer::renderer))
+            .or_else(|| self.get_component::<Sprite>().map(Sprite::renderer))
+    }
+}
+
+/// Number of renderers drawn and culled by the last render of a scene, summed over its cameras.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RenderStats {
+    pub drawn: usize,
+    /// Renderers outside the view of the camera.
+    pub culled: usize,
+}
+
+/// Where a renderer is drawn in the [render queue](Scene::render_queue): by pass, then depth,
+/// then sort key.
+#[derive(Debug, Clone, Copy)]
+struct DrawOrder {
+    transparent: bool,
+    depth: f32,
+    sort_key: i32,
+}
+
+impl DrawOrder {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.transparent
+            .cmp(&other.transparent)
+            .then(self.depth.total_cmp(&other.depth))
+            .then(self.sort_key.cmp(&other.sort_key))
+    }
+}
+
+impl Scene {
+    /// Moves every renderer to the transform of its object. Called at the end of
+    /// [Scene::update], call it again after moving objects outside of the update.
+    pub fn sync_renderers(&self) {
+        for object in self.objects.values().filter(|object| object.is_active()) {
+            if let Some(renderer) = object.renderer() {
+                renderer.get_mut().set_transformation(from_glam_mat4(object.transform.to_matrix()));
+            }
+        }
+    }
+
+    /// Active objects with a [renderer](GameObject::renderer) on one of the layers of the
+    /// camera's culling mask.
+    pub fn renderers_for(
+        &self,
+        camera: &Camera,
+    ) -> impl Iterator<Item = (GameObjectId, &Renderer)> + '_ {
+        let mask = camera.culling_mask;
+        self.objects.iter().filter_map(move |(id, object)| {
+            if !object.is_active() || !object.layers().intersects(mask) {
+                return None;
+            }
+
+            object.renderer().map(|renderer| (*id, renderer))
+        })
+    }
+
+    /// Renderers drawn by the camera, which are [Scene::renderers_for] the camera without the
+    /// ones outside of its [frustum](Camera::frustum).
+    pub fn visible_renderers(
+        &self,
+        camera: &Camera,
+    ) -> (Vec<(GameObjectId, &Renderer)>, RenderStats) {
+        let frustum = camera.frustum();
+        let mut stats = RenderStats::default();
+
+        let visible = self
+            .renderers_for(camera)
+            .filter(|(id, renderer)| {
+                let visible = !renderer.frustum_culling
+                    || self
+                        .placed(*id, renderer)
+                        .world_aabb()
+                        .is_none_or(|aabb| frustum.intersects_aabb(&aabb));
+
+                if visible {
+                    stats.drawn += 1;
+                } else {
+                    stats.culled += 1;
+                }
+                visible
+            })
+            .collect();
+
+        (visible, stats)
+    }
+
+    /// The [visible renderers](Scene::visible_renderers) in the order the camera draws them.
+    ///
+    /// In 3D, opaque renderers are drawn front to back and transparent ones back to front after
+    /// them. In 2D, renderers are drawn by the z of their translation, then by their
+    /// [sort key](Renderer::sort_key), so higher ones are drawn on top.
+    pub fn render_queue(&self, camera: &Camera) -> (Vec<(GameObjectId, &Renderer)>, RenderStats) {
+        let (renderers, stats) = self.visible_renderers(camera);
+        let mut queue: Vec<_> = renderers
+            .into_iter()
+            .map(|(id, renderer)| (self.draw_order(camera, id, renderer), id, renderer))
+            .collect();
+
+        queue.sort_by(|(a, a_id, _), (b, b_id, _)| a.cmp(b).then(a_id.cmp(b_id)));
+        (queue.into_iter().map(|(_, id, renderer)| (id, renderer)).collect(), stats)
+    }
+
+    /// The renderer of the object at the transform of the object.
+    pub(crate) fn placed<'a>(
+        &self,
+        id: GameObjectId,
+        renderer: &'a Renderer,
+    ) -> PlacedRenderable<'a> {
+        match self.objects.get(&id) {
+            Some(object) => renderer.at(&object.transform),
+            None => PlacedRenderable::from(renderer),
+        }
+    }
+
+    fn draw_order(&self, camera: &Camera, id: GameObjectId, renderer: &Renderer) -> DrawOrder {
+        if camera.is_2d() {
+            let z = self.objects.get(&id).map_or(0.0, |object| object.transform.translation.z);
+            return DrawOrder { transparent: false, depth: z, sort_key: renderer.sort_key };
+        }
+
+        // Renderables without bounds, like skyboxes, are treated as infinitely far away.
+        let placed = self.placed(id, renderer);
+        let distance = placed.world_aabb().map_or(f32::INFINITY, |aabb| {
+            aabb.center().distance_squared(to_glam_vec(camera.position()))
+        });
+
+        let transparent = placed.material_type() == MaterialType::Transparent;
+        let depth = if transparent { -distance } else { distance };
+        DrawOrder { transparent, depth, sort_key: 0 }
+    }
+
+    /// Renders what the camera sees into its viewport of the frame, through its
+    /// [post-processing](Camera::post_processing). The shadows of the lights are updated for the
+    /// camera first.
+    ///
+    /// The [mesh batches](Scene::mesh_batches_for) of the camera are drawn with one instanced draw
+    /// call each, after the opaque renderers, and count one drawn or culled renderer per object.
+    pub fn render_camera(&self, frame: &Frame, camera: &Camera) -> RenderStats {
+        let batches = self.mesh_batches_for(camera);
+        self.instancing().update(&frame.input.context, self, &batches);
+        self.update_shadows(camera);
+
+        let (renderers, mut stats) = self.render_queue(camera);
+        let instancing = self.instancing();
+        let frustum = camera.frustum();
+        let batches: Vec<_> = instancing
+            .renderers()
+            .filter(|(renderer, count)| {
+                let visible =
+                    renderer.world_aabb().is_none_or(|aabb| frustum.intersects_aabb(&aabb));
+                if visible {
+                    stats.drawn += count;
+                } else {
+                    stats.culled += count;
+                }
+                visible
+            })
+            .map(|(renderer, _)| PlacedRenderable::from(renderer))
+            .collect();
+
+        let renderers: Vec<_> =
+            renderers.iter().map(|(id, renderer)| self.placed(*id, renderer)).collect();
+        let opaque = renderers
+            .iter()
+            .position(|placed| placed.material_type() == MaterialType::Transparent)
+            .unwrap_or(renderers.len());
+        let (opaque, transparent) = renderers.split_at(opaque);
+        let objects: Vec<_> = opaque
+            .iter()
+            .chain(&batches)
+            .chain(transparent)
+            .map(|placed| (placed, placed.renderer().receive_shadows))
+            .collect();
+        let environment = self.environment();
+        let scene_lights = self.all_lights();
+        let unshadowed = without_shadows(&scene_lights);
+        let lights = Lights {
+            shadowed: scene_lights.iter().map(|light| &**light as &dyn Light).collect(),
+            unshadowed: unshadowed.iter().map(|light| &**light).collect(),
+        };
+        let draw = |target: &RenderTarget<'_>, viewer: &dyn Viewer| {
+            let objects =
+                objects.iter().map(|(object, receives)| (*object as &dyn Object, *receives));
+            draw_in_order(target, viewer, objects, &lights, environment);
+        };
+
+        let screen = frame.input.screen();
+        if camera.post_processing.is_active() {
+            camera.post_processing.render(&frame.input.context, camera, &screen, draw);
+        } else {
+            frame.clear_viewport(camera.viewport(), camera.clear_state);
+            draw(&screen, camera);
+        }
+
+        stats
+    }
+
+    /// Renderers drawn and culled by the last render of the scene.
+    pub fn render_stats(&self) -> RenderStats {
+        self.render_stats
+    }
+}
+
+/// The lights of a scene, with and without their shadows.
+struct Lights<'a> {
+    shadowed: Vec<&'a dyn Light>,
+    unshadowed: Vec<&'a dyn Light>,
+}
+
+/// Renders the objects into the viewport of the viewer in the given order, rather than sorting
+/// them by distance like three-d does. Objects with deferred materials are rendered by three-d
+/// first, since they need its geometry pass.
+///
+/// Each object comes with whether it receives shadows. The sky of the environment is drawn
+/// before the forward objects, where the deferred ones left the background empty.
+fn draw_in_order<'a>(
+    target: &RenderTarget<'_>,
+    viewer: &dyn Viewer,
+    objects: impl Iterator<Item = (&'a dyn Object, bool)>,
+    lights: &Lights<'_>,
+    environment: Option<&Environment>,
+) {
+    let (deferred, forward): (Vec<_>, Vec<_>) =
+        objects.partition(|(object, _)| object.material_type() == MaterialType::Deferred);
+
+    let scissor_box = viewer.viewport().into();
+    let deferred = deferred.into_iter().map(|(object, _)| object);
+    target.render_partially(scissor_box, viewer, deferred, &lights.shadowed);
+    let Ok(_) = target.write_partially::<Infallible>(scissor_box, || {
+        if let Some(environment) = environment {
+            environment.render_sky(viewer);
+        }
+        for (object, receives_shadows) in forward {
+            object.render(
+                viewer,
+                if receives_shadows { &lights.shadowed } else { &lights.unshadowed },
+            );
+        }
+        Ok(())
+    });
+}
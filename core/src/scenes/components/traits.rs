@@ -1,22 +1,96 @@
-use crate::scenes::game_object::GameObject;
-use std::{any::TypeId, collections::HashSet};
+use crate::scenes::components::{Component, UpdateContext};
+use std::any::{TypeId, type_name};
 
 pub trait Updateable {
-    fn update(&mut self, delta_time: f32, game_object: &mut GameObject);
+    fn update(&mut self, ctx: &mut UpdateContext);
+
+    /// Components with a lower priority are updated first.
     fn update_priority(&self) -> i32 {
         0
     }
+
+    /// Whether the component only touches its own object, so it can be updated on a worker thread
+    /// once [Scene::set_parallel_updates](crate::scenes::Scene::set_parallel_updates) is enabled.
+    ///
+    /// Parallel-safe components are updated in a stage after all other components, see
+    /// [UpdateContext] for what they can access there.
+    fn parallel_safe(&self) -> bool {
+        false
+    }
+}
+
+/// Identifies a component type together with its name, used in error messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ComponentType {
+    pub id: TypeId,
+    pub name: &'static str,
+}
+
+impl ComponentType {
+    pub fn of<T: Component + 'static>() -> Self {
+        Self { id: TypeId::of::<T>(), name: type_name::<T>() }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Requirement {
+    pub component: ComponentType,
+    /// Creates the component when it is missing. Set for requirements declared with
+    /// [ComponentRequirements::require_default].
+    pub default: Option<fn() -> Box<dyn Component>>,
 }
 
-#[derive(Default)]
+/// Components that must or must not be present on an object next to the declaring component.
+#[derive(Debug, Clone, Default)]
 pub struct ComponentRequirements {
-    pub required: HashSet<TypeId>,
-    pub conflicts: HashSet<TypeId>,
+    pub required: Vec<Requirement>,
+    pub conflicts: Vec<ComponentType>,
+}
+
+impl ComponentRequirements {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requires `T` to be added to the object first.
+    #[must_use]
+    pub fn require<T: Component + 'static>(mut self) -> Self {
+        self.required.push(Requirement { component: ComponentType::of::<T>(), default: None });
+        self
+    }
+
+    /// Requires `T`, inserting `T::default()` when the object does not have it yet.
+    #[must_use]
+    pub fn require_default<T: Component + Default + 'static>(mut self) -> Self {
+        self.required.push(Requirement {
+            component: ComponentType::of::<T>(),
+            default: Some(|| Box::new(T::default())),
+        });
+        self
+    }
+
+    /// Forbids `T` from being on the same object.
+    #[must_use]
+    pub fn conflict<T: Component + 'static>(mut self) -> Self {
+        self.conflicts.push(ComponentType::of::<T>());
+        self
+    }
+
+    pub fn requires(&self, type_id: TypeId) -> bool {
+        self.required.iter().any(|requirement| requirement.component.id == type_id)
+    }
+
+    pub fn conflicts_with(&self, type_id: TypeId) -> bool {
+        self.conflicts.iter().any(|conflict| conflict.id == type_id)
+    }
 }
 
+/// Hooks called when a component is added to or removed from an object.
 pub trait ComponentLifecycle {
     fn on_add(&mut self) {}
     fn on_remove(&mut self) {}
+
+    #[deprecated(note = "requirements are read from `Component::get_requirements`")]
     fn get_requirements(&self) -> ComponentRequirements {
         ComponentRequirements::default()
     }
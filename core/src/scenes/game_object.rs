@@ -1,4 +1,4 @@
-use crate::scenes::components::Component;
+use crate::scenes::components::{Component, Detached};
 use ciri_math::Transform;
 use id_arena::Id;
 use std::{any::TypeId, collections::HashMap, fmt::Debug};
@@ -20,17 +20,18 @@ impl Clone for GameObject {
             name: self.name.clone(),
             transform: self.transform,
             active: self.active,
-            components: self.components.iter().map(|(k, v)| (*k, v.clone_component())).collect(),
+            components: self.attached().map(|(k, v)| (*k, v.clone_component())).collect(),
         }
     }
 }
 
+#[expect(clippy::missing_fields_in_debug, reason = "the components are shown through attached")]
 impl Debug for GameObject {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct(&format!("GameObject \"{}\"", self.name))
             .field("active", &self.active)
             .field("transform", &self.transform)
-            .field("components", &self.components)
+            .field("components", &self.attached().collect::<HashMap<_, _>>())
             .finish()
     }
 }
@@ -63,4 +64,13 @@ impl GameObject {
     pub fn disable(&mut self) {
         self.active = false;
     }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// The components, without the placeholder of the one being updated.
+    fn attached(&self) -> impl Iterator<Item = (&TypeId, &Box<dyn Component>)> {
+        self.components.iter().filter(|(_, component)| !component.as_any().is::<Detached>())
+    }
 }
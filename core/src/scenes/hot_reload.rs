@@ -0,0 +1,91 @@
+use crate::assets;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+struct WatchedAsset {
+    name: &'static str,
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+/// Polls the modification times of asset files to find the ones changed on disk.
+///
+/// Directories and globs watch the files they matched when the watcher was created. Paths that
+/// can't be read, like URLs, are watched but never reported until they appear.
+pub struct AssetWatcher {
+    assets: Vec<WatchedAsset>,
+    interval: Duration,
+    last_poll: Instant,
+}
+
+impl AssetWatcher {
+    /// Time between two checks of the files in [AssetWatcher::poll].
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(500);
+
+    /// Watches `(name, path)` pairs, as returned by [SceneAuto::asset_paths].
+    ///
+    /// [SceneAuto::asset_paths]: crate::scenes::SceneAuto::asset_paths
+    pub fn new(assets: &[(&'static str, &'static str)]) -> Self {
+        Self {
+            assets: assets
+                .iter()
+                .flat_map(|(name, pattern)| {
+                    let paths = assets::matching_paths(pattern)
+                        .unwrap_or_else(|_| vec![PathBuf::from(pattern)]);
+                    paths.into_iter().map(|path| WatchedAsset {
+                        name,
+                        modified: modified(&path),
+                        path,
+                    })
+                })
+                .collect(),
+            interval: Self::DEFAULT_INTERVAL,
+            last_poll: Instant::now(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Same as [AssetWatcher::changed], but checks at most once per interval.
+    pub fn poll(&mut self) -> Vec<&'static str> {
+        if self.last_poll.elapsed() < self.interval {
+            return Vec::new();
+        }
+
+        self.last_poll = Instant::now();
+        self.changed()
+    }
+
+    /// Names of the assets with a file modified since the last check.
+    pub fn changed(&mut self) -> Vec<&'static str> {
+        let mut changed = Vec::new();
+        for asset in &mut self.assets {
+            let modified = modified(&asset.path);
+            if modified.is_none() || modified == asset.modified {
+                continue;
+            }
+
+            asset.modified = modified;
+            if !changed.contains(&asset.name) {
+                changed.push(asset.name);
+            }
+        }
+
+        changed
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
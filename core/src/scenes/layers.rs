@@ -0,0 +1,83 @@
+use crate::{
+    impl_component,
+    scenes::{Any, Component, GameObject},
+};
+use std::{
+    fmt::Debug,
+    ops::{BitAnd, BitOr, BitOrAssign, Not},
+};
+
+/// A set of up to 32 layers, used to select objects in queries such as [Scene::raycast] and in the
+/// culling mask of cameras.
+///
+/// [Scene::raycast]: crate::scenes::Scene::raycast
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LayerMask(pub u32);
+
+impl LayerMask {
+    pub const ALL: Self = Self(u32::MAX);
+    /// Layer of objects without a [Layers] component.
+    pub const DEFAULT: Self = Self::layer(0);
+    pub const NONE: Self = Self(0);
+
+    /// Mask of the single layer with the given index, in `0..32`.
+    pub const fn layer(index: u32) -> Self {
+        Self(1 << index)
+    }
+
+    pub const fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl Default for LayerMask {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl BitOr for LayerMask {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for LayerMask {
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+impl BitAnd for LayerMask {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+}
+
+impl Not for LayerMask {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(!self.0)
+    }
+}
+
+/// Layers an object belongs to, [LayerMask::DEFAULT] when the component is missing.
+#[derive(Clone, Copy)]
+pub struct Layers(pub LayerMask);
+
+impl_component!(Layers);
+
+impl GameObject {
+    pub fn layers(&self) -> LayerMask {
+        self.get_component::<Layers>().map_or(LayerMask::DEFAULT, |layers| layers.0)
+    }
+}
@@ -1,4 +1,8 @@
-use crate::scenes::{Scene, SceneTrait};
+use crate::{
+    assets::AssetServer,
+    resources::Resources,
+    scenes::{AssetWatcher, Scene, SceneTrait},
+};
 use anyhow::Result;
 use futures::executor::block_on;
 use std::{
@@ -13,15 +17,43 @@ pub struct SceneManager {
     scenes: HashMap<TypeId, Box<dyn SceneTrait>>,
     active: TypeId,
     context: Context,
+    hot_reload: bool,
+    watchers: HashMap<TypeId, AssetWatcher>,
+    assets: AssetServer,
+    /// Resources shared by all scenes, lent to a scene while it runs.
+    pub(crate) resources: Resources,
+}
+
+/// Lends the engine resources to the scene for the duration of `f`.
+fn lend<R>(
+    resources: &mut Resources,
+    scene: &mut dyn SceneTrait,
+    f: impl FnOnce(&mut dyn SceneTrait) -> R,
+) -> R {
+    std::mem::swap(&mut scene.scene().engine_resources, resources);
+    let result = f(scene);
+    std::mem::swap(&mut scene.scene().engine_resources, resources);
+    result
 }
 
 impl SceneManager {
     pub fn new(context: Context) -> Self {
-        Self { scenes: HashMap::new(), active: TypeId::of::<()>(), context }
+        Self {
+            scenes: HashMap::new(),
+            active: TypeId::of::<()>(),
+            context,
+            hot_reload: false,
+            watchers: HashMap::new(),
+            assets: AssetServer::new(),
+            resources: Resources::new(),
+        }
     }
 
     /// Register a new scene, that you can later activate that with [SceneManager::set_active]
-    pub fn register(&mut self, scene: impl SceneTrait + 'static) {
+    ///
+    /// The scene loads its assets through the [AssetServer] of the manager.
+    pub fn register(&mut self, mut scene: impl SceneTrait + 'static) {
+        scene.scene().set_assets(self.assets.clone());
         self.scenes.insert(scene.type_id(), Box::new(scene));
     }
 
@@ -61,11 +93,12 @@ impl SceneManager {
             self.active = type_id;
 
             if !scene.once_loaded() {
-                block_on(async {
-                    scene.load_assets().await?;
-                    scene.setup_async(self.context.clone()).await?;
-                    Ok::<_, anyhow::Error>(())
+                block_on(scene.load_assets())?;
+                lend(&mut self.resources, scene.as_mut(), |scene| {
+                    block_on(scene.setup_async(self.context.clone()))
                 })?;
+
+                scene.scene().loaded = true;
             }
 
             Ok(true)
@@ -78,6 +111,17 @@ impl SceneManager {
         self.scenes.get_mut(&self.active).map(|s| s.as_mut())
     }
 
+    /// Runs `f` on the active scene with the engine resources lent to it.
+    pub(crate) fn with_active<R>(&mut self, f: impl FnOnce(&mut dyn SceneTrait) -> R) -> Option<R> {
+        let scene = self.scenes.get_mut(&self.active)?;
+        Some(lend(&mut self.resources, scene.as_mut(), f))
+    }
+
+    /// Server shared by the registered scenes.
+    pub fn assets(&self) -> &AssetServer {
+        &self.assets
+    }
+
     pub fn is_registered<T: SceneTrait + 'static>(&self) -> bool {
         self.scenes.iter().any(|(_, scene)| scene.type_id() == TypeId::of::<T>())
     }
@@ -85,4 +129,58 @@ impl SceneManager {
     pub fn is_active<T: SceneTrait + 'static>(&self) -> bool {
         self.active == TypeId::of::<T>()
     }
+
+    /// Watches the asset files of the active scene and reloads the ones that change, calling
+    /// [SceneTrait::on_asset_reloaded]. Meant for development, it is disabled by default.
+    pub fn set_hot_reload(&mut self, enabled: bool) {
+        self.hot_reload = enabled;
+        if !enabled {
+            self.watchers.clear();
+        }
+    }
+
+    pub fn hot_reload(&self) -> bool {
+        self.hot_reload
+    }
+
+    /// Reloads the changed assets of the active scene when hot reload is enabled. Assets that fail
+    /// to load keep their previous value.
+    pub(crate) fn reload_changed_assets(&mut self) -> Result<()> {
+        if !self.hot_reload {
+            return Ok(());
+        }
+
+        let Some(scene) = self.scenes.get_mut(&self.active) else {
+            return Ok(());
+        };
+
+        if !scene.once_loaded() {
+            return Ok(());
+        }
+
+        let changed = self
+            .watchers
+            .entry(self.active)
+            .or_insert_with(|| AssetWatcher::new(scene.asset_paths()))
+            .poll();
+
+        for name in changed {
+            // Reloads the shared assets in place, so every handle to them sees the change.
+            let path = scene.asset_paths().iter().find(|(asset, _)| *asset == name);
+            if let Some(Err(error)) = path.map(|(_, path)| self.assets.reload_matching(path)) {
+                log::warn!("failed to reload asset `{name}`: {error:#}");
+                continue;
+            }
+
+            match block_on(scene.reload_asset(name)) {
+                Ok(true) => lend(&mut self.resources, scene.as_mut(), |scene| {
+                    scene.on_asset_reloaded(name, self.context.clone())
+                })?,
+                Ok(false) => {}
+                Err(error) => log::warn!("failed to reload asset `{name}`: {error:#}"),
+            }
+        }
+
+        Ok(())
+    }
 }
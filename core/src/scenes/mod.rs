@@ -1,29 +1,52 @@
 use three_d::HasContext;
+mod commands;
 pub mod components;
 mod game_object;
+mod hot_reload;
+mod layers;
 pub use game_object::*;
 pub mod manager;
+mod pool;
+mod raycast;
+pub mod scheduler;
+mod spatial;
+pub mod systems;
+mod time;
+
+pub use commands::*;
+pub use hot_reload::AssetWatcher;
+pub use layers::{LayerMask, Layers};
+pub use pool::{GameObjectPool, PoolGrowth, PoolId};
+pub use raycast::{MeshCollider, RaycastHit};
+pub use spatial::{Bounds, SpatialIndex};
+pub use time::*;
 
 pub use crate::{
+    assets::AssetServer,
     camera::manager::CameraManager,
     frame::Frame,
+    resources::Resources,
     scenes::{
-        components::{Component, Renderer},
+        components::{Component, RenderStats, Renderer},
         game_object::{GameObject, GameObjectId},
+        pool::Pools,
+        scheduler::Scheduler,
+        systems::Systems,
     },
 };
+use crate::{lights::SceneLight, scenes::components::InstanceCache};
 use anyhow::Result;
+pub use ciri_derive::Scene;
 use ciri_math::Transform;
 use id_arena::{Arena, Id};
 use std::{
-    any::{Any, TypeId},
+    any::Any,
     collections::HashMap,
-    fmt::{Debug, Pointer, format},
+    fmt::Debug,
     pin::Pin,
-    sync::Arc,
+    sync::{Arc, Mutex},
 };
-use three_d::{ClearState, Context, FrameInput, FrameOutput, Light, Object, Viewer, Window};
-use three_d_asset::io::{RawAssets, load_and_deserialize_async};
+use three_d::{Context, FrameOutput};
 
 pub struct Scene {
     pub name: &'static str,
@@ -31,7 +54,19 @@ pub struct Scene {
     pub(crate) camera_manager: CameraManager,
     pub id_arena: Arena<GameObject>,
     pub frame: Option<Frame>,
-    pub lights: Vec<Arc<dyn Light + Send + Sync>>,
+    pub lights: Vec<Arc<dyn SceneLight>>,
+    pub time: Time,
+    pub(crate) systems: Systems,
+    pub(crate) loaded: bool,
+    pub(crate) resources: Resources,
+    pub(crate) engine_resources: Resources,
+    pub(crate) parallel_updates: bool,
+    pub(crate) scheduler: Scheduler,
+    pub(crate) pools: Pools,
+    pub(crate) spatial: SpatialIndex,
+    pub(crate) assets: AssetServer,
+    pub(crate) render_stats: RenderStats,
+    pub(crate) instancing: Mutex<InstanceCache>,
 }
 
 impl Debug for Scene {
@@ -49,6 +84,18 @@ impl Scene {
             id_arena: Arena::new(),
             frame: None,
             lights: Vec::new(),
+            time: Time::default(),
+            systems: Systems::default(),
+            loaded: false,
+            resources: Resources::new(),
+            engine_resources: Resources::new(),
+            parallel_updates: false,
+            scheduler: Scheduler::default(),
+            pools: Pools::default(),
+            spatial: SpatialIndex::default(),
+            assets: AssetServer::new(),
+            render_stats: RenderStats::default(),
+            instancing: Mutex::default(),
         }
     }
 
@@ -61,10 +108,19 @@ impl Scene {
             components: temp.components,
         });
 
-        self.objects.insert(id, self.id_arena[id].clone());
+        let mut object = self.id_arena[id].clone();
+        object.id = Some(id);
+        self.objects.insert(id, object);
         id
     }
 
+    /// Removes the object, cancelling the timers and coroutines it owns.
+    pub fn remove_object(&mut self, id: GameObjectId) -> Option<GameObject> {
+        let object = self.objects.remove(&id)?;
+        self.cancel_owned_tasks(id);
+        Some(object)
+    }
+
     pub fn query(&self, id: GameObjectId) -> Option<&GameObject> {
         self.objects.get(&id)
     }
@@ -76,6 +132,22 @@ impl Scene {
     pub fn objects(&self) -> &HashMap<GameObjectId, GameObject> {
         &self.objects
     }
+
+    /// Server loading the assets of the scene, shared by every scene of the engine once the scene
+    /// is registered.
+    pub fn assets(&self) -> &AssetServer {
+        &self.assets
+    }
+
+    /// Loads the assets of the scene through the server, to share them with other scenes.
+    pub fn set_assets(&mut self, assets: AssetServer) {
+        self.assets = assets;
+    }
+
+    /// Whether the assets of the scene were loaded and the scene was set up.
+    pub fn is_loaded(&self) -> bool {
+        self.loaded
+    }
 }
 
 pub type UpdateResult = Result<FrameOutput>;
@@ -97,25 +169,14 @@ pub trait SceneTrait: SceneAuto + Any + Send + Sync + 'static {
         self.scene().update(frame.delta_time());
 
         let scene = self.scene();
-        let mut objects = Vec::new();
-
-        for (_, object) in scene.objects() {
-            if !object.active {
-                continue;
-            }
-
-            if let Some(renderer) = object.get_component::<Renderer>() {
-                objects.push(&*renderer.to_render);
-            }
-        }
-
-        if let Some(camera) = scene.get_active_camera() {
-            frame.clear(ClearState::color_and_depth(0.5, 0.5, 0.5, 1.0, 1.0));
-            let light_refs: Vec<&dyn Light> =
-                scene.lights.iter().map(|l| l.as_ref() as &dyn Light).collect();
-            frame.render(camera, objects, &light_refs);
+        let mut stats = RenderStats::default();
+        for (_, camera) in scene.camera_manager.rendered_cameras() {
+            let camera_stats = scene.render_camera(frame, camera);
+            stats.drawn += camera_stats.drawn;
+            stats.culled += camera_stats.culled;
         }
 
+        self.scene().render_stats = stats;
         self.scene().frame = None;
         Ok(FrameOutput::default())
     }
@@ -128,6 +189,14 @@ pub trait SceneTrait: SceneAuto + Any + Send + Sync + 'static {
         Ok(())
     }
 
+    /// Called after the asset `name` was reloaded because its file changed, see
+    /// [SceneManager::set_hot_reload].
+    ///
+    /// [SceneManager::set_hot_reload]: manager::SceneManager::set_hot_reload
+    fn on_asset_reloaded(&mut self, _name: &str, _: Context) -> Result<()> {
+        Ok(())
+    }
+
     fn exit(&mut self) {}
 }
 
@@ -137,6 +206,15 @@ pub trait SceneAuto {
     fn load_assets(&mut self) -> ResultFuture<Result<()>> {
         Box::pin(async move { Ok(()) })
     }
+    /// Names and paths of the declared assets.
+    fn asset_paths(&self) -> &'static [(&'static str, &'static str)] {
+        &[]
+    }
+    /// Loads the asset `name` again, returning whether the scene declares it. The previous value
+    /// is kept if loading fails.
+    fn reload_asset<'a>(&'a mut self, _name: &'a str) -> ResultFuture<'a, Result<bool>> {
+        Box::pin(async move { Ok(false) })
+    }
     fn once_loaded(&self) -> bool;
 }
 
@@ -152,21 +230,21 @@ macro_rules! impl_scene {
         $name:expr,
         $struct:ident,
         $data:ty
-        $(, ( $( $asset_name:ident, $path:expr => $asset_ty:ty ),* $(,)? ) )?
+        $(, ( $( $asset_name:ident, $path:expr => $asset_ty:ty $([$($option:tt)*])? ),* $(,)? ) )?
     ) => {
-        use three_d_asset::io::load_and_deserialize_async;
-
         pub struct $struct {
             pub scene: Scene,
             pub data: $data,
+            #[deprecated(note = "scenes are marked as loaded once set up, see `Scene::is_loaded`")]
             pub once_loaded: bool,
              $(
                 $(
-                    pub $asset_name: $asset_ty,
+                    pub $asset_name: $crate::__asset_field!($asset_ty $(; $($option)*)?),
                 )*
             )?
         }
 
+        #[allow(deprecated)]
         impl Default for $struct {
             fn default() -> Self {
                 Self {
@@ -175,7 +253,7 @@ macro_rules! impl_scene {
                     once_loaded: false,
                     $(
                         $(
-                           $asset_name: <$asset_ty>::default(),
+                           $asset_name: $crate::__asset_initial!($asset_ty $(; $($option)*)?),
                         )*
                     )?
                 }
@@ -193,10 +271,11 @@ macro_rules! impl_scene {
             }
 
             fn load_assets(&mut self) -> ResultFuture<Result<()>> {
+                let assets = self.scene.assets().clone();
                 Box::pin(async move {
                     $(
                         $(
-                            self.$asset_name = load_and_deserialize_async($path).await?;
+                            self.$asset_name = $crate::__asset_load!(assets, $path $(; $($option)*)?);
                         )*
                     )?
 
@@ -204,8 +283,35 @@ macro_rules! impl_scene {
                 })
             }
 
+            fn asset_paths(&self) -> &'static [(&'static str, &'static str)] {
+                &[
+                    $(
+                        $(
+                            (stringify!($asset_name), $path),
+                        )*
+                    )?
+                ]
+            }
+
+            fn reload_asset<'a>(&'a mut self, name: &'a str) -> ResultFuture<'a, Result<bool>> {
+                let assets = self.scene.assets().clone();
+                Box::pin(async move {
+                    $(
+                        $(
+                            if name == stringify!($asset_name) {
+                                self.$asset_name = $crate::__asset_reload!(assets, $path $(; $($option)*)?);
+                                return Ok(true);
+                            }
+                        )*
+                    )?
+
+                    Ok(false)
+                })
+            }
+
+            #[allow(deprecated)]
             fn once_loaded(&self) -> bool {
-                self.once_loaded
+                self.once_loaded || self.scene.is_loaded()
             }
         }
 
@@ -216,3 +322,48 @@ macro_rules! impl_scene {
         }
     };
 }
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __asset_field {
+    ($ty:ty) => { $ty };
+    ($ty:ty; optional) => { ::std::option::Option<$ty> };
+    ($ty:ty; many) => { ::std::vec::Vec<$ty> };
+    ($ty:ty; fallback $(= $fallback:expr)?) => { $ty };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __asset_initial {
+    ($ty:ty; fallback) => { <$ty as $crate::assets::Placeholder>::placeholder() };
+    ($ty:ty; fallback = $fallback:expr) => { ($fallback)() };
+    ($ty:ty $(; $($option:tt)*)?) => { ::std::default::Default::default() };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __asset_load {
+    ($assets:ident, $path:expr) => { $crate::assets::load(&$assets, $path).await? };
+    ($assets:ident, $path:expr; optional) => {
+        $crate::assets::load_optional(&$assets, $path).await?
+    };
+    ($assets:ident, $path:expr; many) => { $crate::assets::load_many(&$assets, $path).await? };
+    ($assets:ident, $path:expr; fallback) => {
+        $crate::assets::load_or(&$assets, $path, $crate::assets::Placeholder::placeholder).await
+    };
+    ($assets:ident, $path:expr; fallback = $fallback:expr) => {
+        $crate::assets::load_or(&$assets, $path, $fallback).await
+    };
+}
+
+/// Like `__asset_load`, but fails instead of falling back, so reloads keep the previous value.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __asset_reload {
+    ($assets:ident, $path:expr; fallback $(= $fallback:expr)?) => {
+        $crate::assets::load(&$assets, $path).await?
+    };
+    ($assets:ident, $path:expr $(; $($option:tt)*)?) => {
+        $crate::__asset_load!($assets, $path $(; $($option)*)?)
+    };
+}
@@ -0,0 +1,221 @@
+use crate::scenes::{GameObject, GameObjectId, Scene};
+use ciri_math::Transform;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    sync::Arc,
+};
+
+/// How a [GameObjectPool] grows once every object is in use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PoolGrowth {
+    /// Spawning fails when the pool is exhausted.
+    Fixed,
+    /// Adds the given number of objects.
+    Linear(usize),
+    /// Doubles the size of the pool.
+    #[default]
+    Double,
+}
+
+type SpawnHook = Arc<dyn Fn(&mut GameObject) + Send + Sync>;
+
+/// Disabled copies of a template object, handed out by [Scene::spawn_pooled] instead of adding
+/// new objects.
+///
+/// Pooled objects stay in the scene while released, so their ids remain valid.
+///
+/// ```ignore
+/// let bullets = scene.add_pool(GameObjectPool::new(bullet).with_capacity(64));
+/// let bullet = scene.spawn_pooled(bullets, transform).unwrap();
+/// scene.release_pooled(bullet);
+/// ```
+#[derive(Clone)]
+pub struct GameObjectPool {
+    template: GameObject,
+    capacity: usize,
+    growth: PoolGrowth,
+    max_size: Option<usize>,
+    on_spawn: Option<SpawnHook>,
+    available: Vec<GameObjectId>,
+    in_use: HashSet<GameObjectId>,
+}
+
+impl Debug for GameObjectPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GameObjectPool")
+            .field("template", &self.template.name)
+            .field("growth", &self.growth)
+            .field("available", &self.available.len())
+            .field("in_use", &self.in_use.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl GameObjectPool {
+    pub fn new(template: GameObject) -> Self {
+        Self {
+            template,
+            capacity: 0,
+            growth: PoolGrowth::default(),
+            max_size: None,
+            on_spawn: None,
+            available: Vec::new(),
+            in_use: HashSet::new(),
+        }
+    }
+
+    /// Number of objects instantiated when the pool is added to a scene.
+    #[must_use]
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    #[must_use]
+    pub fn with_growth(mut self, growth: PoolGrowth) -> Self {
+        self.growth = growth;
+        self
+    }
+
+    /// Upper bound on the number of objects the pool grows to.
+    #[must_use]
+    pub fn with_max_size(mut self, max_size: usize) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    /// Called on every spawned object, e.g. to reset component state left from its last use.
+    #[must_use]
+    pub fn on_spawn(mut self, hook: impl Fn(&mut GameObject) + Send + Sync + 'static) -> Self {
+        self.on_spawn = Some(Arc::new(hook));
+        self
+    }
+
+    pub fn template(&self) -> &GameObject {
+        &self.template
+    }
+
+    /// Number of released objects ready to be spawned.
+    pub fn available(&self) -> usize {
+        self.available.len()
+    }
+
+    /// Number of spawned objects that were not released yet.
+    pub fn in_use(&self) -> usize {
+        self.in_use.len()
+    }
+
+    pub fn size(&self) -> usize {
+        self.available.len() + self.in_use.len()
+    }
+
+    pub fn is_pooled(&self, id: GameObjectId) -> bool {
+        self.in_use.contains(&id) || self.available.contains(&id)
+    }
+
+    /// Number of objects to add once the pool is exhausted.
+    fn growth(&self) -> usize {
+        let count = match self.growth {
+            PoolGrowth::Fixed => 0,
+            PoolGrowth::Linear(count) => count,
+            PoolGrowth::Double => self.size().max(1),
+        };
+
+        self.max_size.map_or(count, |max| count.min(max.saturating_sub(self.size())))
+    }
+}
+
+/// Identifies a pool added with [Scene::add_pool].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PoolId(usize);
+
+/// Pools of a scene, together with the pool each pooled object belongs to.
+#[derive(Debug, Default)]
+pub struct Pools {
+    pools: Vec<GameObjectPool>,
+    owners: HashMap<GameObjectId, PoolId>,
+}
+
+impl Scene {
+    /// Adds the pool and instantiates its initial capacity as disabled objects.
+    pub fn add_pool(&mut self, pool: GameObjectPool) -> PoolId {
+        let id = PoolId(self.pools.pools.len());
+        let capacity = pool.capacity;
+        self.pools.pools.push(pool);
+        self.grow_pool(id, capacity);
+        id
+    }
+
+    pub fn pool(&self, id: PoolId) -> Option<&GameObjectPool> {
+        self.pools.pools.get(id.0)
+    }
+
+    fn grow_pool(&mut self, id: PoolId, count: usize) {
+        for _ in 0..count {
+            let mut object = self.pools.pools[id.0].template.clone();
+            object.disable();
+
+            let object = self.add_object(object);
+            self.pools.pools[id.0].available.push(object);
+            self.pools.owners.insert(object, id);
+        }
+    }
+
+    /// Enables a released object of the pool at `transform`, growing the pool if needed.
+    ///
+    /// Returns `None` if the pool is exhausted and cannot grow.
+    pub fn spawn_pooled(&mut self, pool: PoolId, transform: Transform) -> Option<GameObjectId> {
+        let id = loop {
+            let entry = self.pools.pools.get_mut(pool.0)?;
+
+            match entry.available.pop() {
+                // Objects removed from the scene meanwhile are dropped from the pool.
+                Some(id) if self.objects.contains_key(&id) => break id,
+                Some(id) => {
+                    self.pools.owners.remove(&id);
+                }
+                None => match entry.growth() {
+                    0 => return None,
+                    count => self.grow_pool(pool, count),
+                },
+            }
+        };
+
+        let entry = &mut self.pools.pools[pool.0];
+        let object = self.objects.get_mut(&id)?;
+        entry.in_use.insert(id);
+        object.transform = transform;
+        object.enable();
+
+        if let Some(hook) = &entry.on_spawn {
+            hook(object);
+        }
+
+        Some(id)
+    }
+
+    /// Disables a spawned object and returns it to its pool, cancelling the tasks it owns.
+    ///
+    /// Returns `false` if the object is not pooled or was already released.
+    pub fn release_pooled(&mut self, id: GameObjectId) -> bool {
+        let Some(&pool) = self.pools.owners.get(&id) else {
+            return false;
+        };
+
+        let entry = &mut self.pools.pools[pool.0];
+        if !entry.in_use.remove(&id) {
+            return false;
+        }
+
+        let Some(object) = self.objects.get_mut(&id) else {
+            self.pools.owners.remove(&id);
+            return false;
+        };
+
+        object.disable();
+        entry.available.push(id);
+        self.cancel_owned_tasks(id);
+        true
+    }
+}
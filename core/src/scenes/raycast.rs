@@ -0,0 +1,107 @@
+use crate::{
+    impl_component,
+    scenes::{Any, Bounds, Component, GameObject, GameObjectId, LayerMask, Scene},
+};
+use ciri_math::{Aabb, Ray, Vec3, to_glam_vec};
+use std::{fmt::Debug, sync::Arc};
+use three_d::CpuMesh;
+
+/// Triangles of a mesh in object space, so raycasts hit the exact surface instead of the bounds.
+#[derive(Clone)]
+pub struct MeshCollider {
+    triangles: Arc<[[Vec3; 3]]>,
+    bounds: Aabb,
+}
+
+impl MeshCollider {
+    pub fn new(mesh: &CpuMesh) -> Self {
+        let positions: Vec<Vec3> = mesh.positions.to_f32().into_iter().map(to_glam_vec).collect();
+        let mut triangles = Vec::with_capacity(mesh.triangle_count());
+        mesh.for_each_triangle(|a, b, c| {
+            triangles.push([positions[a], positions[b], positions[c]]);
+        });
+
+        let bounds = positions
+            .iter()
+            .map(|position| Aabb::from_point(*position))
+            .reduce(|bounds, point| bounds.union(&point))
+            .unwrap_or(Aabb::from_point(Vec3::ZERO));
+
+        Self { triangles: triangles.into(), bounds }
+    }
+
+    /// Bounds of the mesh in object space.
+    pub fn bounds(&self) -> Aabb {
+        self.bounds
+    }
+}
+
+impl_component!(MeshCollider);
+
+/// An object hit by a ray, see [Scene::raycast].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RaycastHit {
+    pub object: GameObjectId,
+    pub distance: f32,
+    pub point: Vec3,
+    /// Normal of the surface hit, facing the ray.
+    pub normal: Vec3,
+}
+
+/// Distance and normal of the closest hit on the object.
+///
+/// Objects are hit at their [MeshCollider] triangles if present, otherwise at their [Bounds] or
+/// the [bounding box of what they draw](GameObject::rendered_bounds).
+fn intersect(object: &GameObject, ray: &Ray) -> Option<(f32, Vec3)> {
+    if let Some(collider) = object.get_component::<MeshCollider>() {
+        ray.intersect_aabb(&collider.bounds.transformed(&object.transform))?;
+
+        let matrix = object.transform.to_matrix();
+        return collider
+            .triangles
+            .iter()
+            .filter_map(|triangle| {
+                ray.intersect_triangle(triangle.map(|corner| matrix.transform_point3(corner)))
+            })
+            .min_by(|(a, _), (b, _)| a.total_cmp(b));
+    }
+
+    if object.has_component::<Bounds>() {
+        return ray.intersect_aabb(&object.world_bounds());
+    }
+
+    ray.intersect_aabb(&object.rendered_bounds()?)
+}
+
+impl Scene {
+    /// Every active object on one of the `layers` hit by the ray, closest first.
+    ///
+    /// Objects are found through the [spatial index](Scene::spatial), so objects moved, added or
+    /// removed since the last update are only hit after [Scene::update_spatial_index].
+    pub fn raycast(&self, ray: &Ray, layers: LayerMask) -> Vec<RaycastHit> {
+        let mut hits: Vec<RaycastHit> = self
+            .spatial
+            .query_ray(ray)
+            .into_iter()
+            .filter_map(|id| {
+                let object = self.objects.get(&id)?;
+                if !object.is_active() || !object.layers().intersects(layers) {
+                    return None;
+                }
+
+                let (distance, normal) = intersect(object, ray)?;
+                Some(RaycastHit { object: id, distance, point: ray.at(distance), normal })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| {
+            a.distance.total_cmp(&b.distance).then(a.object.index().cmp(&b.object.index()))
+        });
+        hits
+    }
+
+    /// Closest object on one of the `layers` hit by the ray.
+    pub fn raycast_closest(&self, ray: &Ray, layers: LayerMask) -> Option<RaycastHit> {
+        self.raycast(ray, layers).into_iter().next()
+    }
+}
@@ -0,0 +1,171 @@
+use crate::scenes::{Commands, GameObjectId, Scene};
+use std::{
+    fmt::Debug,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::{Context, Poll, Waker},
+};
+use three_d::Event;
+
+enum WaitCondition {
+    Seconds(f32),
+    Frames(u64),
+    Until(Box<dyn FnMut(&Scene) -> bool + Send + Sync>),
+    Event(Box<dyn FnMut(&Event) -> bool + Send + Sync>),
+}
+
+impl WaitCondition {
+    fn is_met(&mut self, scene: &Scene) -> bool {
+        match self {
+            Self::Seconds(remaining) => {
+                *remaining -= scene.time.delta();
+                *remaining <= 0.0
+            }
+            Self::Frames(frames) => {
+                *frames = frames.saturating_sub(1);
+                *frames == 0
+            }
+            Self::Until(condition) => condition(scene),
+            Self::Event(matches) => {
+                scene.frame.as_ref().is_some_and(|frame| frame.input.events.iter().any(matches))
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct CoroutineState {
+    wait: Option<WaitCondition>,
+    commands: Commands,
+}
+
+/// Handle a coroutine uses to wait and to change the scene, see [Scene::start_coroutine].
+#[derive(Clone)]
+pub struct Coroutine {
+    owner: Option<GameObjectId>,
+    state: Arc<Mutex<CoroutineState>>,
+}
+
+impl Debug for Coroutine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Coroutine").field("owner", &self.owner).finish_non_exhaustive()
+    }
+}
+
+impl Coroutine {
+    pub(crate) fn new(owner: Option<GameObjectId>) -> Self {
+        Self { owner, state: Arc::default() }
+    }
+
+    fn state(&self) -> MutexGuard<'_, CoroutineState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn wait(&self, condition: WaitCondition) -> Wait {
+        Wait { coroutine: self.clone(), condition: Some(condition) }
+    }
+
+    /// Object the coroutine was started for.
+    pub fn owner(&self) -> Option<GameObjectId> {
+        self.owner
+    }
+
+    /// Waits for `seconds` of scaled time.
+    pub fn wait_seconds(&self, seconds: f32) -> Wait {
+        self.wait(WaitCondition::Seconds(seconds))
+    }
+
+    /// Waits for `frames` scheduler runs, at least one.
+    pub fn wait_frames(&self, frames: u64) -> Wait {
+        self.wait(WaitCondition::Frames(frames))
+    }
+
+    /// Waits until `condition` holds, checked once per frame.
+    pub fn wait_until(
+        &self,
+        condition: impl FnMut(&Scene) -> bool + Send + Sync + 'static,
+    ) -> Wait {
+        self.wait(WaitCondition::Until(Box::new(condition)))
+    }
+
+    /// Waits for a frame with an input event matching `matches`.
+    pub fn wait_for_event(
+        &self,
+        matches: impl FnMut(&Event) -> bool + Send + Sync + 'static,
+    ) -> Wait {
+        self.wait(WaitCondition::Event(Box::new(matches)))
+    }
+
+    /// Changes the scene once the coroutine suspends.
+    pub fn run(&self, command: impl FnOnce(&mut Scene) + Send + 'static) {
+        self.state().commands.add(command);
+    }
+}
+
+/// Future returned by the waits of [Coroutine], completing once the condition is met.
+pub struct Wait {
+    coroutine: Coroutine,
+    condition: Option<WaitCondition>,
+}
+
+impl Debug for Wait {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Wait").field("coroutine", &self.coroutine).finish_non_exhaustive()
+    }
+}
+
+impl Future for Wait {
+    type Output = ();
+
+    /// The condition is handed to the scheduler on the first poll. The scheduler only polls the
+    /// coroutine again once the condition is met.
+    fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+
+        match this.condition.take() {
+            Some(condition) => {
+                this.coroutine.state().wait = Some(condition);
+                Poll::Pending
+            }
+            None => Poll::Ready(()),
+        }
+    }
+}
+
+pub struct CoroutineTask {
+    coroutine: Coroutine,
+    // Only accessed through `&mut`, the mutex just makes the future `Sync`.
+    future: Mutex<Pin<Box<dyn Future<Output = ()> + Send>>>,
+}
+
+impl CoroutineTask {
+    pub(crate) fn new(
+        coroutine: Coroutine,
+        future: impl Future<Output = ()> + Send + 'static,
+    ) -> Self {
+        Self { coroutine, future: Mutex::new(Box::pin(future)) }
+    }
+
+    /// Polls the coroutine if the condition it waits for is met, returning whether it is still
+    /// running.
+    pub(crate) fn resume(&mut self, scene: &mut Scene) -> bool {
+        {
+            let mut state = self.coroutine.state();
+            if let Some(condition) = &mut state.wait {
+                if !condition.is_met(scene) {
+                    return true;
+                }
+
+                state.wait = None;
+            }
+        }
+
+        let future = self.future.get_mut().unwrap_or_else(PoisonError::into_inner);
+        let poll = future.as_mut().poll(&mut Context::from_waker(Waker::noop()));
+
+        let commands = std::mem::take(&mut self.coroutine.state().commands);
+        commands.apply(scene);
+
+        poll.is_pending()
+    }
+}
@@ -0,0 +1,245 @@
+mod coroutine;
+
+use coroutine::CoroutineTask;
+pub use coroutine::{Coroutine, Wait};
+
+use crate::scenes::{GameObjectId, Scene};
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Identifies a timer or coroutine scheduled on a scene.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TaskId(u64);
+
+impl TaskId {
+    /// Ids are unique across scenes, so they can be handed out before the task reaches its scene.
+    pub(crate) fn next() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+type OnceCallback = Box<dyn FnOnce(&mut Scene) + Send + Sync>;
+type RepeatingCallback = Box<dyn FnMut(&mut Scene) + Send + Sync>;
+
+enum TaskKind {
+    Once(Option<OnceCallback>),
+    Repeating { interval: f32, callback: RepeatingCallback },
+    Coroutine(CoroutineTask),
+}
+
+struct Task {
+    owner: Option<GameObjectId>,
+    /// Time left until a timer fires.
+    remaining: f32,
+    kind: TaskKind,
+}
+
+impl Task {
+    /// Runs the task for one frame, returning whether it is still alive.
+    #[expect(clippy::while_float, reason = "the remaining time is not a loop counter")]
+    fn run(&mut self, scene: &mut Scene) -> bool {
+        if let TaskKind::Coroutine(coroutine) = &mut self.kind {
+            return coroutine.resume(scene);
+        }
+
+        self.remaining -= scene.time.delta();
+
+        while self.remaining <= 0.0 {
+            match &mut self.kind {
+                TaskKind::Once(callback) => {
+                    if let Some(callback) = callback.take() {
+                        callback(scene);
+                    }
+                    return false;
+                }
+                TaskKind::Repeating { interval, callback } => {
+                    callback(scene);
+
+                    if scene.scheduler.cancel_running {
+                        return false;
+                    }
+
+                    // Intervals of zero fire once per frame instead of looping forever.
+                    if *interval <= 0.0 {
+                        self.remaining = 0.0;
+                        break;
+                    }
+
+                    self.remaining += *interval;
+                }
+                TaskKind::Coroutine(_) => unreachable!("coroutines are resumed above"),
+            }
+        }
+
+        true
+    }
+}
+
+/// Timers and coroutines of a scene, advanced once per [Scene::update].
+#[derive(Default)]
+pub struct Scheduler {
+    tasks: BTreeMap<TaskId, Task>,
+    running: Option<TaskId>,
+    running_owner: Option<GameObjectId>,
+    cancel_running: bool,
+}
+
+impl Debug for Scheduler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Scheduler").field("tasks", &self.tasks.len()).finish_non_exhaustive()
+    }
+}
+
+impl Scene {
+    fn schedule(
+        &mut self,
+        id: TaskId,
+        owner: Option<GameObjectId>,
+        remaining: f32,
+        kind: TaskKind,
+    ) {
+        self.scheduler.tasks.insert(id, Task { owner, remaining, kind });
+    }
+
+    /// Calls `callback` once after `seconds` of scaled time.
+    ///
+    /// A task with an `owner` is cancelled when the owner is removed from the scene.
+    pub fn after(
+        &mut self,
+        owner: Option<GameObjectId>,
+        seconds: f32,
+        callback: impl FnOnce(&mut Scene) + Send + Sync + 'static,
+    ) -> TaskId {
+        let id = TaskId::next();
+        self.schedule_once(id, owner, seconds, callback);
+        id
+    }
+
+    /// Calls `callback` every `seconds` of scaled time, until the task is cancelled.
+    ///
+    /// A task with an `owner` is cancelled when the owner is removed from the scene.
+    pub fn every(
+        &mut self,
+        owner: Option<GameObjectId>,
+        seconds: f32,
+        callback: impl FnMut(&mut Scene) + Send + Sync + 'static,
+    ) -> TaskId {
+        let id = TaskId::next();
+        self.schedule_repeating(id, owner, seconds, callback);
+        id
+    }
+
+    /// Starts a coroutine, first resumed on the next scheduler run.
+    ///
+    /// ```ignore
+    /// scene.start_coroutine(Some(door), |co| async move {
+    ///     co.wait_seconds(2.0).await;
+    ///     co.run(move |scene| scene.remove_object(door));
+    /// });
+    /// ```
+    ///
+    /// A coroutine with an `owner` is cancelled when the owner is removed from the scene.
+    pub fn start_coroutine<Fut: Future<Output = ()> + Send + 'static>(
+        &mut self,
+        owner: Option<GameObjectId>,
+        coroutine: impl FnOnce(Coroutine) -> Fut,
+    ) -> TaskId {
+        let id = TaskId::next();
+        self.schedule_coroutine(id, owner, coroutine);
+        id
+    }
+
+    pub(crate) fn schedule_once(
+        &mut self,
+        id: TaskId,
+        owner: Option<GameObjectId>,
+        seconds: f32,
+        callback: impl FnOnce(&mut Scene) + Send + Sync + 'static,
+    ) {
+        self.schedule(id, owner, seconds, TaskKind::Once(Some(Box::new(callback))));
+    }
+
+    pub(crate) fn schedule_repeating(
+        &mut self,
+        id: TaskId,
+        owner: Option<GameObjectId>,
+        seconds: f32,
+        callback: impl FnMut(&mut Scene) + Send + Sync + 'static,
+    ) {
+        let kind = TaskKind::Repeating { interval: seconds, callback: Box::new(callback) };
+        self.schedule(id, owner, seconds, kind);
+    }
+
+    pub(crate) fn schedule_coroutine<Fut: Future<Output = ()> + Send + 'static>(
+        &mut self,
+        id: TaskId,
+        owner: Option<GameObjectId>,
+        coroutine: impl FnOnce(Coroutine) -> Fut,
+    ) {
+        let handle = Coroutine::new(owner);
+        let task = CoroutineTask::new(handle.clone(), coroutine(handle));
+        self.schedule(id, owner, 0.0, TaskKind::Coroutine(task));
+    }
+
+    /// Cancels a timer or coroutine, returning whether it was scheduled.
+    pub fn cancel_task(&mut self, id: TaskId) -> bool {
+        if self.scheduler.running == Some(id) {
+            self.scheduler.cancel_running = true;
+            return true;
+        }
+
+        self.scheduler.tasks.remove(&id).is_some()
+    }
+
+    /// Cancels every timer and coroutine owned by the object.
+    pub(crate) fn cancel_owned_tasks(&mut self, owner: GameObjectId) {
+        self.scheduler.tasks.retain(|_, task| task.owner != Some(owner));
+
+        if self.scheduler.running_owner == Some(owner) {
+            self.scheduler.cancel_running = true;
+        }
+    }
+
+    /// Whether the timer or coroutine is still waiting to run.
+    pub fn is_task_scheduled(&self, id: TaskId) -> bool {
+        self.scheduler.tasks.contains_key(&id)
+            || (self.scheduler.running == Some(id) && !self.scheduler.cancel_running)
+    }
+
+    /// Advances the timers and resumes the coroutines in the order they were scheduled. Tasks
+    /// scheduled meanwhile first run on the next call.
+    ///
+    /// Nothing runs while the scene time is paused.
+    pub(crate) fn run_scheduler(&mut self) {
+        if self.time.is_paused() {
+            return;
+        }
+
+        let ids: Vec<TaskId> = self.scheduler.tasks.keys().copied().collect();
+
+        for id in ids {
+            let Some(mut task) = self.scheduler.tasks.remove(&id) else {
+                continue;
+            };
+
+            if task.owner.is_some_and(|owner| !self.objects.contains_key(&owner)) {
+                continue;
+            }
+
+            self.scheduler.running = Some(id);
+            self.scheduler.running_owner = task.owner;
+            self.scheduler.cancel_running = false;
+            let alive = task.run(self);
+            self.scheduler.running = None;
+            self.scheduler.running_owner = None;
+
+            if alive && !self.scheduler.cancel_running {
+                self.scheduler.tasks.insert(id, task);
+            }
+        }
+    }
+}
@@ -0,0 +1,305 @@
+use crate::{
+    impl_component,
+    scenes::{
+        Any, Component, GameObject, GameObjectId, MeshCollider, Scene, components::MeshRenderer,
+    },
+};
+use ciri_math::{Aabb, Frustum, Ray, Sphere, Vec3};
+use std::{collections::HashMap, fmt::Debug};
+
+/// Local bounds of an object, indexed by the [SpatialIndex] in place of the object's position.
+#[derive(Clone, Copy)]
+pub struct Bounds {
+    pub local: Aabb,
+}
+
+impl Bounds {
+    pub fn new(local: Aabb) -> Self {
+        Self { local }
+    }
+
+    /// Box of the given size centered on the object.
+    pub fn from_size(size: Vec3) -> Self {
+        Self { local: Aabb::from_center(Vec3::ZERO, size * 0.5) }
+    }
+}
+
+impl_component!(Bounds);
+
+impl GameObject {
+    /// World space bounds of the object, from its [Bounds], [MeshCollider] or else the bounding
+    /// box of what it draws, and a point at its position without any.
+    pub fn world_bounds(&self) -> Aabb {
+        let local = self
+            .get_component::<Bounds>()
+            .map(|bounds| bounds.local)
+            .or_else(|| self.get_component::<MeshCollider>().map(MeshCollider::bounds));
+        if let Some(local) = local {
+            return local.transformed(&self.transform);
+        }
+
+        self.rendered_bounds().unwrap_or_else(|| Aabb::from_point(self.transform.translation))
+    }
+
+    /// World space bounding box of the [renderer](GameObject::renderer) or [MeshRenderer] of the
+    /// object, `None` without one or if its bounds are empty or infinite.
+    pub fn rendered_bounds(&self) -> Option<Aabb> {
+        if let Some(renderer) = self.renderer() {
+            return renderer.world_aabb_at(&self.transform);
+        }
+
+        let bounds = self.get_component::<MeshRenderer>()?.bounds()?;
+        Some(bounds.transformed(&self.transform))
+    }
+}
+
+/// Objects stored in a leaf before it is split.
+const LEAF_SIZE: usize = 4;
+
+#[derive(Debug, Clone, Copy)]
+enum NodeKind {
+    Leaf {
+        start: usize,
+        end: usize,
+    },
+    /// The left child directly follows its parent.
+    Internal {
+        right: usize,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    bounds: Aabb,
+    kind: NodeKind,
+}
+
+/// Bounding volume hierarchy over the bounds of the active objects of a scene.
+///
+/// The index is refreshed at the end of every [Scene::update]. Objects moved, added or removed
+/// since then are only reflected after [Scene::update_spatial_index].
+#[derive(Debug, Default)]
+pub struct SpatialIndex {
+    nodes: Vec<Node>,
+    entries: Vec<(GameObjectId, Aabb)>,
+    positions: HashMap<GameObjectId, usize>,
+}
+
+impl SpatialIndex {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Indexed bounds of the object.
+    pub fn bounds(&self, id: GameObjectId) -> Option<Aabb> {
+        self.positions.get(&id).map(|&position| self.entries[position].1)
+    }
+
+    /// Objects whose bounds intersect the box.
+    pub fn query_aabb(&self, aabb: &Aabb) -> Vec<GameObjectId> {
+        self.query(|bounds| bounds.intersects(aabb))
+    }
+
+    /// Objects whose bounds intersect the sphere.
+    pub fn query_sphere(&self, center: Vec3, radius: f32) -> Vec<GameObjectId> {
+        let sphere = Sphere::new(center, radius);
+        self.query(|bounds| bounds.intersects_sphere(&sphere))
+    }
+
+    /// Objects whose bounds are hit by the ray.
+    pub fn query_ray(&self, ray: &Ray) -> Vec<GameObjectId> {
+        self.query(|bounds| ray.intersect_aabb(bounds).is_some())
+    }
+
+    /// Objects whose bounds are at least partly inside the frustum, e.g. [Camera::frustum].
+    ///
+    /// [Camera::frustum]: crate::camera::Camera::frustum
+    pub fn query_frustum(&self, frustum: &Frustum) -> Vec<GameObjectId> {
+        self.query(|bounds| frustum.intersects_aabb(bounds))
+    }
+
+    /// Up to `k` objects closest to the point, nearest first. Distances are measured to the
+    /// bounds, so every object containing the point is at distance zero.
+    pub fn nearest_k(&self, point: Vec3, k: usize) -> Vec<GameObjectId> {
+        let mut nearest: Vec<(f32, GameObjectId)> = Vec::with_capacity(k + 1);
+        if k == 0 || self.nodes.is_empty() {
+            return Vec::new();
+        }
+
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            let worst = if nearest.len() == k { nearest[k - 1].0 } else { f32::INFINITY };
+            if node.bounds.distance_squared(point) > worst {
+                continue;
+            }
+
+            match node.kind {
+                NodeKind::Leaf { start, end } => {
+                    for &(id, bounds) in &self.entries[start..end] {
+                        let distance = bounds.distance_squared(point);
+                        let position = nearest.partition_point(|(other, _)| *other <= distance);
+                        if position < k {
+                            nearest.insert(position, (distance, id));
+                            nearest.truncate(k);
+                        }
+                    }
+                }
+                NodeKind::Internal { right } => {
+                    let left = index + 1;
+                    let left_distance = self.nodes[left].bounds.distance_squared(point);
+                    let right_distance = self.nodes[right].bounds.distance_squared(point);
+
+                    // The closer child is pushed last so it is visited first.
+                    if left_distance <= right_distance {
+                        stack.extend([right, left]);
+                    } else {
+                        stack.extend([left, right]);
+                    }
+                }
+            }
+        }
+
+        nearest.into_iter().map(|(_, id)| id).collect()
+    }
+
+    fn query(&self, overlaps: impl Fn(&Aabb) -> bool) -> Vec<GameObjectId> {
+        let mut found = Vec::new();
+        let mut stack = if self.nodes.is_empty() { Vec::new() } else { vec![0] };
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !overlaps(&node.bounds) {
+                continue;
+            }
+
+            match node.kind {
+                NodeKind::Leaf { start, end } => found.extend(
+                    self.entries[start..end]
+                        .iter()
+                        .filter(|(_, bounds)| overlaps(bounds))
+                        .map(|(id, _)| *id),
+                ),
+                NodeKind::Internal { right } => stack.extend([right, index + 1]),
+            }
+        }
+
+        found
+    }
+
+    /// Refits the hierarchy when the same objects are indexed as before, otherwise rebuilds it.
+    pub(crate) fn sync(&mut self, objects: &HashMap<GameObjectId, GameObject>) {
+        let mut current: Vec<(GameObjectId, Aabb)> = objects
+            .iter()
+            .filter(|(_, object)| object.is_active())
+            .map(|(id, object)| (*id, object.world_bounds()))
+            .collect();
+
+        let unchanged = current.len() == self.entries.len()
+            && current.iter().all(|(id, _)| self.positions.contains_key(id));
+
+        if unchanged {
+            for (id, bounds) in current {
+                self.entries[self.positions[&id]].1 = bounds;
+            }
+            self.refit();
+        } else {
+            current.sort_unstable_by_key(|(id, _)| id.index());
+            self.entries = current;
+            self.rebuild();
+        }
+    }
+
+    fn rebuild(&mut self) {
+        self.nodes.clear();
+        if !self.entries.is_empty() {
+            self.build(0, self.entries.len());
+        }
+
+        self.positions =
+            self.entries.iter().enumerate().map(|(position, (id, _))| (*id, position)).collect();
+    }
+
+    /// Builds the subtree over `entries[start..end]`, returning the index of its root.
+    fn build(&mut self, start: usize, end: usize) -> usize {
+        let bounds = Self::union(&self.entries[start..end]);
+        let index = self.nodes.len();
+        self.nodes.push(Node { bounds, kind: NodeKind::Leaf { start, end } });
+
+        if end - start <= LEAF_SIZE {
+            return index;
+        }
+
+        // Splits at the median along the axis the centers spread the most.
+        let size = self.entries[start..end]
+            .iter()
+            .map(|(_, bounds)| Aabb::from_point(bounds.center()))
+            .reduce(|spread, center| spread.union(&center))
+            .expect("nodes are never empty")
+            .size();
+        let axis = if size.x >= size.y && size.x >= size.z {
+            0
+        } else if size.y >= size.z {
+            1
+        } else {
+            2
+        };
+
+        let middle = (end - start) / 2;
+        self.entries[start..end].select_nth_unstable_by(middle, |(_, a), (_, b)| {
+            a.center()[axis].total_cmp(&b.center()[axis])
+        });
+
+        self.build(start, start + middle);
+        let right = self.build(start + middle, end);
+        self.nodes[index].kind = NodeKind::Internal { right };
+        index
+    }
+
+    /// Recomputes the node bounds from the entries. Children always follow their parent, so
+    /// walking backwards visits children first.
+    fn refit(&mut self) {
+        for index in (0..self.nodes.len()).rev() {
+            self.nodes[index].bounds = match self.nodes[index].kind {
+                NodeKind::Leaf { start, end } => Self::union(&self.entries[start..end]),
+                NodeKind::Internal { right } => {
+                    self.nodes[index + 1].bounds.union(&self.nodes[right].bounds)
+                }
+            };
+        }
+    }
+
+    fn union(entries: &[(GameObjectId, Aabb)]) -> Aabb {
+        entries
+            .iter()
+            .map(|(_, bounds)| *bounds)
+            .reduce(|union, bounds| union.union(&bounds))
+            .expect("nodes are never empty")
+    }
+}
+
+impl Scene {
+    pub fn spatial(&self) -> &SpatialIndex {
+        &self.spatial
+    }
+
+    /// Brings the [SpatialIndex] in sync with the objects, which [Scene::update] does at its end.
+    pub fn update_spatial_index(&mut self) {
+        self.spatial.sync(&self.objects);
+    }
+
+    /// Objects within `radius` of the object, excluding the object itself.
+    pub fn objects_near(&self, id: GameObjectId, radius: f32) -> Vec<GameObjectId> {
+        let Some(object) = self.objects.get(&id) else {
+            return Vec::new();
+        };
+
+        let mut near = self.spatial.query_sphere(object.transform.translation, radius);
+        near.retain(|other| *other != id);
+        near
+    }
+}
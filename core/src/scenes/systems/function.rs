@@ -0,0 +1,48 @@
+use crate::scenes::systems::{SystemContext, SystemParam};
+
+/// A function that can run as a system. Implemented for functions taking up to eight
+/// [SystemParam]s.
+///
+/// `Marker` only distinguishes the implementations for different parameter lists.
+pub trait SystemFunction<Marker>: Send + Sync + 'static {
+    fn run(&mut self, ctx: &SystemContext, name: &'static str);
+}
+
+macro_rules! impl_system_function {
+    ($($param:ident $value:ident),*) => {
+        impl<Func, $($param: SystemParam),*> SystemFunction<fn($($param,)*)> for Func
+        where
+            Func: Send + Sync + 'static,
+            for<'a> &'a mut Func: FnMut($($param),*) + FnMut($(<$param as SystemParam>::Item<'_>),*),
+        {
+            fn run(&mut self, ctx: &SystemContext, name: &'static str) {
+                // Calling through a generic function lets the compiler pick the `FnMut` bound
+                // matching the fetched items.
+                fn call<$($param),*>(mut function: impl FnMut($($param),*), $($value: $param),*) {
+                    function($($value),*);
+                }
+
+                $(let $value = $param::fetch(ctx, name);)*
+                call(self, $($value),*);
+            }
+        }
+    };
+}
+
+impl<Func> SystemFunction<fn()> for Func
+where
+    Func: FnMut() + Send + Sync + 'static,
+{
+    fn run(&mut self, _: &SystemContext, _: &'static str) {
+        self();
+    }
+}
+
+impl_system_function!(A a);
+impl_system_function!(A a, B b);
+impl_system_function!(A a, B b, C c);
+impl_system_function!(A a, B b, C c, D d);
+impl_system_function!(A a, B b, C c, D d, E e);
+impl_system_function!(A a, B b, C c, D d, E e, F f);
+impl_system_function!(A a, B b, C c, D d, E e, F f, G g);
+impl_system_function!(A a, B b, C c, D d, E e, F f, G g, H h);
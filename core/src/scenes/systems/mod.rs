@@ -0,0 +1,508 @@
+mod function;
+mod query;
+
+pub use function::*;
+pub use query::*;
+
+use crate::{
+    resources::{ResourceCells, Resources},
+    scenes::{Commands, GameObject, GameObjectId, Scene, Time},
+};
+use std::{
+    any::{Any, TypeId, type_name},
+    cell::{Ref, RefCell, RefMut},
+    collections::HashMap,
+    fmt::Debug,
+    mem,
+    ops::{Deref, DerefMut},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Point of [Scene::update] at which a system runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Stage {
+    /// Before components are updated.
+    PreUpdate,
+    /// After components are updated.
+    #[default]
+    Update,
+    /// After the [Stage::Update] systems ran.
+    PostUpdate,
+}
+
+/// Scene state the parameters of a system are fetched from.
+pub struct SystemContext {
+    pub(crate) objects: RefCell<HashMap<GameObjectId, GameObject>>,
+    pub(crate) commands: RefCell<Commands>,
+    pub(crate) time: Time,
+    pub(crate) resources: ResourceCells,
+    pub(crate) engine_resources: ResourceCells,
+}
+
+impl SystemContext {
+    /// Finds the cell of a resource, preferring the scene's resources over the engine's.
+    fn resource<T: Any>(&self) -> Option<&RefCell<Box<dyn Any + Send + Sync>>> {
+        self.resources.get::<T>().or_else(|| self.engine_resources.get::<T>())
+    }
+}
+
+/// A value a system function can take as a parameter.
+pub trait SystemParam {
+    type Item<'w>;
+
+    fn fetch<'w>(ctx: &'w SystemContext, system: &'static str) -> Self::Item<'w>;
+}
+
+impl SystemParam for Time {
+    type Item<'w> = Time;
+
+    fn fetch(ctx: &SystemContext, _: &'static str) -> Time {
+        ctx.time
+    }
+}
+
+/// System parameter giving access to the command buffer, applied at the end of the stage.
+pub struct SceneCommands<'w>(RefMut<'w, Commands>);
+
+impl Deref for SceneCommands<'_> {
+    type Target = Commands;
+
+    fn deref(&self) -> &Commands {
+        &self.0
+    }
+}
+
+impl DerefMut for SceneCommands<'_> {
+    fn deref_mut(&mut self) -> &mut Commands {
+        &mut self.0
+    }
+}
+
+impl SystemParam for SceneCommands<'_> {
+    type Item<'w> = SceneCommands<'w>;
+
+    fn fetch<'w>(ctx: &'w SystemContext, system: &'static str) -> SceneCommands<'w> {
+        let Ok(commands) = ctx.commands.try_borrow_mut() else {
+            panic!("system `{system}` takes the scene commands more than once");
+        };
+
+        SceneCommands(commands)
+    }
+}
+
+/// System parameter borrowing a resource, see [Scene::insert_resource].
+///
+/// Panics when fetched if the resource is missing, use `Option<Res<T>>` for optional resources.
+pub struct Res<'w, T: 'static>(Ref<'w, T>);
+
+impl<T> Deref for Res<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Any + Send + Sync> SystemParam for Option<Res<'_, T>> {
+    type Item<'w> = Option<Res<'w, T>>;
+
+    fn fetch<'w>(ctx: &'w SystemContext, system: &'static str) -> Option<Res<'w, T>> {
+        let Ok(resource) = ctx.resource::<T>()?.try_borrow() else {
+            panic!("system `{system}` borrows resource `{}` mutably elsewhere", type_name::<T>());
+        };
+
+        Some(Res(Ref::map(resource, |resource| {
+            resource.downcast_ref().expect("resources are keyed by their type")
+        })))
+    }
+}
+
+impl<T: Any + Send + Sync> SystemParam for Res<'_, T> {
+    type Item<'w> = Res<'w, T>;
+
+    fn fetch<'w>(ctx: &'w SystemContext, system: &'static str) -> Res<'w, T> {
+        Option::<Res<T>>::fetch(ctx, system).unwrap_or_else(|| {
+            panic!("system `{system}` requires missing resource `{}`", type_name::<T>())
+        })
+    }
+}
+
+/// System parameter mutably borrowing a resource, see [Scene::insert_resource].
+///
+/// Panics when fetched if the resource is missing, use `Option<ResMut<T>>` for optional resources.
+pub struct ResMut<'w, T: 'static>(RefMut<'w, T>);
+
+impl<T> Deref for ResMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for ResMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: Any + Send + Sync> SystemParam for Option<ResMut<'_, T>> {
+    type Item<'w> = Option<ResMut<'w, T>>;
+
+    fn fetch<'w>(ctx: &'w SystemContext, system: &'static str) -> Option<ResMut<'w, T>> {
+        let Ok(resource) = ctx.resource::<T>()?.try_borrow_mut() else {
+            panic!("system `{system}` borrows resource `{}` more than once", type_name::<T>());
+        };
+
+        Some(ResMut(RefMut::map(resource, |resource| {
+            resource.downcast_mut().expect("resources are keyed by their type")
+        })))
+    }
+}
+
+impl<T: Any + Send + Sync> SystemParam for ResMut<'_, T> {
+    type Item<'w> = ResMut<'w, T>;
+
+    fn fetch<'w>(ctx: &'w SystemContext, system: &'static str) -> ResMut<'w, T> {
+        Option::<ResMut<T>>::fetch(ctx, system).unwrap_or_else(|| {
+            panic!("system `{system}` requires missing resource `{}`", type_name::<T>())
+        })
+    }
+}
+
+trait System: Send + Sync {
+    fn run(&mut self, ctx: &SystemContext, name: &'static str);
+}
+
+struct FunctionSystem<F, M> {
+    function: F,
+    marker: std::marker::PhantomData<fn() -> M>,
+}
+
+impl<F: SystemFunction<M>, M: 'static> System for FunctionSystem<F, M> {
+    fn run(&mut self, ctx: &SystemContext, name: &'static str) {
+        self.function.run(ctx, name);
+    }
+}
+
+/// Identifies the function of a system, so it can be found again by passing the function.
+///
+/// Only fn items and closures have a type of their own, function pointers share theirs with
+/// every function of the same signature, so they get no key and are found by [SystemId] only.
+fn function_key<F: 'static>() -> Option<TypeId> {
+    (size_of::<F>() == 0).then(TypeId::of::<F>)
+}
+
+/// Id a system is registered under, see [Scene::register_system].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SystemId(u64);
+
+impl SystemId {
+    fn next() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// Finds a registered system: either its [SystemId] or the function it was registered with.
+pub trait SystemKey<Marker> {
+    fn position(self, systems: &Systems) -> Option<usize>;
+}
+
+impl<F: SystemFunction<M>, M> SystemKey<M> for F {
+    fn position(self, systems: &Systems) -> Option<usize> {
+        systems.position(function_key::<F>())
+    }
+}
+
+impl SystemKey<()> for SystemId {
+    fn position(self, systems: &Systems) -> Option<usize> {
+        systems.systems.iter().position(|system| system.id == self)
+    }
+}
+
+/// A system together with its stage, ordering and whether it is enabled.
+pub struct SystemConfig {
+    id: SystemId,
+    function: Option<TypeId>,
+    name: &'static str,
+    stage: Stage,
+    before: Vec<TypeId>,
+    after: Vec<TypeId>,
+    enabled: bool,
+    system: Box<dyn System>,
+}
+
+impl Debug for SystemConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SystemConfig")
+            .field("name", &self.name)
+            .field("stage", &self.stage)
+            .field("enabled", &self.enabled)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Converts a system function into a [SystemConfig], adding stage and ordering on the way.
+///
+/// ```ignore
+/// scene.add_system(move_enemies.in_stage(Stage::PreUpdate).after(spawn_enemies));
+/// ```
+pub trait IntoSystemConfig<Marker>: Sized {
+    fn into_config(self) -> SystemConfig;
+
+    #[must_use]
+    fn in_stage(self, stage: Stage) -> SystemConfig {
+        let mut config = self.into_config();
+        config.stage = stage;
+        config
+    }
+
+    /// Runs this system before `other` when both are in the same stage.
+    ///
+    /// Ignored if `other` is a function pointer.
+    #[must_use]
+    fn before<F: SystemFunction<M>, M>(self, _: F) -> SystemConfig {
+        let mut config = self.into_config();
+        config.before.extend(function_key::<F>());
+        config
+    }
+
+    /// Runs this system after `other` when both are in the same stage.
+    ///
+    /// Ignored if `other` is a function pointer.
+    #[must_use]
+    fn after<F: SystemFunction<M>, M>(self, _: F) -> SystemConfig {
+        let mut config = self.into_config();
+        config.after.extend(function_key::<F>());
+        config
+    }
+
+    /// Adds the system disabled, see [Scene::set_system_enabled].
+    #[must_use]
+    fn disabled(self) -> SystemConfig {
+        let mut config = self.into_config();
+        config.enabled = false;
+        config
+    }
+}
+
+impl<F: SystemFunction<M>, M: 'static> IntoSystemConfig<M> for F {
+    fn into_config(self) -> SystemConfig {
+        SystemConfig {
+            id: SystemId::next(),
+            function: function_key::<F>(),
+            name: type_name::<F>(),
+            stage: Stage::default(),
+            before: Vec::new(),
+            after: Vec::new(),
+            enabled: true,
+            system: Box::new(FunctionSystem { function: self, marker: std::marker::PhantomData }),
+        }
+    }
+}
+
+impl IntoSystemConfig<()> for SystemConfig {
+    fn into_config(self) -> SystemConfig {
+        self
+    }
+}
+
+/// Systems registered on a scene.
+#[derive(Default)]
+pub struct Systems {
+    systems: Vec<SystemConfig>,
+    order: HashMap<Stage, Vec<usize>>,
+}
+
+impl Debug for Systems {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(&self.systems).finish()
+    }
+}
+
+impl Systems {
+    fn position(&self, function: Option<TypeId>) -> Option<usize> {
+        let function = function?;
+        self.systems.iter().position(|system| system.function == Some(function))
+    }
+
+    fn add(&mut self, config: SystemConfig) -> SystemId {
+        let id = config.id;
+
+        if let Some(index) = self.position(config.function) {
+            self.systems[index] = config;
+        } else {
+            self.systems.push(config);
+        }
+
+        self.order.clear();
+        id
+    }
+
+    fn remove(&mut self, index: Option<usize>) -> bool {
+        let Some(index) = index else {
+            return false;
+        };
+
+        self.systems.remove(index);
+        self.order.clear();
+        true
+    }
+
+    /// Sorts the systems of the stage so every `before`/`after` constraint is met, keeping the
+    /// insertion order otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the constraints form a cycle.
+    fn order(&mut self, stage: Stage) -> Vec<usize> {
+        if let Some(order) = self.order.get(&stage) {
+            return order.clone();
+        }
+
+        let indices: Vec<usize> =
+            (0..self.systems.len()).filter(|&i| self.systems[i].stage == stage).collect();
+
+        // `edges[a]` holds the systems that must run after `a`.
+        let mut edges: HashMap<usize, Vec<usize>> = HashMap::new();
+        let mut incoming: HashMap<usize, usize> = indices.iter().map(|&i| (i, 0)).collect();
+
+        for &index in &indices {
+            let system = &self.systems[index];
+            let constraints = system
+                .before
+                .iter()
+                .map(|function| (index, *function, true))
+                .chain(system.after.iter().map(|function| (index, *function, false)));
+
+            for (index, function, before) in constraints {
+                let Some(other) =
+                    indices.iter().copied().find(|&i| self.systems[i].function == Some(function))
+                else {
+                    continue;
+                };
+
+                let (first, then) = if before { (index, other) } else { (other, index) };
+                edges.entry(first).or_default().push(then);
+                *incoming.entry(then).or_default() += 1;
+            }
+        }
+
+        let mut order = Vec::with_capacity(indices.len());
+        let mut ready: Vec<usize> = indices.iter().copied().filter(|i| incoming[i] == 0).collect();
+
+        while !ready.is_empty() {
+            let index = ready.remove(0);
+            order.push(index);
+
+            for &then in edges.get(&index).into_iter().flatten() {
+                let count = incoming.get_mut(&then).expect("every system has an incoming count");
+                *count -= 1;
+
+                if *count == 0 {
+                    let position = ready.partition_point(|&i| i < then);
+                    ready.insert(position, then);
+                }
+            }
+        }
+
+        if order.len() != indices.len() {
+            let cycle: Vec<&str> = indices
+                .iter()
+                .filter(|i| !order.contains(i))
+                .map(|&i| self.systems[i].name)
+                .collect();
+            panic!("systems in {stage:?} stage have cyclic ordering: {}", cycle.join(", "));
+        }
+
+        self.order.insert(stage, order.clone());
+        order
+    }
+}
+
+impl Scene {
+    /// Registers a system, replacing an earlier registration of the same function.
+    ///
+    /// Function pointers are never replaced, every registration of one adds a system.
+    pub fn add_system<M>(&mut self, system: impl IntoSystemConfig<M>) -> &mut Self {
+        self.register_system(system);
+        self
+    }
+
+    /// Like [Scene::add_system], but returns the id of the system, which finds it even if it was
+    /// registered as a function pointer.
+    pub fn register_system<M>(&mut self, system: impl IntoSystemConfig<M>) -> SystemId {
+        self.systems.add(system.into_config())
+    }
+
+    /// Removes the system, returning whether it was registered.
+    pub fn remove_system<M>(&mut self, system: impl SystemKey<M>) -> bool {
+        self.systems.remove(system.position(&self.systems))
+    }
+
+    /// Enables or disables the system, returning whether it is registered.
+    pub fn set_system_enabled<M>(&mut self, system: impl SystemKey<M>, enabled: bool) -> bool {
+        let Some(index) = system.position(&self.systems) else {
+            return false;
+        };
+
+        self.systems.systems[index].enabled = enabled;
+        true
+    }
+
+    /// Whether the system is enabled, or `None` if it is not registered.
+    pub fn is_system_enabled<M>(&self, system: impl SystemKey<M>) -> Option<bool> {
+        let index = system.position(&self.systems)?;
+        Some(self.systems.systems[index].enabled)
+    }
+
+    /// Runs the enabled systems of the stage, then applies their commands.
+    pub fn run_systems(&mut self, stage: Stage) {
+        let order = self.systems.order(stage);
+        if order.iter().all(|&i| !self.systems.systems[i].enabled) {
+            return;
+        }
+
+        let lent = Lent {
+            ctx: SystemContext {
+                objects: RefCell::new(mem::take(&mut self.objects)),
+                commands: RefCell::new(Commands::new()),
+                time: self.time,
+                resources: mem::take(&mut self.resources).into_cells(),
+                engine_resources: mem::take(&mut self.engine_resources).into_cells(),
+            },
+            objects: &mut self.objects,
+            resources: &mut self.resources,
+            engine_resources: &mut self.engine_resources,
+        };
+
+        for index in order {
+            let system = &mut self.systems.systems[index];
+            if system.enabled {
+                system.system.run(&lent.ctx, system.name);
+            }
+        }
+
+        let commands = lent.ctx.commands.take();
+        drop(lent);
+        commands.apply(self);
+    }
+}
+
+/// Gives the objects and resources lent to the systems back to the scene when dropped, even if a
+/// system panics.
+struct Lent<'s> {
+    ctx: SystemContext,
+    objects: &'s mut HashMap<GameObjectId, GameObject>,
+    resources: &'s mut Resources,
+    engine_resources: &'s mut Resources,
+}
+
+impl Drop for Lent<'_> {
+    fn drop(&mut self) {
+        *self.objects = self.ctx.objects.take();
+        *self.resources = mem::take(&mut self.ctx.resources).into_resources();
+        *self.engine_resources = mem::take(&mut self.ctx.engine_resources).into_resources();
+    }
+}
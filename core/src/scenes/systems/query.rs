@@ -0,0 +1,452 @@
+use crate::scenes::{
+    GameObject, GameObjectId,
+    components::Component,
+    systems::{SystemContext, SystemParam},
+};
+use ciri_math::Transform;
+use std::{
+    any::TypeId,
+    cell::{Ref, RefMut},
+    collections::HashMap,
+    marker::PhantomData,
+};
+
+type ObjectMap = HashMap<GameObjectId, GameObject>;
+
+/// Orders the objects by id, like [crate::scenes::Scene::update] does.
+fn by_id<'a, T>(objects: impl Iterator<Item = (&'a GameObjectId, T)>) -> impl Iterator<Item = T> {
+    let mut objects: Vec<_> = objects.collect();
+    objects.sort_unstable_by_key(|(id, _)| id.index());
+    objects.into_iter().map(|(_, object)| object)
+}
+
+/// The parts of an object a [QueryElement] is fetched from when the query has mutable access.
+pub struct Source<'a> {
+    id: Option<GameObjectId>,
+    transform: Option<&'a mut Transform>,
+}
+
+/// The parts of an object a [QueryElement] is fetched from when the query is read-only.
+pub struct SourceRef<'a> {
+    id: Option<GameObjectId>,
+    transform: &'a Transform,
+}
+
+/// A single value fetched from an object by a [Query], such as `&T`, `&mut T` or `&Transform`.
+pub trait QueryElement {
+    type Item<'a>;
+
+    /// Whether the element borrows the object mutably.
+    const MUTABLE: bool = false;
+
+    /// Component type the element reads, if any.
+    fn component() -> Option<TypeId> {
+        None
+    }
+
+    fn fetch<'a>(
+        source: &mut Source<'a>,
+        slot: Option<&'a mut Box<dyn Component>>,
+    ) -> Option<Self::Item<'a>>;
+
+    /// Fetches the element through a shared borrow. Only called for elements that are not
+    /// [QueryElement::MUTABLE].
+    fn fetch_ref<'a>(
+        source: &SourceRef<'a>,
+        slot: Option<&'a dyn Component>,
+    ) -> Option<Self::Item<'a>>;
+}
+
+/// Marks query elements and data that never borrow objects mutably.
+pub trait ReadOnlyQueryData: QueryData {}
+
+impl<T: Component + 'static> QueryElement for &T {
+    type Item<'a> = &'a T;
+
+    fn component() -> Option<TypeId> {
+        Some(TypeId::of::<T>())
+    }
+
+    fn fetch<'a>(_: &mut Source<'a>, slot: Option<&'a mut Box<dyn Component>>) -> Option<&'a T> {
+        let slot: &'a dyn Component = &**slot?;
+        slot.as_any().downcast_ref::<T>()
+    }
+
+    fn fetch_ref<'a>(_: &SourceRef<'a>, slot: Option<&'a dyn Component>) -> Option<&'a T> {
+        slot?.as_any().downcast_ref::<T>()
+    }
+}
+
+impl<T: Component + 'static> ReadOnlyQueryData for &T {}
+
+impl<T: Component + 'static> QueryElement for &mut T {
+    type Item<'a> = &'a mut T;
+
+    const MUTABLE: bool = true;
+
+    fn component() -> Option<TypeId> {
+        Some(TypeId::of::<T>())
+    }
+
+    fn fetch<'a>(
+        _: &mut Source<'a>,
+        slot: Option<&'a mut Box<dyn Component>>,
+    ) -> Option<&'a mut T> {
+        slot?.as_any_mut().downcast_mut::<T>()
+    }
+
+    fn fetch_ref<'a>(_: &SourceRef<'a>, _: Option<&'a dyn Component>) -> Option<&'a mut T> {
+        unreachable!("mutable query elements are never fetched through a shared borrow")
+    }
+}
+
+impl<T: Component + 'static> QueryElement for Option<&T> {
+    type Item<'a> = Option<&'a T>;
+
+    fn component() -> Option<TypeId> {
+        Some(TypeId::of::<T>())
+    }
+
+    fn fetch<'a>(
+        source: &mut Source<'a>,
+        slot: Option<&'a mut Box<dyn Component>>,
+    ) -> Option<Option<&'a T>> {
+        Some(<&T as QueryElement>::fetch(source, slot))
+    }
+
+    fn fetch_ref<'a>(
+        source: &SourceRef<'a>,
+        slot: Option<&'a dyn Component>,
+    ) -> Option<Option<&'a T>> {
+        Some(<&T as QueryElement>::fetch_ref(source, slot))
+    }
+}
+
+impl<T: Component + 'static> ReadOnlyQueryData for Option<&T> {}
+
+impl<T: Component + 'static> QueryElement for Option<&mut T> {
+    type Item<'a> = Option<&'a mut T>;
+
+    const MUTABLE: bool = true;
+
+    fn component() -> Option<TypeId> {
+        Some(TypeId::of::<T>())
+    }
+
+    fn fetch<'a>(
+        source: &mut Source<'a>,
+        slot: Option<&'a mut Box<dyn Component>>,
+    ) -> Option<Option<&'a mut T>> {
+        Some(<&mut T as QueryElement>::fetch(source, slot))
+    }
+
+    fn fetch_ref<'a>(_: &SourceRef<'a>, _: Option<&'a dyn Component>) -> Option<Option<&'a mut T>> {
+        unreachable!("mutable query elements are never fetched through a shared borrow")
+    }
+}
+
+impl QueryElement for &Transform {
+    type Item<'a> = &'a Transform;
+
+    fn fetch<'a>(
+        source: &mut Source<'a>,
+        _: Option<&'a mut Box<dyn Component>>,
+    ) -> Option<&'a Transform> {
+        source.transform.take().map(|transform| &*transform)
+    }
+
+    fn fetch_ref<'a>(
+        source: &SourceRef<'a>,
+        _: Option<&'a dyn Component>,
+    ) -> Option<&'a Transform> {
+        Some(source.transform)
+    }
+}
+
+impl ReadOnlyQueryData for &Transform {}
+
+impl QueryElement for &mut Transform {
+    type Item<'a> = &'a mut Transform;
+
+    const MUTABLE: bool = true;
+
+    fn fetch<'a>(
+        source: &mut Source<'a>,
+        _: Option<&'a mut Box<dyn Component>>,
+    ) -> Option<&'a mut Transform> {
+        source.transform.take()
+    }
+
+    fn fetch_ref<'a>(_: &SourceRef<'a>, _: Option<&'a dyn Component>) -> Option<&'a mut Transform> {
+        unreachable!("mutable query elements are never fetched through a shared borrow")
+    }
+}
+
+impl QueryElement for GameObjectId {
+    type Item<'a> = GameObjectId;
+
+    fn fetch<'a>(
+        source: &mut Source<'a>,
+        _: Option<&'a mut Box<dyn Component>>,
+    ) -> Option<GameObjectId> {
+        source.id
+    }
+
+    fn fetch_ref<'a>(source: &SourceRef<'a>, _: Option<&'a dyn Component>) -> Option<GameObjectId> {
+        source.id
+    }
+}
+
+impl ReadOnlyQueryData for GameObjectId {}
+
+/// The data a [Query] fetches from each object: a [QueryElement] or a tuple of them.
+///
+/// The transform can be requested only once per query.
+pub trait QueryData {
+    type Item<'a>;
+
+    /// Whether fetching borrows objects mutably.
+    const MUTABLE: bool;
+
+    fn fetch(object: &mut GameObject) -> Option<Self::Item<'_>>;
+
+    /// Fetches the data through a shared borrow. Only called when [QueryData::MUTABLE] is false.
+    fn fetch_ref(object: &GameObject) -> Option<Self::Item<'_>>;
+}
+
+/// Splits the object into its id, transform and the components requested by `keys`.
+fn split<const N: usize>(
+    object: &mut GameObject,
+    keys: [Option<TypeId>; N],
+) -> (Source<'_>, [Option<&mut Box<dyn Component>>; N]) {
+    let mut slots = std::array::from_fn(|_| None);
+
+    for (type_id, component) in &mut object.components {
+        if let Some(index) = keys.iter().position(|key| *key == Some(*type_id)) {
+            slots[index] = Some(component);
+        }
+    }
+
+    (Source { id: object.id, transform: Some(&mut object.transform) }, slots)
+}
+
+fn split_ref<const N: usize>(
+    object: &GameObject,
+    keys: [Option<TypeId>; N],
+) -> (SourceRef<'_>, [Option<&dyn Component>; N]) {
+    let slots = keys
+        .map(|key| key.and_then(|key| object.components.get(&key)).map(|component| &**component));
+
+    (SourceRef { id: object.id, transform: &object.transform }, slots)
+}
+
+impl<E: QueryElement> QueryData for E {
+    type Item<'a> = E::Item<'a>;
+
+    const MUTABLE: bool = E::MUTABLE;
+
+    fn fetch(object: &mut GameObject) -> Option<Self::Item<'_>> {
+        let (mut source, [slot]) = split(object, [E::component()]);
+        E::fetch(&mut source, slot)
+    }
+
+    fn fetch_ref(object: &GameObject) -> Option<Self::Item<'_>> {
+        let (source, [slot]) = split_ref(object, [E::component()]);
+        E::fetch_ref(&source, slot)
+    }
+}
+
+macro_rules! impl_query_data {
+    ($($element:ident $slot:ident),*) => {
+        impl<$($element: QueryElement),*> QueryData for ($($element,)*) {
+            type Item<'a> = ($($element::Item<'a>,)*);
+
+            const MUTABLE: bool = $($element::MUTABLE)||*;
+
+            fn fetch(object: &mut GameObject) -> Option<Self::Item<'_>> {
+                let (mut source, [$($slot),*]) = split(object, [$($element::component()),*]);
+                Some(($($element::fetch(&mut source, $slot)?,)*))
+            }
+
+            fn fetch_ref(object: &GameObject) -> Option<Self::Item<'_>> {
+                let (source, [$($slot),*]) = split_ref(object, [$($element::component()),*]);
+                Some(($($element::fetch_ref(&source, $slot)?,)*))
+            }
+        }
+
+        impl<$($element: QueryElement + ReadOnlyQueryData),*> ReadOnlyQueryData for ($($element,)*) {}
+    };
+}
+
+impl_query_data!(A a);
+impl_query_data!(A a, B b);
+impl_query_data!(A a, B b, C c);
+impl_query_data!(A a, B b, C c, D d);
+impl_query_data!(A a, B b, C c, D d, E e);
+impl_query_data!(A a, B b, C c, D d, E e, F f);
+impl_query_data!(A a, B b, C c, D d, E e, F f, G g);
+impl_query_data!(A a, B b, C c, D d, E e, F f, G g, H h);
+
+/// Restricts the objects a [Query] matches without fetching anything from them.
+pub trait QueryFilter {
+    fn matches(object: &GameObject) -> bool;
+}
+
+impl QueryFilter for () {
+    fn matches(_: &GameObject) -> bool {
+        true
+    }
+}
+
+/// Matches objects that have the component `T`.
+pub struct With<T>(PhantomData<T>);
+
+impl<T: Component + 'static> QueryFilter for With<T> {
+    fn matches(object: &GameObject) -> bool {
+        object.has_component::<T>()
+    }
+}
+
+/// Matches objects that do not have the component `T`.
+pub struct Without<T>(PhantomData<T>);
+
+impl<T: Component + 'static> QueryFilter for Without<T> {
+    fn matches(object: &GameObject) -> bool {
+        !object.has_component::<T>()
+    }
+}
+
+macro_rules! impl_query_filter {
+    ($($filter:ident),*) => {
+        impl<$($filter: QueryFilter),*> QueryFilter for ($($filter,)*) {
+            fn matches(object: &GameObject) -> bool {
+                $($filter::matches(object))&&*
+            }
+        }
+    };
+}
+
+impl_query_filter!(A);
+impl_query_filter!(A, B);
+impl_query_filter!(A, B, C);
+impl_query_filter!(A, B, C, D);
+
+enum Objects<'w> {
+    Shared(Ref<'w, ObjectMap>),
+    Exclusive(RefMut<'w, ObjectMap>),
+}
+
+/// System parameter that iterates over the active objects matching `D` and `F`.
+///
+/// A system can have any number of read-only queries, but a query with mutable access must be
+/// the only one borrowing the scene objects.
+///
+/// ```ignore
+/// fn move_enemies(mut enemies: Query<(&mut Transform, &Enemy)>, time: Time) {
+///     for (transform, enemy) in enemies.iter_mut() {
+///         transform.translation.x += enemy.speed * time.delta();
+///     }
+/// }
+/// ```
+pub struct Query<'w, D: QueryData, F: QueryFilter = ()> {
+    objects: Objects<'w>,
+    marker: PhantomData<fn() -> (D, F)>,
+}
+
+impl<D: QueryData, F: QueryFilter> Query<'_, D, F> {
+    fn matches(object: &GameObject) -> bool {
+        object.active && F::matches(object)
+    }
+
+    /// Iterates over the matching objects in order of their ids.
+    pub fn iter(&self) -> impl Iterator<Item = D::Item<'_>>
+    where
+        D: ReadOnlyQueryData,
+    {
+        let objects = match &self.objects {
+            Objects::Shared(objects) => &**objects,
+            Objects::Exclusive(objects) => &**objects,
+        };
+
+        by_id(objects.iter()).filter(|object| Self::matches(object)).filter_map(D::fetch_ref)
+    }
+
+    /// Mutably iterates over the matching objects in order of their ids.
+    pub fn iter_mut(&mut self) -> Box<dyn Iterator<Item = D::Item<'_>> + '_> {
+        match &mut self.objects {
+            Objects::Shared(objects) => Box::new(
+                by_id(objects.iter())
+                    .filter(|object| Self::matches(object))
+                    .filter_map(D::fetch_ref),
+            ),
+            Objects::Exclusive(objects) => Box::new(
+                by_id(objects.iter_mut())
+                    .filter(|object| Self::matches(object))
+                    .filter_map(D::fetch),
+            ),
+        }
+    }
+
+    /// Gets the data of a single object, if it matches the query.
+    pub fn get(&self, id: GameObjectId) -> Option<D::Item<'_>>
+    where
+        D: ReadOnlyQueryData,
+    {
+        let object = match &self.objects {
+            Objects::Shared(objects) => objects.get(&id)?,
+            Objects::Exclusive(objects) => objects.get(&id)?,
+        };
+
+        Self::matches(object).then(|| D::fetch_ref(object)).flatten()
+    }
+
+    /// Mutably gets the data of a single object, if it matches the query.
+    pub fn get_mut(&mut self, id: GameObjectId) -> Option<D::Item<'_>> {
+        match &mut self.objects {
+            Objects::Shared(objects) => {
+                let object = objects.get(&id)?;
+                Self::matches(object).then(|| D::fetch_ref(object)).flatten()
+            }
+            Objects::Exclusive(objects) => {
+                let object = objects.get_mut(&id)?;
+                if Self::matches(object) { D::fetch(object) } else { None }
+            }
+        }
+    }
+
+    /// Number of matching objects.
+    pub fn count(&mut self) -> usize {
+        self.iter_mut().count()
+    }
+
+    pub fn is_empty(&mut self) -> bool {
+        self.iter_mut().next().is_none()
+    }
+}
+
+impl<'q, D: QueryData, F: QueryFilter> IntoIterator for &'q mut Query<'_, D, F> {
+    type IntoIter = Box<dyn Iterator<Item = D::Item<'q>> + 'q>;
+    type Item = D::Item<'q>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<D: QueryData, F: QueryFilter> SystemParam for Query<'_, D, F> {
+    type Item<'w> = Query<'w, D, F>;
+
+    fn fetch<'w>(ctx: &'w SystemContext, system: &'static str) -> Self::Item<'w> {
+        let objects = if D::MUTABLE {
+            ctx.objects.try_borrow_mut().map(Objects::Exclusive).ok()
+        } else {
+            ctx.objects.try_borrow().map(Objects::Shared).ok()
+        };
+
+        let Some(objects) = objects else {
+            panic!("system `{system}` has a query that conflicts with another query");
+        };
+
+        Query { objects, marker: PhantomData }
+    }
+}
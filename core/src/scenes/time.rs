@@ -0,0 +1,74 @@
+/// Frame timing information of a scene.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Time {
+    delta: f32,
+    unscaled_delta: f32,
+    elapsed: f64,
+    frame_count: u64,
+    scale: f32,
+    paused: bool,
+}
+
+impl Default for Time {
+    fn default() -> Self {
+        Self {
+            delta: 0.0,
+            unscaled_delta: 0.0,
+            elapsed: 0.0,
+            frame_count: 0,
+            scale: 1.0,
+            paused: false,
+        }
+    }
+}
+
+impl Time {
+    /// Time passed since the previous update, multiplied by the time scale and zero while paused.
+    pub fn delta(&self) -> f32 {
+        self.delta
+    }
+
+    /// Time passed since the previous update, ignoring time scale and pause.
+    pub fn unscaled_delta(&self) -> f32 {
+        self.unscaled_delta
+    }
+
+    /// Total scaled time accumulated over all updates of the scene.
+    pub fn elapsed(&self) -> f64 {
+        self.elapsed
+    }
+
+    /// Number of updates the scene went through.
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Speeds up or slows down the scene, `1.0` being real time.
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale.max(0.0);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Stops the scaled time, and with it the scheduled tasks of the scene.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub(crate) fn advance(&mut self, delta: f32) {
+        self.unscaled_delta = delta;
+        self.delta = if self.paused { 0.0 } else { delta * self.scale };
+        self.elapsed += f64::from(self.delta);
+        self.frame_count += 1;
+    }
+}
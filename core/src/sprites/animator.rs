@@ -0,0 +1,169 @@
+use crate::{
+    impl_component,
+    scenes::components::{Component, UpdateContext, Updateable},
+    sprites::Sprite,
+};
+use std::{any::Any, fmt::Debug, sync::Arc};
+
+type SpriteEvent = Arc<dyn Fn(&mut UpdateContext<'_>) + Send + Sync>;
+
+/// Steps the [Sprite] of its object through frames of its atlas, like the frames of a walk cycle
+/// in a sprite sheet.
+///
+/// Events are called with the context of the update in which the animation shows their frame,
+/// including the first one once it starts playing.
+#[derive(Clone)]
+pub struct SpriteAnimator {
+    /// Frames of the atlas, in the order they are shown.
+    pub frames: Vec<usize>,
+    /// Frames shown per second.
+    pub frame_rate: f32,
+    /// Whether the animation starts over after its last frame, otherwise it stops there.
+    pub looping: bool,
+    playing: bool,
+    /// Index into the frames of the frame being shown.
+    position: usize,
+    /// Seconds the frame has been shown for.
+    elapsed: f32,
+    /// Whether the events of the frame being shown have been called.
+    entered: bool,
+    events: Vec<(usize, SpriteEvent)>,
+    finish_events: Vec<SpriteEvent>,
+}
+
+impl SpriteAnimator {
+    pub fn new(frames: impl IntoIterator<Item = usize>, frame_rate: f32) -> Self {
+        Self {
+            frames: frames.into_iter().collect(),
+            frame_rate,
+            looping: true,
+            playing: true,
+            position: 0,
+            elapsed: 0.0,
+            entered: false,
+            events: Vec::new(),
+            finish_events: Vec::new(),
+        }
+    }
+
+    /// Plays the animation once, stopping at its last frame.
+    #[must_use]
+    pub fn once(mut self) -> Self {
+        self.looping = false;
+        self
+    }
+
+    /// Doesn't play the animation until [SpriteAnimator::play] is called.
+    #[must_use]
+    pub fn paused(mut self) -> Self {
+        self.playing = false;
+        self
+    }
+
+    /// Calls `callback` whenever the animation shows the frame at `position` in its frames.
+    #[must_use]
+    pub fn with_event(
+        mut self,
+        position: usize,
+        callback: impl Fn(&mut UpdateContext<'_>) + Send + Sync + 'static,
+    ) -> Self {
+        self.events.push((position, Arc::new(callback)));
+        self
+    }
+
+    /// Calls `callback` when an animation which doesn't loop reaches the end of its last frame.
+    #[must_use]
+    pub fn with_finish_event(
+        mut self,
+        callback: impl Fn(&mut UpdateContext<'_>) + Send + Sync + 'static,
+    ) -> Self {
+        self.finish_events.push(Arc::new(callback));
+        self
+    }
+
+    pub fn play(&mut self) {
+        self.playing = true;
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    /// Plays the animation from its first frame.
+    pub fn restart(&mut self) {
+        self.position = 0;
+        self.elapsed = 0.0;
+        self.entered = false;
+        self.playing = true;
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Index into the frames of the frame being shown.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The frame of the atlas being shown, `None` without frames.
+    pub fn frame(&self) -> Option<usize> {
+        self.frames.get(self.position).copied()
+    }
+
+    /// Advances the animation by `delta_time` seconds. Returns the positions of the frames shown
+    /// on the way, and whether the animation finished.
+    #[expect(clippy::while_float, reason = "the elapsed time shrinks by a frame each iteration")]
+    fn advance(&mut self, delta_time: f32) -> (Vec<usize>, bool) {
+        let mut shown = Vec::new();
+        if !self.playing || self.frames.is_empty() {
+            return (shown, false);
+        }
+
+        if !self.entered {
+            self.entered = true;
+            shown.push(self.position);
+        }
+
+        let duration = 1.0 / self.frame_rate.max(f32::EPSILON);
+        self.elapsed += delta_time;
+        while self.elapsed >= duration {
+            self.elapsed -= duration;
+            if self.position + 1 < self.frames.len() {
+                self.position += 1;
+            } else if self.looping {
+                self.position = 0;
+            } else {
+                self.elapsed = 0.0;
+                self.playing = false;
+                return (shown, true);
+            }
+            shown.push(self.position);
+        }
+
+        (shown, false)
+    }
+}
+
+impl_component!(SpriteAnimator, updateable);
+
+impl Updateable for SpriteAnimator {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        let (shown, finished) = self.advance(ctx.delta_time());
+
+        if let (Some(frame), Some(sprite)) = (self.frame(), ctx.get_component::<Sprite>()) {
+            sprite.set_frame(frame);
+        }
+
+        for position in shown {
+            for (_, callback) in self.events.iter().filter(|(event, _)| *event == position) {
+                callback(ctx);
+            }
+        }
+        if finished {
+            for callback in &self.finish_events {
+                callback(ctx);
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use ciri_math::Vec2;
+use std::sync::Arc;
+use three_d::Texture2D;
+
+/// A rectangle of a texture, in texels from its top left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpriteRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl SpriteRegion {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    /// The cells of a grid of `columns` by `rows` over a texture of the size, row by row from the
+    /// top left. Texels left over by sizes which aren't a multiple of the grid are skipped.
+    pub fn grid(texture_width: u32, texture_height: u32, columns: u32, rows: u32) -> Vec<Self> {
+        if columns == 0 || rows == 0 {
+            return Vec::new();
+        }
+
+        let (width, height) = (texture_width / columns, texture_height / rows);
+        (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .map(|(column, row)| Self::new(column * width, row * height, width, height))
+            .collect()
+    }
+
+    /// Texture coordinates of the top left and bottom right corners of the region in a texture
+    /// of the size.
+    pub fn uv_bounds(&self, texture_width: u32, texture_height: u32) -> (Vec2, Vec2) {
+        let size = Vec2::new(texture_width.max(1) as f32, texture_height.max(1) as f32);
+        let min = Vec2::new(self.x as f32, self.y as f32);
+        let max = min + Vec2::new(self.width as f32, self.height as f32);
+        (min / size, max / size)
+    }
+}
+
+/// A texture holding the images of many sprites, like the frames of a sprite sheet. Frames are
+/// indices into its regions.
+#[derive(Clone)]
+pub struct TextureAtlas {
+    pub texture: Arc<Texture2D>,
+    pub regions: Vec<SpriteRegion>,
+}
+
+impl TextureAtlas {
+    pub fn new(texture: Arc<Texture2D>, regions: Vec<SpriteRegion>) -> Self {
+        Self { texture, regions }
+    }
+
+    /// A sprite sheet of equally sized frames, see [SpriteRegion::grid].
+    pub fn grid(texture: Arc<Texture2D>, columns: u32, rows: u32) -> Self {
+        let regions = SpriteRegion::grid(texture.width(), texture.height(), columns, rows);
+        Self { texture, regions }
+    }
+
+    pub fn region(&self, frame: usize) -> Option<SpriteRegion> {
+        self.regions.get(frame).copied()
+    }
+
+    /// Number of frames in the atlas.
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+}
@@ -0,0 +1,7 @@
+mod animator;
+mod atlas;
+mod sprite;
+
+pub use animator::SpriteAnimator;
+pub use atlas::{SpriteRegion, TextureAtlas};
+pub use sprite::Sprite;
@@ -0,0 +1,386 @@
+use crate::{
+    impl_component,
+    scenes::{
+        Component, Renderer,
+        components::{InstancedRenderer, MeshRenderer, Renderable},
+    },
+    sprites::{SpriteRegion, TextureAtlas},
+};
+use ciri_math::{Mat4, Vec2, Vec3, from_glam_mat4, from_glam_vec, to_glam_mat4};
+use std::{
+    any::Any,
+    fmt::Debug,
+    sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+use three_d::{
+    AxisAlignedBoundingBox, Blend, ColorMaterial, ColorTexture, Context, CpuMesh, CpuTexture,
+    DepthTexture, Effect, Geometry, GeometryId, Gm, Light, Mat3, Material, MaterialType, Mesh,
+    Object, Program, RenderStates, Texture2D, Texture2DRef, TextureData, Viewer, WriteMask,
+};
+use three_d_asset::Srgba;
+
+/// Draws an image, or a region of a [TextureAtlas], on a quad at the transform of its object.
+///
+/// A texel covers one world unit by default, which is one pixel for cameras from
+/// [Camera::new_2d](crate::camera::Camera::new_2d). Sprites blend with what is behind them, so
+/// in 2D they are drawn by the z of their object and then by the
+/// [sort key](Renderer::sort_key) of their [renderer](Sprite::renderer).
+///
+/// Clones of the component draw the same quad, so they also share its settings.
+#[derive(Clone)]
+pub struct Sprite {
+    renderer: Renderer,
+    quad: Arc<RwLock<SpriteQuad>>,
+    atlas: Option<Arc<TextureAtlas>>,
+}
+
+impl Sprite {
+    /// A sprite of the whole texture, which colors are expected in linear sRGB.
+    pub fn new(context: &Context, texture: Arc<Texture2D>) -> Self {
+        let region = SpriteRegion::new(0, 0, texture.width(), texture.height());
+        Self::with_texture(context, texture, region, None)
+    }
+
+    /// A sprite of the whole image, converting 8-bit colors to linear sRGB like three-d's
+    /// materials do.
+    pub fn from_cpu_texture(context: &Context, texture: &CpuTexture) -> Self {
+        let mut texture = texture.clone();
+        if let TextureData::RgbU8(_) | TextureData::RgbaU8(_) = texture.data {
+            texture.data.to_linear_srgb();
+        }
+        Self::new(context, Arc::new(Texture2D::new(context, &texture)))
+    }
+
+    /// A sprite of a frame of the atlas, which can be changed with [Sprite::set_frame].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the atlas has no such frame.
+    pub fn from_atlas(context: &Context, atlas: Arc<TextureAtlas>, frame: usize) -> Self {
+        let region = atlas.region(frame).expect("frame should be in the atlas");
+        let texture = Arc::clone(&atlas.texture);
+        let sprite = Self::with_texture(context, texture, region, Some(atlas));
+        sprite.get_mut().frame = Some(frame);
+        sprite
+    }
+
+    fn with_texture(
+        context: &Context,
+        texture: Arc<Texture2D>,
+        region: SpriteRegion,
+        atlas: Option<Arc<TextureAtlas>>,
+    ) -> Self {
+        let texture_size = (texture.width(), texture.height());
+        let material = ColorMaterial {
+            color: Srgba::WHITE,
+            texture: Some(Texture2DRef { texture, transformation: Mat3::from_scale(1.0) }),
+            render_states: RenderStates {
+                write_mask: WriteMask::COLOR,
+                blend: Blend::TRANSPARENCY,
+                ..Default::default()
+            },
+            is_transparent: true,
+        };
+
+        let mut quad = SpriteQuad {
+            quad: Gm::new(Mesh::new(context, &CpuMesh::square()), material),
+            texture_size,
+            region,
+            frame: None,
+            pivot: Vec2::splat(0.5),
+            flip: (false, false),
+            pixels_per_unit: 1.0,
+            pixel_perfect: false,
+            transformation: Mat4::IDENTITY,
+        };
+        quad.update_uvs();
+        quad.update_transformation();
+
+        let quad = Arc::new(RwLock::new(quad));
+        Self { renderer: Renderer::shared(Arc::<RwLock<SpriteQuad>>::clone(&quad)), quad, atlas }
+    }
+
+    /// Point of the sprite placed at its object, from `(0, 0)` at its bottom left corner to
+    /// `(1, 1)` at its top right one. Sprites are centered by default.
+    #[must_use]
+    pub fn with_pivot(self, pivot: Vec2) -> Self {
+        self.set_pivot(pivot);
+        self
+    }
+
+    /// Mirrors the image horizontally and/or vertically, keeping the pivot in place.
+    #[must_use]
+    pub fn with_flip(self, flip_x: bool, flip_y: bool) -> Self {
+        self.set_flip(flip_x, flip_y);
+        self
+    }
+
+    /// Color multiplied with the image, white by default.
+    #[must_use]
+    pub fn with_tint(self, tint: Srgba) -> Self {
+        self.set_tint(tint);
+        self
+    }
+
+    /// Texels of the image per world unit.
+    #[must_use]
+    pub fn with_pixels_per_unit(self, pixels_per_unit: f32) -> Self {
+        self.set_pixels_per_unit(pixels_per_unit);
+        self
+    }
+
+    /// Snaps the sprite to whole pixels of 2D cameras, rounding the scale of its object so every
+    /// texel covers a whole number of pixels. Sprites meant to be pixel-perfect shouldn't be
+    /// rotated.
+    #[must_use]
+    pub fn pixel_perfect(self) -> Self {
+        self.set_pixel_perfect(true);
+        self
+    }
+
+    pub fn pivot(&self) -> Vec2 {
+        self.get().pivot
+    }
+
+    pub fn set_pivot(&self, pivot: Vec2) {
+        let mut quad = self.get_mut();
+        quad.pivot = pivot;
+        quad.update_transformation();
+    }
+
+    /// Whether the image is mirrored horizontally and vertically.
+    pub fn flip(&self) -> (bool, bool) {
+        self.get().flip
+    }
+
+    pub fn set_flip(&self, flip_x: bool, flip_y: bool) {
+        let mut quad = self.get_mut();
+        quad.flip = (flip_x, flip_y);
+        quad.update_uvs();
+    }
+
+    pub fn tint(&self) -> Srgba {
+        self.get().quad.material.color
+    }
+
+    pub fn set_tint(&self, tint: Srgba) {
+        self.get_mut().quad.material.color = tint;
+    }
+
+    pub fn pixels_per_unit(&self) -> f32 {
+        self.get().pixels_per_unit
+    }
+
+    pub fn set_pixels_per_unit(&self, pixels_per_unit: f32) {
+        let mut quad = self.get_mut();
+        quad.pixels_per_unit = pixels_per_unit.max(f32::EPSILON);
+        quad.update_transformation();
+    }
+
+    pub fn is_pixel_perfect(&self) -> bool {
+        self.get().pixel_perfect
+    }
+
+    pub fn set_pixel_perfect(&self, pixel_perfect: bool) {
+        let mut quad = self.get_mut();
+        quad.pixel_perfect = pixel_perfect;
+        quad.update_transformation();
+    }
+
+    /// The region of the texture which is drawn.
+    pub fn region(&self) -> SpriteRegion {
+        self.get().region
+    }
+
+    /// Draws another region of the texture, which also changes the size of the sprite.
+    pub fn set_region(&self, region: SpriteRegion) {
+        let mut quad = self.get_mut();
+        quad.region = region;
+        quad.frame = None;
+        quad.update_uvs();
+        quad.update_transformation();
+    }
+
+    pub fn atlas(&self) -> Option<&Arc<TextureAtlas>> {
+        self.atlas.as_ref()
+    }
+
+    /// The frame of the atlas which is drawn, `None` for sprites without an atlas or after
+    /// [Sprite::set_region].
+    pub fn frame(&self) -> Option<usize> {
+        self.get().frame
+    }
+
+    /// Draws a frame of the atlas. Returns whether the atlas has the frame, otherwise the sprite
+    /// is left as is.
+    pub fn set_frame(&self, frame: usize) -> bool {
+        let Some(region) = self.atlas.as_ref().and_then(|atlas| atlas.region(frame)) else {
+            return false;
+        };
+
+        let mut quad = self.get_mut();
+        if quad.frame != Some(frame) {
+            quad.region = region;
+            quad.frame = Some(frame);
+            quad.update_uvs();
+            quad.update_transformation();
+        }
+        true
+    }
+
+    /// Size of the sprite in world units, before the scale of its object.
+    pub fn size(&self) -> Vec2 {
+        self.get().size()
+    }
+
+    /// The renderer drawing the sprite, with its culling and sort settings.
+    pub fn renderer(&self) -> &Renderer {
+        &self.renderer
+    }
+
+    pub fn renderer_mut(&mut self) -> &mut Renderer {
+        &mut self.renderer
+    }
+
+    fn get(&self) -> RwLockReadGuard<'_, SpriteQuad> {
+        self.quad.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn get_mut(&self) -> RwLockWriteGuard<'_, SpriteQuad> {
+        self.quad.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl_component!(Sprite, conflicts(Renderer, InstancedRenderer, MeshRenderer));
+
+/// The quad of a [Sprite], spanning from -1 to 1 before being fit to the region and pivot.
+struct SpriteQuad {
+    quad: Gm<Mesh, ColorMaterial>,
+    texture_size: (u32, u32),
+    region: SpriteRegion,
+    frame: Option<usize>,
+    pivot: Vec2,
+    flip: (bool, bool),
+    pixels_per_unit: f32,
+    pixel_perfect: bool,
+    /// World transformation of the object.
+    transformation: Mat4,
+}
+
+impl SpriteQuad {
+    fn size(&self) -> Vec2 {
+        Vec2::new(self.region.width as f32, self.region.height as f32) / self.pixels_per_unit
+    }
+
+    /// Maps the texture coordinates of the quad, from the top left corner, to the region.
+    fn update_uvs(&mut self) {
+        let (mut min, mut max) = self.region.uv_bounds(self.texture_size.0, self.texture_size.1);
+        if self.flip.0 {
+            std::mem::swap(&mut min.x, &mut max.x);
+        }
+        if self.flip.1 {
+            std::mem::swap(&mut min.y, &mut max.y);
+        }
+
+        let scale = max - min;
+        if let Some(texture) = &mut self.quad.material.texture {
+            texture.transformation =
+                Mat3::new(scale.x, 0.0, 0.0, 0.0, scale.y, 0.0, min.x, min.y, 1.0);
+        }
+    }
+
+    /// Fits the quad to the size of the region around the pivot.
+    fn local_transformation(&self) -> Mat4 {
+        let size = self.size();
+        let offset = (Vec2::splat(0.5) - self.pivot) * size;
+        Mat4::from_translation(offset.extend(0.0)) * Mat4::from_scale((size / 2.0).extend(1.0))
+    }
+
+    fn update_transformation(&mut self) {
+        let local = self.local_transformation();
+
+        let transformation = if self.pixel_perfect {
+            let (scale, rotation, translation) =
+                self.transformation.to_scale_rotation_translation();
+            let snap = |scale: f32| {
+                let texels = (scale.abs() / self.pixels_per_unit).round().max(1.0);
+                (texels * self.pixels_per_unit).copysign(scale)
+            };
+            let scale = Vec3::new(snap(scale.x), snap(scale.y), scale.z);
+            let transformation =
+                Mat4::from_scale_rotation_translation(scale, rotation, translation) * local;
+
+            // Moves the bottom left corner onto a whole pixel.
+            let corner = transformation.transform_point3(Vec3::new(-1.0, -1.0, 0.0));
+            let snapped = Vec3::new(corner.x.round(), corner.y.round(), corner.z);
+            Mat4::from_translation(snapped - corner) * transformation
+        } else {
+            self.transformation * local
+        };
+
+        self.quad.set_transformation(from_glam_mat4(transformation));
+    }
+}
+
+impl Geometry for SpriteQuad {
+    fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
+        self.quad.draw(viewer, program, render_states);
+    }
+
+    fn vertex_shader_source(&self) -> String {
+        self.quad.vertex_shader_source()
+    }
+
+    fn id(&self) -> GeometryId {
+        self.quad.id()
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+    ) {
+        self.quad.render_with_material(material, viewer, lights);
+    }
+
+    fn render_with_effect(
+        &self,
+        material: &dyn Effect,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        self.quad.render_with_effect(material, viewer, lights, color_texture, depth_texture);
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.quad.aabb()
+    }
+}
+
+impl Object for SpriteQuad {
+    fn render(&self, viewer: &dyn Viewer, lights: &[&dyn Light]) {
+        self.quad.render(viewer, lights);
+    }
+
+    fn material_type(&self) -> MaterialType {
+        self.quad.material_type()
+    }
+}
+
+impl Renderable for SpriteQuad {
+    fn set_transformation(&mut self, transformation: three_d::Mat4) {
+        self.transformation = to_glam_mat4(transformation);
+        self.update_transformation();
+    }
+
+    /// Bounds of the quad before it is snapped to whole pixels.
+    fn aabb_at(&self, transformation: three_d::Mat4) -> AxisAlignedBoundingBox {
+        let matrix = to_glam_mat4(transformation) * self.local_transformation();
+        let corners = [(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)]
+            .map(|(x, y)| from_glam_vec(matrix.transform_point3(Vec3::new(x, y, 0.0))));
+        AxisAlignedBoundingBox::new_with_positions(&corners)
+    }
+}
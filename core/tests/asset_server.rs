@@ -0,0 +1,132 @@
+use ciri::{
+    assets::{AssetServer, Handle, LoadState},
+    scenes::{Scene, SceneAuto},
+};
+use futures::executor::block_on;
+use std::{fs, path::Path};
+use three_d_asset::Texture2D;
+
+const DIR: &str = "../target/asset_server";
+
+#[derive(Scene)]
+struct Forest {
+    scene: Scene,
+    #[asset("../target/asset_server/shared.hdr")]
+    sky: Handle<Texture2D>,
+}
+
+#[derive(Scene)]
+struct Desert {
+    scene: Scene,
+    #[asset("../target/asset_server/shared.hdr")]
+    sky: Handle<Texture2D>,
+}
+
+/// A flat Radiance HDR image of the given width and a single row.
+fn write_hdr(name: &str, width: usize) -> String {
+    let path = format!("{DIR}/{name}");
+    let mut bytes = format!("#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 1 +X {width}\n").into_bytes();
+    bytes.extend([128, 64, 32, 129].repeat(width));
+    fs::create_dir_all(Path::new(&path).parent().unwrap()).unwrap();
+    fs::write(&path, bytes).unwrap();
+    path
+}
+
+#[test]
+fn deduplicates_and_frees_assets() {
+    let path = write_hdr("dedup.hdr", 2);
+    let server = AssetServer::new();
+
+    let first = server.load::<Texture2D>(&path);
+    let second = AssetServer::clone(&server).load::<Texture2D>(&path);
+    assert!(first.ptr_eq(&second));
+    assert_eq!(server.len(), 1);
+
+    assert_eq!(first.wait().unwrap().width, 2);
+    assert_eq!(second.state(), LoadState::Loaded);
+    assert!(server.get::<Texture2D>(&path).is_some());
+
+    drop((first, second));
+    assert!(server.is_empty());
+    assert!(server.get::<Texture2D>(&path).is_none());
+}
+
+#[test]
+fn reports_failures() {
+    let server = AssetServer::new();
+    let handle = server.load::<Texture2D>(format!("{DIR}/missing.hdr"));
+
+    assert!(handle.wait().unwrap_err().to_string().contains("missing.hdr"));
+    assert_eq!(handle.state(), LoadState::Failed);
+    assert!(handle.get().is_none());
+    assert!(handle.error().is_some());
+}
+
+#[test]
+fn reloads_in_place() {
+    let path = write_hdr("reload.hdr", 1);
+    let server = AssetServer::new();
+    let handle = server.load::<Texture2D>(&path);
+    handle.wait().unwrap();
+
+    write_hdr("reload.hdr", 3);
+    assert_eq!(server.reload_matching(&path).unwrap(), 1);
+    assert_eq!(handle.get().unwrap().width, 3);
+
+    fs::write(&path, "not an image").unwrap();
+    assert!(server.reload_matching(&path).is_err());
+    assert_eq!(handle.get().unwrap().width, 3);
+}
+
+#[test]
+fn awaits_handles() {
+    let path = write_hdr("await.hdr", 4);
+    let server = AssetServer::new();
+
+    let handle = server.load::<Texture2D>(&path);
+    assert_eq!(block_on(handle.loaded()).unwrap().width, 4);
+
+    let missing = server.load::<Texture2D>(format!("{DIR}/missing_await.hdr"));
+    assert!(block_on(missing.loaded()).is_err());
+}
+
+#[test]
+fn reloads_every_match_before_failing() {
+    let broken = write_hdr("many/a.hdr", 1);
+    let working = write_hdr("many/b.hdr", 1);
+    let server = AssetServer::new();
+    let handles = [&broken, &working].map(|path| server.load::<Texture2D>(path));
+    for handle in &handles {
+        handle.wait().unwrap();
+    }
+
+    fs::write(&broken, "not an image").unwrap();
+    write_hdr("many/b.hdr", 5);
+    let error = server.reload_matching(format!("{DIR}/many/*.hdr")).unwrap_err();
+
+    assert!(error.to_string().contains("a.hdr"));
+    assert_eq!(handles[0].get().unwrap().width, 1);
+    assert_eq!(handles[1].get().unwrap().width, 5);
+}
+
+#[test]
+fn scenes_share_declared_handles() {
+    write_hdr("shared.hdr", 2);
+    let server = AssetServer::new();
+
+    let mut forest = Forest::build();
+    let mut desert = Desert::build();
+    assert!(forest.sky.is_loaded());
+    forest.scene.set_assets(server.clone());
+    desert.scene.set_assets(server.clone());
+
+    block_on(forest.load_assets()).unwrap();
+    block_on(desert.load_assets()).unwrap();
+
+    assert!(forest.sky.ptr_eq(&desert.sky));
+    assert_eq!(desert.sky.get().unwrap().width, 2);
+    assert_eq!(server.len(), 1);
+
+    drop((forest, desert));
+    assert!(server.is_empty());
+}
@@ -0,0 +1,146 @@
+use anyhow::Result;
+use ciri::{
+    assets::{Placeholder, matching_paths},
+    scenes::{ResultFuture, Scene, SceneAuto},
+};
+use futures::executor::block_on;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use three_d_asset::{
+    Model as CpuModel, Texture2D,
+    io::{Deserialize, RawAssets},
+};
+
+const DIR: &str = "../target/assets";
+
+#[derive(Scene)]
+struct Level {
+    scene: Scene,
+    #[asset("../target/assets/sky.hdr")]
+    sky: Texture2D,
+    #[asset("../target/assets/missing.hdr", optional)]
+    overlay: Option<Texture2D>,
+    #[asset("../target/assets/tiles/*.hdr", many)]
+    tiles: Vec<Texture2D>,
+    #[asset("../target/assets/missing.png", fallback)]
+    logo: Texture2D,
+    #[asset("../target/assets/missing.png", fallback = checker)]
+    checker: Texture2D,
+    #[asset("../target/assets/triangle.obj", fallback)]
+    model: CpuModel,
+    #[asset("../target/assets/tiles/notes.txt")]
+    notes: Notes,
+}
+
+#[derive(Scene)]
+struct Broken {
+    scene: Scene,
+    #[asset("../target/assets/missing.hdr")]
+    sky: Texture2D,
+}
+
+ciri::impl_scene!("Macro", MacroLevel, (), (
+    sky, "../target/assets/sky.hdr" => Texture2D,
+    overlay, "../target/assets/missing.hdr" => Texture2D [optional],
+    tiles, "../target/assets/tiles/*.hdr" => Texture2D [many],
+    logo, "../target/assets/missing.png" => Texture2D [fallback = checker],
+    notes, "../target/assets/tiles/notes.txt" => Notes,
+));
+
+/// An asset without a placeholder, which only needs a default value.
+#[derive(Default)]
+struct Notes(String);
+
+impl Deserialize for Notes {
+    fn deserialize(
+        path: impl AsRef<Path>,
+        raw_assets: &mut RawAssets,
+    ) -> three_d_asset::Result<Self> {
+        Ok(Self(String::from_utf8_lossy(&raw_assets.remove(path)?).into_owned()))
+    }
+}
+
+fn checker() -> Texture2D {
+    Texture2D { width: 2, height: 2, ..Texture2D::placeholder() }
+}
+
+/// A flat Radiance HDR image of the given width and a single row.
+fn hdr(width: usize) -> Vec<u8> {
+    let mut bytes = format!("#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 1 +X {width}\n").into_bytes();
+    bytes.extend([128, 64, 32, 129].repeat(width));
+    bytes
+}
+
+fn write_assets() {
+    fs::create_dir_all(format!("{DIR}/tiles")).unwrap();
+    fs::write(format!("{DIR}/sky.hdr"), hdr(4)).unwrap();
+    fs::write(format!("{DIR}/tiles/b.hdr"), hdr(2)).unwrap();
+    fs::write(format!("{DIR}/tiles/a.hdr"), hdr(1)).unwrap();
+    fs::write(format!("{DIR}/tiles/notes.txt"), "not a tile").unwrap();
+    fs::write(format!("{DIR}/triangle.obj"), "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n").unwrap();
+}
+
+#[test]
+fn matches_directories_and_globs() {
+    write_assets();
+    let tile = |name: &str| PathBuf::from(format!("{DIR}/tiles/{name}"));
+
+    assert_eq!(
+        matching_paths(format!("{DIR}/tiles/*.hdr")).unwrap(),
+        [tile("a.hdr"), tile("b.hdr")]
+    );
+    assert_eq!(matching_paths(format!("{DIR}/tiles/?.hdr")).unwrap().len(), 2);
+    assert_eq!(matching_paths(format!("{DIR}/tiles")).unwrap().len(), 3);
+    assert_eq!(matching_paths("single.hdr").unwrap(), [PathBuf::from("single.hdr")]);
+    assert!(matching_paths(format!("{DIR}/missing/*.hdr")).is_err());
+}
+
+#[test]
+fn loads_every_kind_of_asset() {
+    write_assets();
+    let mut level = Level::build();
+    assert_eq!(level.logo.width, 1);
+    assert!(level.model.geometries.is_empty());
+
+    block_on(level.load_assets()).unwrap();
+
+    assert_eq!(level.sky.width, 4);
+    assert!(level.overlay.is_none());
+    assert_eq!(level.tiles.iter().map(|tile| tile.width).collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(level.logo.name, "placeholder");
+    assert_eq!(level.checker.width, 2);
+    assert_eq!(level.model.geometries.len(), 1);
+    assert_eq!(level.notes.0, "not a tile");
+}
+
+#[test]
+fn errors_name_the_path() {
+    let error = block_on(Broken::build().load_assets()).unwrap_err();
+    assert!(error.to_string().contains("../target/assets/missing.hdr"));
+}
+
+#[test]
+fn impl_scene_declares_options() {
+    write_assets();
+    let mut level = MacroLevel::build();
+    block_on(level.load_assets()).unwrap();
+
+    assert_eq!(level.sky.width, 4);
+    assert!(level.overlay.is_none());
+    assert!(block_on(level.reload_asset("tiles")).unwrap());
+    assert!(block_on(level.reload_asset("logo")).is_err());
+    assert_eq!(level.logo.width, 2);
+    assert_eq!(level.notes.0, "not a tile");
+}
+
+#[test]
+#[expect(deprecated, reason = "scenes made with impl_scene! still expose once_loaded")]
+fn impl_scene_keeps_once_loaded() {
+    let mut level = MacroLevel::build();
+    assert!(!level.once_loaded());
+
+    level.once_loaded = true;
+    assert!(level.once_loaded());
+}
@@ -0,0 +1,109 @@
+#![allow(dead_code, reason = "every test uses a part of the helpers")]
+
+use ciri::{
+    camera::{Camera, CameraBuilder},
+    math::{self, Transform},
+    scenes::{
+        GameObject, GameObjectId, LayerMask, Scene,
+        components::{Renderable, Renderer},
+    },
+};
+use std::sync::{Arc, Mutex};
+use three_d::{
+    AxisAlignedBoundingBox, ColorTexture, DepthTexture, Effect, Geometry, GeometryId, Light, Mat4,
+    Material, MaterialType, Object, Program, RenderStates, SquareMatrix, Vec3, Viewer, Viewport,
+};
+
+/// A renderable without GPU resources, a unit cube around the origin before it is transformed.
+#[derive(Clone)]
+pub struct MockRenderable {
+    pub transformation: Arc<Mutex<Mat4>>,
+    pub material_type: MaterialType,
+}
+
+impl MockRenderable {
+    pub fn new() -> Self {
+        Self {
+            transformation: Arc::new(Mutex::new(Mat4::identity())),
+            material_type: MaterialType::Opaque,
+        }
+    }
+
+    pub fn transformation(&self) -> Mat4 {
+        *self.transformation.lock().unwrap()
+    }
+}
+
+impl Geometry for MockRenderable {
+    fn draw(&self, _: &dyn Viewer, _: &Program, _: RenderStates) {
+        unreachable!("mock renderables are never drawn")
+    }
+
+    fn vertex_shader_source(&self) -> String {
+        String::new()
+    }
+
+    fn id(&self) -> GeometryId {
+        GeometryId::Screen
+    }
+
+    fn render_with_material(&self, _: &dyn Material, _: &dyn Viewer, _: &[&dyn Light]) {
+        unreachable!("mock renderables are never drawn")
+    }
+
+    fn render_with_effect(
+        &self,
+        _: &dyn Effect,
+        _: &dyn Viewer,
+        _: &[&dyn Light],
+        _: Option<ColorTexture>,
+        _: Option<DepthTexture>,
+    ) {
+        unreachable!("mock renderables are never drawn")
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.aabb_at(self.transformation())
+    }
+}
+
+impl Object for MockRenderable {
+    fn render(&self, _: &dyn Viewer, _: &[&dyn Light]) {
+        unreachable!("mock renderables are never drawn")
+    }
+
+    fn material_type(&self) -> MaterialType {
+        self.material_type
+    }
+}
+
+impl Renderable for MockRenderable {
+    fn set_transformation(&mut self, transformation: Mat4) {
+        *self.transformation.lock().unwrap() = transformation;
+    }
+
+    fn aabb_at(&self, transformation: Mat4) -> AxisAlignedBoundingBox {
+        AxisAlignedBoundingBox::new_with_positions(&[
+            Vec3::new(-0.5, -0.5, -0.5),
+            Vec3::new(0.5, 0.5, 0.5),
+        ])
+        .transformed(transformation)
+    }
+}
+
+/// An object without components at the position.
+pub fn object_at(name: &str, position: math::Vec3) -> GameObject {
+    let mut object = GameObject::new(name);
+    object.transform = Transform::from_translation(position);
+    object
+}
+
+/// Adds an object with the renderer at the position.
+pub fn rendered(scene: &mut Scene, position: math::Vec3, renderer: Renderer) -> GameObjectId {
+    scene.add_object(object_at("rendered", position).with_component(renderer))
+}
+
+/// A camera rendering the layers of the mask.
+pub fn camera(mask: LayerMask) -> Camera {
+    CameraBuilder::new().culling_mask(mask).build(Viewport::new_at_origo(800, 600))
+}
@@ -0,0 +1,134 @@
+use ciri::scenes::{
+    GameObject, Scene, SceneAuto,
+    components::{Component, ComponentLifecycle, ComponentRequirements, UpdateContext, Updateable},
+};
+use std::{
+    any::TypeId,
+    fmt::{Debug, Formatter},
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+#[derive(Debug, Clone, Default, Component)]
+struct Health(u32);
+
+#[derive(Clone, Component)]
+#[component(updateable, requires(Health))]
+struct Regeneration {
+    amount: u32,
+}
+
+impl Debug for Regeneration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Regeneration(+{})", self.amount)
+    }
+}
+
+impl Updateable for Regeneration {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        let amount = self.amount;
+        if let Some(health) = ctx.get_component_mut::<Health>() {
+            health.0 += amount;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Component)]
+#[component(lifecycle, requires_default(Health), conflicts(Regeneration))]
+struct Tracked {
+    events: Arc<AtomicUsize>,
+}
+
+impl ComponentLifecycle for Tracked {
+    fn on_add(&mut self) {
+        self.events.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn on_remove(&mut self) {
+        self.events.fetch_add(10, Ordering::SeqCst);
+    }
+}
+
+#[derive(Debug, Clone, Default, Component)]
+struct Generic<T: Clone + Debug + Default + Send + Sync + 'static> {
+    value: T,
+}
+
+#[derive(Default)]
+struct GameData {
+    score: u32,
+}
+
+#[derive(Scene)]
+#[scene(name = "Game")]
+struct Game {
+    #[scene]
+    world: Scene,
+    data: GameData,
+}
+
+#[derive(Scene)]
+struct Menu {
+    scene: Scene,
+}
+
+#[test]
+fn derives_requirements() {
+    let requirements: ComponentRequirements = Regeneration { amount: 1 }.get_requirements();
+    assert!(requirements.requires(TypeId::of::<Health>()));
+
+    let requirements = Component::get_requirements(&Tracked::default());
+    assert!(requirements.requires(TypeId::of::<Health>()));
+    assert!(requirements.conflicts_with(TypeId::of::<Regeneration>()));
+}
+
+#[test]
+fn keeps_user_debug() {
+    assert_eq!(format!("{:?}", Regeneration { amount: 2 }), "Regeneration(+2)");
+    assert_eq!(format!("{:?}", Health(3)), "Health(3)");
+}
+
+#[test]
+fn derives_updateable() {
+    let mut scene = Scene::new("test");
+    let id = scene.add_object(
+        GameObject::new("object")
+            .with_component(Health(0))
+            .with_component(Regeneration { amount: 2 })
+            .with_component(Generic::<u8>::default()),
+    );
+
+    scene.update(1.0);
+
+    let object = scene.query(id).unwrap();
+    assert_eq!(object.get_component::<Health>().unwrap().0, 2);
+    assert_eq!(object.get_component::<Generic<u8>>().unwrap().value, 0);
+}
+
+#[test]
+fn derives_lifecycle() {
+    let events = Arc::new(AtomicUsize::new(0));
+    let mut object = GameObject::new("object");
+
+    object.add_component(Tracked { events: Arc::clone(&events) });
+    assert_eq!(events.load(Ordering::SeqCst), 1);
+    assert!(object.has_component::<Health>());
+
+    object.remove_component::<Tracked>();
+    assert_eq!(events.load(Ordering::SeqCst), 11);
+}
+
+#[test]
+fn derives_scene() {
+    let mut game = Game::build();
+    assert_eq!(game.name(), "Game");
+    assert_eq!(game.scene().name, "Game");
+    assert_eq!(game.data.score, 0);
+    assert!(!game.once_loaded());
+
+    let mut menu = Menu::build();
+    assert_eq!(menu.name(), "Menu");
+    assert!(futures::executor::block_on(menu.load_assets()).is_ok());
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use ciri::{
+    camera::{Camera, CameraBuilder},
+    math::Vec3,
+    scenes::{GameObjectId, Scene, components::Renderer},
+};
+use common::{MockRenderable, rendered};
+use three_d::{MaterialType, Viewport};
+
+fn opaque() -> Renderer {
+    Renderer::new(MockRenderable::new())
+}
+
+fn transparent() -> Renderer {
+    Renderer::new(MockRenderable {
+        material_type: MaterialType::Transparent,
+        ..MockRenderable::new()
+    })
+}
+
+fn queue(scene: &Scene, camera: &Camera) -> Vec<GameObjectId> {
+    scene.render_queue(camera).0.into_iter().map(|(id, _)| id).collect()
+}
+
+#[test]
+fn opaque_front_to_back_then_transparent_back_to_front() {
+    let camera = CameraBuilder::new()
+        .position(0.0, 0.0, -10.0)
+        .target_origin()
+        .near_far(0.1, 100.0)
+        .build(Viewport::new_at_origo(800, 600));
+
+    let mut scene = Scene::new("test");
+    let far_glass = rendered(&mut scene, Vec3::new(0.0, 0.0, 20.0), transparent());
+    let far_wall = rendered(&mut scene, Vec3::new(0.0, 0.0, 10.0), opaque());
+    let near_glass = rendered(&mut scene, Vec3::new(0.0, 0.0, -5.0), transparent());
+    let near_wall = rendered(&mut scene, Vec3::ZERO, opaque());
+    scene.update(0.1);
+
+    assert_eq!(queue(&scene, &camera), [near_wall, far_wall, far_glass, near_glass]);
+}
+
+#[test]
+fn sprites_are_ordered_by_z_then_sort_key() {
+    let camera = CameraBuilder::new().orthographic().build(Viewport::new_at_origo(800, 600));
+    assert!(camera.is_2d());
+
+    let mut scene = Scene::new("test");
+    let at = |z| Vec3::new(400.0, 300.0, z);
+    let front = rendered(&mut scene, at(0.5), transparent());
+    let top = rendered(&mut scene, at(0.0), opaque().with_sort_key(10));
+    let bottom = rendered(&mut scene, at(0.0), transparent().with_sort_key(-1));
+    let middle = rendered(&mut scene, at(0.0), opaque());
+    let background = rendered(&mut scene, at(-5.0), opaque().with_sort_key(100));
+    scene.update(0.1);
+
+    assert_eq!(queue(&scene, &camera), [background, bottom, middle, top, front]);
+}
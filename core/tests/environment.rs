@@ -0,0 +1,39 @@
+use ciri::{lights::Sky, math::Vec3};
+use three_d::CpuTexture;
+use three_d_asset::Srgba;
+
+fn close(a: Vec3, b: Vec3) -> bool {
+    a.distance(b) < 1e-5
+}
+
+#[test]
+fn solid_sky_is_the_same_everywhere() {
+    let sky = Sky::Color(Srgba::WHITE);
+
+    assert_eq!(sky.linear_color(Vec3::Y), Some(Vec3::ONE));
+    assert_eq!(sky.linear_color(Vec3::new(1.0, -2.0, 0.5)), Some(Vec3::ONE));
+}
+
+#[test]
+fn gradient_blends_through_the_horizon() {
+    let sky = Sky::gradient(Srgba::WHITE, Srgba::BLACK, Srgba::RED);
+
+    assert!(close(sky.linear_color(Vec3::Y).unwrap(), Vec3::ONE));
+    assert!(close(sky.linear_color(Vec3::X).unwrap(), Vec3::ZERO));
+    assert!(close(sky.linear_color(Vec3::NEG_Y).unwrap(), Vec3::X));
+
+    let halfway = sky.linear_color(Vec3::new(1.0, 1.0, 0.0).normalize()).unwrap();
+    assert!(close(halfway, Vec3::splat(std::f32::consts::FRAC_1_SQRT_2)));
+}
+
+#[test]
+fn gradient_ignores_the_length_of_directions() {
+    let sky = Sky::gradient(Srgba::WHITE, Srgba::BLACK, Srgba::BLACK);
+
+    assert_eq!(sky.linear_color(Vec3::new(0.0, 10.0, 0.0)), sky.linear_color(Vec3::Y));
+}
+
+#[test]
+fn images_have_no_procedural_color() {
+    assert_eq!(Sky::Image(CpuTexture::default()).linear_color(Vec3::Y), None);
+}
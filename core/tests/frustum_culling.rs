@@ -0,0 +1,70 @@
+mod common;
+
+use ciri::{
+    camera::{Camera, CameraBuilder},
+    math::Vec3,
+    scenes::{
+        GameObjectId, Scene,
+        components::{RenderStats, Renderer},
+    },
+};
+use common::{MockRenderable, rendered};
+use std::collections::HashSet;
+use three_d::Viewport;
+
+fn camera() -> Camera {
+    CameraBuilder::new()
+        .position(0.0, 0.0, -10.0)
+        .target_origin()
+        .near_far(0.1, 100.0)
+        .build(Viewport::new_at_origo(800, 600))
+}
+
+fn cube() -> Renderer {
+    Renderer::new(MockRenderable::new())
+}
+
+fn visible(scene: &Scene, camera: &Camera) -> (HashSet<GameObjectId>, RenderStats) {
+    let (renderers, stats) = scene.visible_renderers(camera);
+    (renderers.into_iter().map(|(id, _)| id).collect(), stats)
+}
+
+#[test]
+fn culls_objects_outside_the_view() {
+    let mut scene = Scene::new("test");
+    let center = rendered(&mut scene, Vec3::ZERO, cube());
+    let edge = rendered(&mut scene, Vec3::new(4.5, 0.0, 0.0), cube());
+    rendered(&mut scene, Vec3::new(0.0, 0.0, -20.0), cube());
+    rendered(&mut scene, Vec3::new(50.0, 0.0, 0.0), cube());
+    rendered(&mut scene, Vec3::new(0.0, 0.0, 200.0), cube());
+    scene.update(0.1);
+
+    let (ids, stats) = visible(&scene, &camera());
+    assert_eq!(ids, HashSet::from([center, edge]));
+    assert_eq!(stats, RenderStats { drawn: 2, culled: 3 });
+}
+
+#[test]
+fn culling_follows_moved_objects() {
+    let mut scene = Scene::new("test");
+    let id = rendered(&mut scene, Vec3::new(50.0, 0.0, 0.0), cube());
+    scene.update(0.1);
+    assert_eq!(visible(&scene, &camera()).1, RenderStats { drawn: 0, culled: 1 });
+
+    scene.objects.get_mut(&id).unwrap().transform.translation = Vec3::ZERO;
+    scene.update(0.1);
+    assert_eq!(visible(&scene, &camera()).0, HashSet::from([id]));
+}
+
+#[test]
+fn objects_can_opt_out() {
+    let mut scene = Scene::new("test");
+    let behind = rendered(&mut scene, Vec3::new(0.0, 0.0, -20.0), cube().without_frustum_culling());
+    scene.update(0.1);
+
+    assert_eq!(
+        visible(&scene, &camera()),
+        (HashSet::from([behind]), RenderStats { drawn: 1, culled: 0 })
+    );
+    assert_eq!(scene.render_stats(), RenderStats::default());
+}
@@ -0,0 +1,83 @@
+use ciri::scenes::{AssetWatcher, Scene, SceneAuto};
+use futures::executor::block_on;
+use std::{
+    fs::{self, File},
+    path::Path,
+    time::{Duration, SystemTime},
+};
+use three_d_asset::Texture2D;
+
+const SKYBOX: &str = "../target/hot_reload/skybox.hdr";
+const WATCHED: &str = "../target/hot_reload/watched.txt";
+
+#[derive(Scene)]
+struct Game {
+    scene: Scene,
+    #[asset("../target/hot_reload/skybox.hdr")]
+    skybox: Texture2D,
+}
+
+/// Writes the file and moves its modification time forward, so changes are seen even when the
+/// file system has a coarse clock.
+fn write(path: &str, contents: &[u8], age: u64) {
+    fs::create_dir_all(Path::new(path).parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+    let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000 + age);
+    File::options().write(true).open(path).unwrap().set_modified(modified).unwrap();
+}
+
+/// A flat Radiance HDR image of the given width and a single row.
+fn hdr(width: usize) -> Vec<u8> {
+    let mut bytes = format!("#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 1 +X {width}\n").into_bytes();
+    bytes.extend([128, 64, 32, 129].repeat(width));
+    bytes
+}
+
+#[test]
+fn watcher_reports_modified_files() {
+    write(WATCHED, b"first", 0);
+    let mut watcher = AssetWatcher::new(&[("watched", WATCHED), ("missing", "missing.txt")]);
+    assert!(watcher.changed().is_empty());
+
+    write(WATCHED, b"second", 1);
+    assert_eq!(watcher.changed(), ["watched"]);
+    assert!(watcher.changed().is_empty());
+}
+
+#[test]
+fn watcher_expands_globs() {
+    write("../target/hot_reload/tiles/a.txt", b"a", 0);
+    write("../target/hot_reload/tiles/b.txt", b"b", 0);
+    let mut watcher = AssetWatcher::new(&[("tiles", "../target/hot_reload/tiles/*.txt")]);
+
+    write("../target/hot_reload/tiles/a.txt", b"a", 1);
+    write("../target/hot_reload/tiles/b.txt", b"b", 1);
+    assert_eq!(watcher.changed(), ["tiles"]);
+}
+
+#[test]
+fn watcher_polls_once_per_interval() {
+    let mut watcher = AssetWatcher::new(&[]).with_interval(Duration::from_mins(1));
+    assert_eq!(watcher.interval(), Duration::from_mins(1));
+    assert!(watcher.poll().is_empty());
+}
+
+#[test]
+fn reloads_declared_assets() {
+    write(SKYBOX, &hdr(1), 0);
+
+    let mut game = Game::build();
+    assert_eq!(game.asset_paths(), [("skybox", SKYBOX)]);
+    block_on(game.load_assets()).unwrap();
+    assert_eq!(game.skybox.width, 1);
+
+    write(SKYBOX, &hdr(2), 1);
+    assert!(block_on(game.reload_asset("skybox")).unwrap());
+    assert_eq!(game.skybox.width, 2);
+    assert!(!block_on(game.reload_asset("unknown")).unwrap());
+
+    // A broken file keeps the previous asset.
+    write(SKYBOX, b"not an image", 2);
+    assert!(block_on(game.reload_asset("skybox")).is_err());
+    assert_eq!(game.skybox.width, 2);
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use ciri::{
+    assets::Handle,
+    math::{Aabb, Ray, Transform, Vec3},
+    scenes::{
+        GameObject, GameObjectId, LayerMask, Layers, Scene,
+        components::{ComponentError, MeshRenderer, Renderer},
+    },
+};
+use common::{MockRenderable, camera};
+use three_d::{CpuMaterial, CpuMesh};
+
+fn tree(scene: &mut Scene, mesh: &Handle<CpuMesh>, material: &Handle<CpuMaterial>) -> GameObjectId {
+    scene.add_object(
+        GameObject::new("tree").with_component(MeshRenderer::new(mesh.clone(), material.clone())),
+    )
+}
+
+#[test]
+fn shared_handles_are_batched() {
+    let mut scene = Scene::new("test");
+    let mesh = Handle::new(CpuMesh::cube());
+    let material = Handle::new(CpuMaterial::default());
+    let other_material = Handle::new(CpuMaterial::default());
+
+    let a = tree(&mut scene, &mesh, &material);
+    let b = tree(&mut scene, &mesh, &material);
+    let c = tree(&mut scene, &mesh, &other_material);
+    // Equal assets behind different handles aren't batched together.
+    let d = tree(&mut scene, &Handle::new(CpuMesh::cube()), &material);
+
+    let batches = scene.mesh_batches();
+    assert_eq!(batches.len(), 3);
+    assert_eq!(batches[0].objects, vec![a, b]);
+    assert!(batches[0].mesh.ptr_eq(&mesh) && batches[0].material.ptr_eq(&material));
+    assert_eq!(batches[1].objects, vec![c]);
+    assert_eq!(batches[2].objects, vec![d]);
+}
+
+#[test]
+fn inactive_objects_are_not_batched() {
+    let mut scene = Scene::new("test");
+    let mesh = Handle::new(CpuMesh::cube());
+    let material = Handle::new(CpuMaterial::default());
+
+    let shown = tree(&mut scene, &mesh, &material);
+    let hidden = tree(&mut scene, &mesh, &material);
+    scene.objects.get_mut(&hidden).unwrap().disable();
+
+    let batches = scene.mesh_batches();
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0].objects, vec![shown]);
+}
+
+#[test]
+fn batches_follow_the_culling_mask() {
+    const UI: LayerMask = LayerMask::layer(5);
+
+    let mut scene = Scene::new("test");
+    let mesh = Handle::new(CpuMesh::cube());
+    let material = Handle::new(CpuMaterial::default());
+
+    let world = tree(&mut scene, &mesh, &material);
+    let ui = tree(&mut scene, &mesh, &material);
+    scene.objects.get_mut(&ui).unwrap().add_component(Layers(UI));
+
+    let batches = scene.mesh_batches_for(&camera(!UI));
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0].objects, vec![world]);
+
+    let batches = scene.mesh_batches_for(&camera(UI));
+    assert_eq!(batches[0].objects, vec![ui]);
+}
+
+#[test]
+fn mesh_renderers_conflict_with_renderers() {
+    let mesh = MeshRenderer::new(Handle::new(CpuMesh::cube()), Handle::new(CpuMaterial::default()));
+
+    let mut object = GameObject::new("object").with_component(Renderer::new(MockRenderable::new()));
+    let err = object.try_add_component(mesh).unwrap_err();
+    assert!(matches!(err, ComponentError::Conflict { .. }));
+}
+
+#[test]
+fn mesh_renderers_are_indexed_and_hit_at_their_mesh_bounds() {
+    let mut scene = Scene::new("test");
+    let mesh = Handle::new(CpuMesh::cube());
+    let material = Handle::new(CpuMaterial::default());
+    let id = tree(&mut scene, &mesh, &material);
+    scene.objects.get_mut(&id).unwrap().transform = Transform::from_translation(Vec3::X * 10.0);
+    scene.update_spatial_index();
+
+    let bounds = Aabb::new(Vec3::new(9.0, -1.0, -1.0), Vec3::new(11.0, 1.0, 1.0));
+    assert_eq!(scene.spatial().bounds(id), Some(bounds));
+    assert_eq!(scene.spatial().query_sphere(Vec3::new(11.5, 0.0, 0.0), 0.6), [id]);
+
+    let hit = scene.raycast_closest(&Ray::new(Vec3::ZERO, Vec3::X), LayerMask::ALL).unwrap();
+    assert_eq!((hit.object, hit.distance), (id, 9.0));
+}
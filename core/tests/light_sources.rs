@@ -0,0 +1,74 @@
+use ciri::{
+    lights::{LightSource, PointLight},
+    math::{Transform, Vec3},
+    scenes::{GameObject, GameObjectId, Scene},
+};
+use std::any::Any;
+
+fn lamp(scene: &mut Scene, position: Vec3) -> GameObjectId {
+    let mut object = GameObject::new("lamp")
+        .with_component(LightSource::new(PointLight::builder().range(5.0).build()));
+    object.transform = Transform::from_translation(position);
+    scene.add_object(object)
+}
+
+fn light_position(scene: &Scene, id: GameObjectId) -> Vec3 {
+    let source = scene.objects[&id].get_component::<LightSource>().unwrap();
+    let light = source.get();
+    (&*light as &dyn Any).downcast_ref::<PointLight>().unwrap().position
+}
+
+#[test]
+fn lights_follow_their_objects() {
+    let mut scene = Scene::new("test");
+    let id = lamp(&mut scene, Vec3::new(1.0, 2.0, 3.0));
+    scene.update(0.1);
+    assert_eq!(light_position(&scene, id), Vec3::new(1.0, 2.0, 3.0));
+
+    scene.objects.get_mut(&id).unwrap().transform.translation = Vec3::new(-4.0, 0.0, 0.0);
+    scene.update(0.1);
+    assert_eq!(light_position(&scene, id), Vec3::new(-4.0, 0.0, 0.0));
+}
+
+#[test]
+fn only_enabled_lights_of_active_objects_shine() {
+    let mut scene = Scene::new("test");
+    let shining = lamp(&mut scene, Vec3::ZERO);
+    let inactive = lamp(&mut scene, Vec3::ZERO);
+    scene.objects.get_mut(&inactive).unwrap().disable();
+    scene.add_object(
+        GameObject::new("off")
+            .with_component(LightSource::new(PointLight::builder().build()).disabled()),
+    );
+
+    let ids: Vec<_> = scene.light_sources().map(|(id, _)| id).collect();
+    assert_eq!(ids, vec![shining]);
+}
+
+#[test]
+fn removed_objects_take_their_lights() {
+    let mut scene = Scene::new("test");
+    let id = lamp(&mut scene, Vec3::ZERO);
+    assert_eq!(scene.light_sources().count(), 1);
+
+    scene.remove_object(id);
+    assert_eq!(scene.light_sources().count(), 0);
+}
+
+#[test]
+fn cloned_lights_follow_their_own_objects() {
+    let mut scene = Scene::new("test");
+    let torch = GameObject::new("torch")
+        .with_component(LightSource::new(PointLight::builder().range(5.0).build()));
+
+    let mut ids = Vec::new();
+    for x in [1.0, -1.0] {
+        let mut object = torch.clone();
+        object.transform = Transform::from_translation(Vec3::new(x, 0.0, 0.0));
+        ids.push(scene.add_object(object));
+    }
+    scene.update(0.1);
+
+    assert_eq!(light_position(&scene, ids[0]), Vec3::new(1.0, 0.0, 0.0));
+    assert_eq!(light_position(&scene, ids[1]), Vec3::new(-1.0, 0.0, 0.0));
+}
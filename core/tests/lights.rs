@@ -0,0 +1,60 @@
+use ciri::{
+    lights::{PointLight, ShadowSettings, SpotLight, range_attenuation},
+    math::Vec3,
+};
+use three_d::{Attenuation, Light, LightId};
+use three_d_asset::Srgba;
+
+fn attenuate(attenuation: Attenuation, distance: f32) -> f32 {
+    1.0 / attenuation
+        .quadratic
+        .mul_add(distance * distance, attenuation.linear.mul_add(distance, attenuation.constant))
+}
+
+#[test]
+fn range_fades_light_out() {
+    let attenuation = range_attenuation(10.0);
+
+    assert!((attenuate(attenuation, 0.0) - 1.0).abs() < f32::EPSILON);
+    assert!(attenuate(attenuation, 5.0) > 0.03);
+    assert!(attenuate(attenuation, 10.0) < 0.015);
+}
+
+#[test]
+fn point_light_builder() {
+    let light = PointLight::builder()
+        .intensity(2.0)
+        .color(Srgba::RED)
+        .position(Vec3::new(1.0, 2.0, 3.0))
+        .range(5.0)
+        .build();
+
+    assert!((light.intensity - 2.0).abs() < f32::EPSILON);
+    assert_eq!(light.color, Srgba::RED);
+    assert_eq!(light.position, Vec3::new(1.0, 2.0, 3.0));
+    assert_eq!(light.attenuation, range_attenuation(5.0));
+    assert!(light.id() == LightId::PointLight);
+}
+
+#[test]
+fn spot_light_builder() {
+    let builder = SpotLight::builder()
+        .position(Vec3::new(0.0, 3.0, 0.0))
+        .direction(Vec3::new(0.0, -1.0, 1.0))
+        .cone(20.0, 30.0)
+        .shadows(512);
+
+    assert_eq!(builder.position, Vec3::new(0.0, 3.0, 0.0));
+    assert_eq!(builder.direction, Vec3::new(0.0, -1.0, 1.0));
+    assert!((builder.inner_angle - 20.0).abs() < f32::EPSILON);
+    assert!((builder.outer_angle - 30.0).abs() < f32::EPSILON);
+    assert_eq!(builder.shadows, Some(ShadowSettings { resolution: 512, ..Default::default() }));
+}
+
+#[test]
+fn spot_light_inner_cone_is_inside_the_outer_one() {
+    let builder = SpotLight::builder().cone(50.0, 30.0);
+
+    assert!((builder.inner_angle - 30.0).abs() < f32::EPSILON);
+    assert!((builder.outer_angle - 30.0).abs() < f32::EPSILON);
+}
@@ -0,0 +1,93 @@
+use ciri::{
+    math::Vec3,
+    scenes::{
+        GameObject, Scene,
+        components::{Component, UpdateContext, Updateable},
+    },
+};
+
+#[derive(Debug, Default)]
+struct Visits(Vec<String>);
+
+#[derive(Debug)]
+struct Speed(f32);
+
+#[derive(Debug, Clone, Component)]
+#[component(updateable)]
+struct Agent;
+
+impl Updateable for Agent {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        assert!(ctx.is_parallel());
+        assert!(ctx.resource_mut::<Speed>().is_none());
+        assert_eq!(ctx.objects().count(), 0);
+
+        let speed = ctx.resource::<Speed>().unwrap().0;
+        ctx.transform_mut().translation.x += speed;
+
+        let name = ctx.game_object().name.clone();
+        ctx.commands().add(move |scene| {
+            scene.resource_mut::<Visits>().unwrap().0.push(name);
+        });
+    }
+
+    fn parallel_safe(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Clone, Component)]
+#[component(updateable)]
+struct Leader;
+
+impl Updateable for Leader {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        assert!(!ctx.is_parallel());
+        ctx.resource_mut::<Speed>().unwrap().0 = 2.0;
+    }
+}
+
+fn setup(parallel: bool) -> Scene {
+    let mut scene = Scene::new("test");
+    scene.set_parallel_updates(parallel);
+    scene.insert_resource(Visits::default());
+    scene.insert_resource(Speed(1.0));
+    scene.add_object(GameObject::new("leader").with_component(Leader));
+
+    for i in 0..64 {
+        scene.add_object(GameObject::new(format!("agent {i}")).with_component(Agent));
+    }
+
+    scene
+}
+
+#[test]
+fn parallel_components_run_after_serial_ones() {
+    let mut scene = setup(true);
+
+    scene.update(1.0);
+
+    for object in scene.objects().values().filter(|object| object.has_component::<Agent>()) {
+        assert_eq!(object.transform.translation, Vec3::new(2.0, 0.0, 0.0));
+    }
+}
+
+#[test]
+fn commands_are_applied_in_object_order() {
+    let mut scene = setup(true);
+
+    scene.update(1.0);
+    scene.update(1.0);
+
+    let expected: Vec<String> = (0..64).map(|i| format!("agent {i}")).collect();
+    let visits = &scene.resource::<Visits>().unwrap().0;
+    assert_eq!(visits[..64], expected);
+    assert_eq!(visits[64..], expected);
+}
+
+#[test]
+#[should_panic(expected = "is_parallel")]
+fn parallel_safe_components_run_serially_by_default() {
+    let mut scene = setup(false);
+    scene.update(1.0);
+}
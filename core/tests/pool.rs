@@ -0,0 +1,136 @@
+mod common;
+
+use ciri::{
+    camera::CameraBuilder,
+    math::{Transform, Vec3},
+    scenes::{
+        GameObject, GameObjectPool, PoolGrowth, Scene,
+        components::{Component, Renderer, UpdateContext, Updateable},
+    },
+};
+use common::MockRenderable;
+use three_d::Viewport;
+
+#[derive(Debug, Clone, Component)]
+#[component(updateable)]
+struct Bullet {
+    age: u32,
+}
+
+impl Updateable for Bullet {
+    fn update(&mut self, _: &mut UpdateContext) {
+        self.age += 1;
+    }
+}
+
+fn at(x: f32) -> Transform {
+    let mut transform = Transform::identity();
+    transform.translation = Vec3::new(x, 0.0, 0.0);
+    transform
+}
+
+fn bullet() -> GameObject {
+    GameObject::new("bullet").with_component(Bullet { age: 0 })
+}
+
+#[test]
+fn pre_instantiates_disabled_objects() {
+    let mut scene = Scene::new("test");
+    let pool = scene.add_pool(GameObjectPool::new(bullet()).with_capacity(4));
+
+    assert_eq!(scene.objects().len(), 4);
+    assert!(scene.objects().values().all(|object| !object.is_active()));
+    assert_eq!(scene.pool(pool).unwrap().available(), 4);
+}
+
+#[test]
+fn spawned_objects_are_regular_objects() {
+    let mut scene = Scene::new("test");
+    let pool = scene.add_pool(GameObjectPool::new(bullet()).with_capacity(2));
+
+    let id = scene.spawn_pooled(pool, at(3.0)).unwrap();
+    scene.update(1.0);
+
+    let object = scene.query(id).unwrap();
+    assert!(object.is_active());
+    assert_eq!(object.transform.translation, Vec3::new(3.0, 0.0, 0.0));
+    assert_eq!(object.get_component::<Bullet>().unwrap().age, 1);
+    assert_eq!(scene.pool(pool).unwrap().in_use(), 1);
+}
+
+#[test]
+fn released_objects_are_reused() {
+    let mut scene = Scene::new("test");
+    let pool = scene.add_pool(
+        GameObjectPool::new(bullet())
+            .with_capacity(1)
+            .on_spawn(|object| object.get_component_mut::<Bullet>().unwrap().age = 0),
+    );
+
+    let first = scene.spawn_pooled(pool, at(0.0)).unwrap();
+    scene.update(1.0);
+    assert!(scene.release_pooled(first));
+    assert!(!scene.release_pooled(first));
+    assert!(!scene.query(first).unwrap().is_active());
+
+    let second = scene.spawn_pooled(pool, at(1.0)).unwrap();
+    assert_eq!(first, second);
+    assert_eq!(scene.query(second).unwrap().get_component::<Bullet>().unwrap().age, 0);
+    assert_eq!(scene.objects().len(), 1);
+}
+
+#[test]
+fn grows_according_to_policy() {
+    let mut scene = Scene::new("test");
+    let fixed = scene
+        .add_pool(GameObjectPool::new(bullet()).with_capacity(1).with_growth(PoolGrowth::Fixed));
+    let doubling = scene.add_pool(GameObjectPool::new(bullet()).with_capacity(1).with_max_size(3));
+
+    assert!(scene.spawn_pooled(fixed, at(0.0)).is_some());
+    assert!(scene.spawn_pooled(fixed, at(0.0)).is_none());
+
+    for _ in 0..3 {
+        assert!(scene.spawn_pooled(doubling, at(0.0)).is_some());
+    }
+    assert!(scene.spawn_pooled(doubling, at(0.0)).is_none());
+    assert_eq!(scene.pool(doubling).unwrap().size(), 3);
+}
+
+#[test]
+fn releasing_cancels_owned_tasks() {
+    let mut scene = Scene::new("test");
+    let pool = scene.add_pool(GameObjectPool::new(bullet()).with_capacity(1));
+
+    let id = scene.spawn_pooled(pool, at(0.0)).unwrap();
+    let task = scene.after(Some(id), 1.0, |_| panic!("task of a released object ran"));
+    scene.release_pooled(id);
+    scene.update(2.0);
+
+    assert!(!scene.is_task_scheduled(task));
+}
+
+#[test]
+fn pooled_renderers_render_at_their_own_objects() {
+    let mut scene = Scene::new("test");
+    let template = GameObject::new("rendered").with_component(Renderer::new(MockRenderable::new()));
+    let pool = scene.add_pool(GameObjectPool::new(template).with_capacity(2));
+
+    let near = scene.spawn_pooled(pool, at(0.0)).unwrap();
+    let far = scene.spawn_pooled(pool, at(50.0)).unwrap();
+    scene.update(0.1);
+
+    for (id, x) in [(near, 0.0), (far, 50.0)] {
+        let object = &scene.objects()[&id];
+        let aabb = object.renderer().unwrap().at(&object.transform).world_aabb().unwrap();
+        assert_eq!(aabb.center(), Vec3::new(x, 0.0, 0.0));
+    }
+
+    let camera = CameraBuilder::new()
+        .position(0.0, 0.0, -10.0)
+        .target_origin()
+        .near_far(0.1, 100.0)
+        .build(Viewport::new_at_origo(800, 600));
+    let (visible, stats) = scene.visible_renderers(&camera);
+    assert_eq!(visible.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![near]);
+    assert_eq!((stats.drawn, stats.culled), (1, 1));
+}
@@ -0,0 +1,79 @@
+use ciri::camera::{
+    CameraBuilder,
+    post_processing::{
+        Bloom, Exposure, Fxaa, PostContext, PostEffect, PostProcessing, ShaderEffect, Vignette,
+    },
+};
+use three_d::{ColorTarget, Texture2D, Viewport};
+
+struct Scanlines {
+    lines: u32,
+}
+
+impl PostEffect for Scanlines {
+    fn apply(&self, _: &PostContext<'_>, _: &Texture2D, _: &ColorTarget<'_>) {
+        unreachable!("effects are not applied without a window")
+    }
+}
+
+fn stack() -> PostProcessing {
+    PostProcessing::new()
+        .with_effect(Fxaa)
+        .with_effect(Bloom::default())
+        .with_effect(Scanlines { lines: 240 })
+        .with_effect(Exposure { exposure: 2.0, gamma: 2.2 })
+}
+
+#[test]
+fn effects_can_be_toggled_at_runtime() {
+    let mut effects = stack();
+    assert!(effects.is_active());
+    assert!(effects.is_enabled::<Bloom>());
+
+    assert!(effects.set_enabled::<Bloom>(false));
+    assert!(!effects.is_enabled::<Bloom>());
+    assert!(!effects.set_enabled::<Vignette>(false));
+    assert!(!effects.is_enabled::<Vignette>());
+
+    for index in 0..effects.len() {
+        effects.set_enabled_at(index, false);
+    }
+    assert!(!effects.is_active());
+    assert!(!effects.set_enabled_at(effects.len(), true));
+
+    let index = effects.add(Vignette::default());
+    assert!(effects.is_enabled_at(index) && effects.is_active());
+}
+
+#[test]
+fn effects_can_be_tweaked_by_type() {
+    let mut effects = stack();
+    effects.get_mut::<Scanlines>().unwrap().lines = 480;
+    effects.get_mut::<Exposure>().unwrap().exposure = 0.5;
+
+    assert_eq!(effects.get::<Scanlines>().unwrap().lines, 480);
+    assert!((effects.get::<Exposure>().unwrap().exposure - 0.5).abs() < f32::EPSILON);
+    assert!(effects.get::<ShaderEffect>().is_none());
+}
+
+#[test]
+fn cameras_start_without_effects() {
+    let viewport = Viewport::new_at_origo(800, 600);
+    assert!(CameraBuilder::new().build(viewport).post_processing.is_empty());
+
+    let sepia = ShaderEffect::new(
+        "uniform float amount;
+        void main() {
+            vec4 color = sample_color(uvs);
+            vec3 sepia = vec3(dot(color.rgb, vec3(0.393, 0.769, 0.189)));
+            outColor = vec4(mix(color.rgb, sepia, amount), color.a);
+        }",
+    )
+    .with_uniforms(|program| program.use_uniform("amount", 0.8f32));
+
+    let camera = CameraBuilder::new()
+        .post_processing(PostProcessing::new().with_effect(Fxaa).with_effect(sepia))
+        .build(viewport);
+    assert_eq!(camera.post_processing.len(), 2);
+    assert!(format!("{:?}", camera.post_processing).contains("ShaderEffect\": true"));
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use ciri::{
+    camera::Camera,
+    math::{Aabb, Ray, Transform, Vec3},
+    scenes::{Bounds, GameObject, LayerMask, Layers, MeshCollider, Scene, components::Renderer},
+};
+use common::{MockRenderable, object_at};
+use three_d::{CpuMesh, Positions, Vec3 as CpuVec3, Viewport, degrees};
+
+fn cube(name: &str, position: Vec3) -> GameObject {
+    object_at(name, position).with_component(Bounds::from_size(Vec3::splat(2.0)))
+}
+
+#[test]
+fn ray_hits_box_faces() {
+    let aabb = Aabb::new(Vec3::splat(-1.0), Vec3::splat(1.0));
+    let ray = Ray::new(Vec3::new(0.0, 0.0, -5.0), Vec3::Z);
+
+    assert_eq!(ray.intersect_aabb(&aabb), Some((4.0, Vec3::NEG_Z)));
+    assert_eq!(Ray::new(Vec3::ZERO, Vec3::X).intersect_aabb(&aabb), Some((0.0, Vec3::NEG_X)));
+    assert_eq!(Ray::new(Vec3::new(0.0, 0.0, -5.0), Vec3::NEG_Z).intersect_aabb(&aabb), None);
+}
+
+#[test]
+fn raycast_returns_sorted_hits() {
+    let mut scene = Scene::new("test");
+    let near = scene.add_object(cube("near", Vec3::new(0.0, 0.0, 2.0)));
+    let far = scene.add_object(cube("far", Vec3::new(0.0, 0.0, 6.0)));
+    scene.add_object(cube("aside", Vec3::new(5.0, 0.0, 2.0)));
+    scene.update_spatial_index();
+
+    let hits = scene.raycast(&Ray::new(Vec3::ZERO, Vec3::Z), LayerMask::ALL);
+
+    assert_eq!(hits.len(), 2);
+    assert_eq!((hits[0].object, hits[1].object), (near, far));
+    assert_eq!(hits[0].point, Vec3::new(0.0, 0.0, 1.0));
+    assert_eq!(hits[0].normal, Vec3::NEG_Z);
+}
+
+#[test]
+fn raycast_respects_layers() {
+    let mut scene = Scene::new("test");
+    let ui = LayerMask::layer(3);
+    scene.add_object(cube("default", Vec3::new(0.0, 0.0, 2.0)));
+    let button =
+        scene.add_object(cube("button", Vec3::new(0.0, 0.0, 6.0)).with_component(Layers(ui)));
+    scene.update_spatial_index();
+
+    let ray = Ray::new(Vec3::ZERO, Vec3::Z);
+
+    assert_eq!(scene.raycast_closest(&ray, ui).map(|hit| hit.object), Some(button));
+    assert_eq!(scene.raycast(&ray, LayerMask::DEFAULT | ui).len(), 2);
+    assert!(scene.raycast(&ray, LayerMask::NONE).is_empty());
+}
+
+#[test]
+fn mesh_colliders_test_triangles() {
+    let triangle = CpuMesh {
+        positions: Positions::F32(vec![
+            CpuVec3::new(-1.0, -1.0, 0.0),
+            CpuVec3::new(1.0, -1.0, 0.0),
+            CpuVec3::new(-1.0, 1.0, 0.0),
+        ]),
+        ..Default::default()
+    };
+
+    let mut scene = Scene::new("test");
+    let object = scene.add_object(
+        object_at("triangle", Vec3::new(0.0, 0.0, 4.0))
+            .with_component(MeshCollider::new(&triangle)),
+    );
+    scene.update_spatial_index();
+
+    let inside = Ray::new(Vec3::new(-0.5, -0.5, 0.0), Vec3::Z);
+    let hit = scene.raycast_closest(&inside, LayerMask::ALL).unwrap();
+    assert_eq!((hit.object, hit.point), (object, Vec3::new(-0.5, -0.5, 4.0)));
+    assert_eq!(hit.normal, Vec3::NEG_Z);
+
+    // Inside the bounds of the triangle, but outside the triangle itself.
+    let outside = Ray::new(Vec3::new(0.5, 0.5, 0.0), Vec3::Z);
+    assert!(scene.raycast(&outside, LayerMask::ALL).is_empty());
+}
+
+#[test]
+fn camera_ray_through_center_looks_forward() {
+    let viewport = Viewport::new_at_origo(800, 600);
+    let transform = Transform::from_translation(Vec3::new(0.0, 0.0, -10.0));
+    let camera = Camera::new_3d(viewport, &transform, Vec3::Y, degrees(60.0), 0.5, 100.0);
+
+    let mut scene = Scene::new("test");
+    let target = scene.add_object(cube("target", Vec3::ZERO));
+    scene.update_spatial_index();
+
+    let ray = camera.screen_ray((400.0, 300.0));
+    assert!(ray.direction.abs_diff_eq(Vec3::Z, 1e-5));
+    assert_eq!(scene.raycast_closest(&ray, LayerMask::ALL).map(|hit| hit.object), Some(target));
+
+    let corner = camera.screen_ray((0.0, 0.0));
+    assert!(scene.raycast_closest(&corner, LayerMask::ALL).is_none());
+}
+
+#[test]
+fn raycasts_leave_shared_renderables_in_place() {
+    let renderable = MockRenderable::new();
+    let renderer = Renderer::new(renderable.clone());
+
+    let mut scene = Scene::new("test");
+    let near = scene
+        .add_object(object_at("near", Vec3::new(0.0, 0.0, 2.0)).with_component(renderer.clone()));
+    let far = scene.add_object(object_at("far", Vec3::new(0.0, 0.0, 6.0)).with_component(renderer));
+    scene.update_spatial_index();
+    let before = renderable.transformation();
+
+    let hits = scene.raycast(&Ray::new(Vec3::ZERO, Vec3::Z), LayerMask::ALL);
+
+    assert_eq!(hits.iter().map(|hit| hit.object).collect::<Vec<_>>(), [near, far]);
+    assert_eq!(hits[1].point, Vec3::new(0.0, 0.0, 5.5));
+    assert_eq!(renderable.transformation(), before);
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use ciri::{
+    camera::Camera,
+    scenes::{GameObject, GameObjectId, LayerMask, Layers, Scene, components::Renderer},
+};
+use common::{MockRenderable, camera};
+use std::collections::HashSet;
+
+const UI: LayerMask = LayerMask::layer(5);
+const WEAPON: LayerMask = LayerMask::layer(6);
+
+fn rendered(scene: &mut Scene, layers: Option<LayerMask>) -> GameObjectId {
+    let mut object =
+        GameObject::new("rendered").with_component(Renderer::new(MockRenderable::new()));
+    if let Some(layers) = layers {
+        object.add_component(Layers(layers));
+    }
+    scene.add_object(object)
+}
+
+fn rendered_by(scene: &Scene, camera: &Camera) -> HashSet<GameObjectId> {
+    scene.renderers_for(camera).map(|(id, _)| id).collect()
+}
+
+#[test]
+fn cameras_render_everything_by_default() {
+    let mut scene = Scene::new("test");
+    let world = rendered(&mut scene, None);
+    let ui = rendered(&mut scene, Some(UI));
+    scene.add_object(GameObject::new("not rendered"));
+
+    assert_eq!(camera(LayerMask::ALL).culling_mask, LayerMask::ALL);
+    assert_eq!(rendered_by(&scene, &camera(LayerMask::ALL)), HashSet::from([world, ui]));
+}
+
+#[test]
+fn culling_mask_selects_layers() {
+    let mut scene = Scene::new("test");
+    let world = rendered(&mut scene, None);
+    let ui = rendered(&mut scene, Some(UI));
+    let weapon = rendered(&mut scene, Some(WEAPON));
+    let both = rendered(&mut scene, Some(UI | WEAPON));
+
+    let minimap = camera(!UI);
+    assert_eq!(rendered_by(&scene, &minimap), HashSet::from([world, weapon, both]));
+
+    let weapon_camera = camera(WEAPON);
+    assert_eq!(rendered_by(&scene, &weapon_camera), HashSet::from([weapon, both]));
+
+    scene.objects.get_mut(&weapon).unwrap().disable();
+    assert_eq!(rendered_by(&scene, &weapon_camera), HashSet::from([both]));
+    assert!(rendered_by(&scene, &camera(LayerMask::NONE)).is_empty());
+    assert!(rendered_by(&scene, &camera(LayerMask::DEFAULT)).contains(&world));
+    assert!(!rendered_by(&scene, &camera(LayerMask::DEFAULT)).contains(&ui));
+}
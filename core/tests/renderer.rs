@@ -0,0 +1,63 @@
+mod common;
+
+use ciri::{
+    math::{Ray, Transform, Vec3, from_glam_mat4},
+    scenes::{GameObject, LayerMask, Scene, components::Renderer},
+};
+use common::MockRenderable;
+
+fn rendered(mock: &MockRenderable, position: Vec3) -> GameObject {
+    let mut object = GameObject::new("rendered").with_component(Renderer::new(mock.clone()));
+    object.transform = Transform::from_translation(position);
+    object
+}
+
+#[test]
+fn update_moves_renderers_to_their_transform() {
+    let mock = MockRenderable::new();
+    let mut scene = Scene::new("test");
+    let id = scene.add_object(rendered(&mock, Vec3::new(1.0, 2.0, 3.0)));
+
+    scene.update(0.1);
+    let expected = Transform::from_translation(Vec3::new(1.0, 2.0, 3.0)).to_matrix();
+    assert_eq!(mock.transformation(), from_glam_mat4(expected));
+
+    let object = scene.objects.get_mut(&id).unwrap();
+    object.transform.translation = Vec3::new(-4.0, 0.0, 0.0);
+    object.transform.scale = Vec3::splat(2.0);
+    scene.sync_renderers();
+
+    let object = scene.query(id).unwrap();
+    assert_eq!(mock.transformation(), from_glam_mat4(object.transform.to_matrix()));
+}
+
+#[test]
+fn inactive_objects_keep_their_last_transform() {
+    let mock = MockRenderable::new();
+    let mut scene = Scene::new("test");
+    let id = scene.add_object(rendered(&mock, Vec3::X));
+    scene.update(0.1);
+
+    let object = scene.objects.get_mut(&id).unwrap();
+    object.disable();
+    object.transform.translation = Vec3::Y;
+    scene.update(0.1);
+
+    let expected = Transform::from_translation(Vec3::X).to_matrix();
+    assert_eq!(mock.transformation(), from_glam_mat4(expected));
+}
+
+#[test]
+fn raycasts_hit_moved_renderers() {
+    let mut scene = Scene::new("test");
+    let id = scene.add_object(rendered(&MockRenderable::new(), Vec3::new(0.0, 0.0, 5.0)));
+    let ray = Ray::new(Vec3::ZERO, Vec3::Z);
+
+    scene.update(0.1);
+    let hit = scene.raycast_closest(&ray, LayerMask::ALL).unwrap();
+    assert_eq!((hit.object, hit.distance), (id, 4.5));
+
+    scene.objects.get_mut(&id).unwrap().transform.translation = Vec3::new(3.0, 0.0, 5.0);
+    scene.update(0.1);
+    assert!(scene.raycast_closest(&ray, LayerMask::ALL).is_none());
+}
@@ -0,0 +1,148 @@
+use ciri::{
+    impl_component,
+    scenes::{
+        GameObject, Scene,
+        components::{Component, ComponentError, UpdateContext, Updateable},
+    },
+};
+use std::{any::Any, fmt::Debug};
+
+#[derive(Clone, Default)]
+struct Health(u32);
+
+impl_component!(Health);
+
+#[derive(Clone, Default)]
+struct Armor;
+
+impl_component!(Armor, requires_default(Health));
+
+#[derive(Clone)]
+struct Regeneration;
+
+impl_component!(Regeneration, updateable, requires(Health));
+
+impl Updateable for Regeneration {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        if let Some(health) = ctx.get_component_mut::<Health>() {
+            health.0 += 1;
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Ghost;
+
+impl_component!(Ghost, conflicts(Health));
+
+#[derive(Clone)]
+struct Shield;
+
+impl_component!(Shield, updateable, requires(Regeneration));
+
+impl Updateable for Shield {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        let err = ctx.try_remove_component::<Regeneration>().unwrap_err();
+        assert!(matches!(err, ComponentError::RequiredBy { .. }));
+    }
+}
+
+#[derive(Clone, Default)]
+struct Rider(u32);
+
+impl_component!(Rider, requires_default(Mount));
+
+#[derive(Clone, Default)]
+struct Mount;
+
+impl_component!(Mount, requires_default(Rider));
+
+#[test]
+fn missing_requirement_names_types() {
+    let mut object = GameObject::new("object");
+    let err = object.try_add_component(Regeneration).unwrap_err();
+
+    assert!(matches!(err, ComponentError::MissingRequired { .. }));
+    let message = err.to_string();
+    assert!(message.contains("Regeneration"), "{message}");
+    assert!(message.contains("Health"), "{message}");
+    assert!(!object.has_component::<Regeneration>());
+}
+
+#[test]
+fn conflicts_are_checked_both_ways() {
+    let mut object = GameObject::new("object").with_component(Health(1));
+    let err = object.try_add_component(Ghost).unwrap_err();
+    assert!(err.to_string().contains("Ghost"));
+
+    let mut object = GameObject::new("object").with_component(Ghost);
+    let err = object.try_add_component(Health(1)).unwrap_err();
+    assert!(matches!(err, ComponentError::Conflict { .. }));
+}
+
+#[test]
+fn inserts_default_requirements() {
+    let mut object = GameObject::new("object");
+    object.try_add_component(Armor).unwrap();
+
+    assert_eq!(object.get_component::<Health>().unwrap().0, 0);
+    assert!(object.has_component::<Armor>());
+}
+
+#[test]
+fn inserts_components_requiring_each_other() {
+    let mut object = GameObject::new("object");
+    object.try_add_component(Rider(3)).unwrap();
+
+    assert_eq!(object.get_component::<Rider>().unwrap().0, 3);
+    assert!(object.has_component::<Mount>());
+
+    let mut object = GameObject::new("object");
+    object.try_add_component(Mount).unwrap();
+    assert_eq!(object.get_component::<Rider>().unwrap().0, 0);
+}
+
+#[test]
+fn rolls_back_default_requirements_on_error() {
+    let mut object = GameObject::new("object").with_component(Ghost);
+    object.try_add_component(Armor).unwrap_err();
+
+    assert!(!object.has_component::<Health>());
+    assert!(!object.has_component::<Armor>());
+}
+
+#[test]
+fn required_component_cannot_be_removed() {
+    let mut object =
+        GameObject::new("object").with_component(Health(1)).with_component(Regeneration);
+
+    let err = object.try_remove_component::<Health>().unwrap_err();
+    assert!(matches!(err, ComponentError::RequiredBy { .. }));
+    assert!(object.has_component::<Health>());
+
+    assert!(object.try_remove_component::<Regeneration>().unwrap());
+    assert!(object.try_remove_component::<Health>().unwrap());
+}
+
+#[test]
+#[should_panic(expected = "requires missing component")]
+fn add_component_panics_with_error() {
+    GameObject::new("object").add_component(Regeneration);
+}
+
+#[test]
+fn requirements_hold_during_update() {
+    let mut scene = Scene::new("test");
+    let id = scene.add_object(
+        GameObject::new("object")
+            .with_component(Health(0))
+            .with_component(Regeneration)
+            .with_component(Shield),
+    );
+
+    scene.update(1.0);
+
+    let object = scene.query(id).unwrap();
+    assert!(object.has_component::<Regeneration>());
+    assert_eq!(object.get_component::<Health>().unwrap().0, 1);
+}
@@ -0,0 +1,86 @@
+use ciri::{
+    resources::Resources,
+    scenes::{
+        GameObject, Scene,
+        components::{Component, UpdateContext, Updateable},
+        systems::{Res, ResMut},
+    },
+};
+
+#[derive(Debug, Default, PartialEq)]
+struct Score(u32);
+
+#[derive(Debug, PartialEq)]
+struct Difficulty(u32);
+
+#[derive(Debug, Clone, Component)]
+#[component(updateable)]
+struct Collector;
+
+impl Updateable for Collector {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        let points = ctx.resource::<Difficulty>().map_or(1, |difficulty| difficulty.0);
+        ctx.resource_mut::<Score>().unwrap().0 += points;
+    }
+}
+
+fn double_score(mut score: ResMut<Score>, difficulty: Option<Res<Difficulty>>) {
+    score.0 *= difficulty.map_or(2, |difficulty| difficulty.0);
+}
+
+#[test]
+fn resources_are_keyed_by_type() {
+    let mut resources = Resources::new();
+    assert_eq!(resources.insert(Score(1)), None);
+    assert_eq!(resources.insert(Score(2)), Some(Score(1)));
+    resources.insert(Difficulty(3));
+
+    resources.get_mut::<Score>().unwrap().0 += 1;
+
+    assert_eq!(resources.get::<Score>(), Some(&Score(3)));
+    assert_eq!(resources.remove::<Difficulty>(), Some(Difficulty(3)));
+    assert!(!resources.contains::<Difficulty>());
+    assert_eq!(resources.len(), 1);
+}
+
+#[test]
+fn components_and_systems_share_resources() {
+    let mut scene = Scene::new("test");
+    scene.insert_resource(Score::default());
+    scene.add_object(GameObject::new("collector").with_component(Collector));
+    scene.add_system(double_score);
+
+    scene.update(1.0);
+    scene.update(1.0);
+
+    assert_eq!(scene.resource::<Score>(), Some(&Score(6)));
+}
+
+#[test]
+fn optional_resources_can_be_missing() {
+    let mut scene = Scene::new("test");
+    scene.insert_resource(Difficulty(3));
+    scene.add_system(|score: Option<Res<Score>>, _: Res<Difficulty>| assert!(score.is_none()));
+
+    scene.update(1.0);
+
+    assert_eq!(scene.remove_resource::<Difficulty>(), Some(Difficulty(3)));
+    assert_eq!(scene.resource::<Difficulty>(), None);
+}
+
+#[test]
+#[should_panic(expected = "requires missing resource")]
+fn missing_resources_panic() {
+    let mut scene = Scene::new("test");
+    scene.add_system(double_score);
+    scene.update(1.0);
+}
+
+#[test]
+#[should_panic(expected = "more than once")]
+fn conflicting_resources_panic() {
+    let mut scene = Scene::new("test");
+    scene.insert_resource(Score::default());
+    scene.add_system(|_: ResMut<Score>, _: ResMut<Score>| {});
+    scene.update(1.0);
+}
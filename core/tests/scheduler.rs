@@ -0,0 +1,144 @@
+use ciri::scenes::{
+    Commands, GameObject, Scene,
+    components::{Component, UpdateContext, Updateable},
+};
+
+#[derive(Debug, Default)]
+struct Log(Vec<&'static str>);
+
+fn log(scene: &mut Scene, entry: &'static str) {
+    scene.resource_mut::<Log>().unwrap().0.push(entry);
+}
+
+fn entries(scene: &Scene) -> Vec<&'static str> {
+    scene.resource::<Log>().unwrap().0.clone()
+}
+
+fn setup() -> Scene {
+    let mut scene = Scene::new("test");
+    scene.insert_resource(Log::default());
+    scene
+}
+
+#[derive(Debug, Clone, Component)]
+#[component(updateable)]
+struct Fuse {
+    lit: bool,
+}
+
+impl Updateable for Fuse {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        if !self.lit {
+            self.lit = true;
+            ctx.after(1.0, |scene| log(scene, "boom"));
+        }
+    }
+}
+
+#[test]
+fn timers_fire_after_delay() {
+    let mut scene = setup();
+    scene.after(None, 1.0, |scene| log(scene, "once"));
+    let every = scene.every(None, 0.5, |scene| log(scene, "tick"));
+
+    scene.update(0.6);
+    assert_eq!(entries(&scene), ["tick"]);
+
+    scene.update(0.6);
+    assert_eq!(entries(&scene), ["tick", "once", "tick"]);
+
+    assert!(scene.cancel_task(every));
+    scene.update(1.0);
+    assert_eq!(entries(&scene).len(), 3);
+    assert!(!scene.is_task_scheduled(every));
+}
+
+#[test]
+fn owned_tasks_are_cancelled_with_owner() {
+    let mut scene = setup();
+    let owner = scene.add_object(GameObject::new("fuse").with_component(Fuse { lit: false }));
+
+    scene.update(0.5);
+    scene.remove_object(owner);
+    scene.update(1.0);
+
+    assert!(entries(&scene).is_empty());
+}
+
+#[test]
+fn despawning_unschedules_owned_tasks() {
+    let mut scene = setup();
+    let owner = scene.add_object(GameObject::new("owner"));
+    let timer = scene.after(Some(owner), 1.0, |scene| log(scene, "boom"));
+    let other = scene.after(None, 1.0, |scene| log(scene, "other"));
+
+    let mut commands = Commands::new();
+    commands.despawn(owner);
+    commands.apply(&mut scene);
+
+    assert!(!scene.is_task_scheduled(timer));
+    assert!(scene.is_task_scheduled(other));
+}
+
+#[test]
+fn component_timers_fire() {
+    let mut scene = setup();
+    scene.add_object(GameObject::new("fuse").with_component(Fuse { lit: false }));
+
+    scene.update(0.5);
+    scene.update(0.5);
+
+    assert_eq!(entries(&scene), ["boom"]);
+}
+
+#[test]
+fn coroutines_wait_between_steps() {
+    let mut scene = setup();
+    scene.start_coroutine(None, |co| async move {
+        co.run(|scene| log(scene, "start"));
+        co.wait_seconds(1.0).await;
+        co.run(|scene| log(scene, "seconds"));
+        co.wait_frames(2).await;
+        co.run(|scene| log(scene, "frames"));
+        co.wait_until(|scene| scene.objects().len() == 1).await;
+        co.run(|scene| log(scene, "until"));
+    });
+
+    scene.update(0.5);
+    assert_eq!(entries(&scene), ["start"]);
+
+    scene.update(0.5);
+    scene.update(0.5);
+    assert_eq!(entries(&scene), ["start", "seconds"]);
+
+    scene.update(0.5);
+    scene.update(0.5);
+    assert_eq!(entries(&scene), ["start", "seconds", "frames"]);
+
+    scene.add_object(GameObject::new("trigger"));
+    scene.update(0.5);
+    assert_eq!(entries(&scene), ["start", "seconds", "frames", "until"]);
+}
+
+#[test]
+fn time_scale_and_pause_are_honored() {
+    let mut scene = setup();
+    scene.after(None, 1.0, |scene| log(scene, "once"));
+    scene.start_coroutine(None, |co| async move {
+        co.wait_frames(1).await;
+        co.run(|scene| log(scene, "frame"));
+    });
+
+    scene.time.pause();
+    scene.update(5.0);
+    assert!(entries(&scene).is_empty());
+    assert!(scene.time.delta() <= 0.0);
+
+    scene.time.resume();
+    scene.time.set_scale(0.5);
+    scene.update(1.0);
+    assert!(entries(&scene).is_empty());
+
+    scene.update(1.0);
+    assert_eq!(entries(&scene), ["once", "frame"]);
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use ciri::{
+    lights::{Cascades, DirectionalLight, MAX_CASCADES, ShadowSettings},
+    scenes::{GameObject, GameObjectId, Scene, components::Renderer},
+};
+use common::MockRenderable;
+use std::collections::HashSet;
+
+#[test]
+fn cascade_splits_grow_with_distance() {
+    let splits = Cascades::new(4, 100.0).splits(0.1, 1000.0);
+
+    assert_eq!(splits.len(), 4);
+    assert!((splits[3] - 100.0).abs() < 1e-3);
+    assert!(splits.windows(2).all(|pair| pair[0] < pair[1]));
+
+    // Later cascades cover more of the view than earlier ones.
+    let lengths: Vec<_> = splits.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    assert!(lengths.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn uniform_cascade_splits_are_evenly_spaced() {
+    let cascades = Cascades { split_lambda: 0.0, ..Cascades::new(2, 50.0) };
+    let splits = cascades.splits(0.0, 100.0);
+
+    assert!((splits[0] - 25.0).abs() < 1e-4);
+    assert!((splits[1] - 50.0).abs() < 1e-4);
+}
+
+#[test]
+fn cascade_count_is_limited() {
+    assert_eq!(Cascades::new(0, 10.0).count, 1);
+    assert_eq!(Cascades::new(16, 10.0).count, MAX_CASCADES);
+}
+
+#[test]
+fn builder_enables_shadows() {
+    assert_eq!(DirectionalLight::builder().shadows, None);
+
+    let builder = DirectionalLight::builder().shadows(1024).shadow_bias(0.01, 0.1);
+    assert_eq!(
+        builder.shadows,
+        Some(ShadowSettings { resolution: 1024, bias: 0.01, slope_bias: 0.1, cascades: None })
+    );
+
+    let builder = DirectionalLight::builder().cascades(3, 80.0);
+    assert_eq!(
+        builder.shadows,
+        Some(ShadowSettings { cascades: Some(Cascades::new(3, 80.0)), ..Default::default() })
+    );
+}
+
+fn rendered(scene: &mut Scene, renderer: Renderer) -> GameObjectId {
+    scene.add_object(GameObject::new("rendered").with_component(renderer))
+}
+
+#[test]
+fn objects_can_opt_out_of_casting_shadows() {
+    let mut scene = Scene::new("test");
+    let caster = rendered(&mut scene, Renderer::new(MockRenderable::new()));
+    let receiver =
+        rendered(&mut scene, Renderer::new(MockRenderable::new()).without_shadow_receiving());
+    rendered(&mut scene, Renderer::new(MockRenderable::new()).without_shadow_casting());
+    scene.update(0.1);
+
+    let casters: HashSet<_> = scene.shadow_casters().map(|(id, _)| id).collect();
+    assert_eq!(casters, HashSet::from([caster, receiver]));
+
+    let renderer = scene.objects[&receiver].get_component::<Renderer>().unwrap();
+    assert!(renderer.cast_shadows);
+    assert!(!renderer.receive_shadows);
+}
+
+#[test]
+fn inactive_objects_cast_no_shadows() {
+    let mut scene = Scene::new("test");
+    let id = rendered(&mut scene, Renderer::new(MockRenderable::new()));
+    scene.objects.get_mut(&id).unwrap().disable();
+    scene.update(0.1);
+
+    assert_eq!(scene.shadow_casters().count(), 0);
+}
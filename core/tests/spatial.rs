@@ -0,0 +1,123 @@
+mod common;
+
+use ciri::{
+    math::{Aabb, Frustum, Mat4, Vec3},
+    scenes::{Bounds, GameObjectId, Scene, components::Renderer},
+};
+use common::{MockRenderable, object_at};
+use std::collections::HashSet;
+
+fn grid() -> (Scene, Vec<GameObjectId>) {
+    let mut scene = Scene::new("test");
+    let mut ids = Vec::new();
+
+    for x in 0..10 {
+        for z in 0..10 {
+            let position = Vec3::new(x as f32, 0.0, z as f32);
+            ids.push(scene.add_object(object_at("point", position)));
+        }
+    }
+
+    scene.update_spatial_index();
+    (scene, ids)
+}
+
+fn brute_force(scene: &Scene, matches: impl Fn(Vec3) -> bool) -> HashSet<GameObjectId> {
+    scene
+        .objects()
+        .iter()
+        .filter(|(_, object)| matches(object.transform.translation))
+        .map(|(id, _)| *id)
+        .collect()
+}
+
+fn set(ids: Vec<GameObjectId>) -> HashSet<GameObjectId> {
+    ids.into_iter().collect()
+}
+
+#[test]
+fn region_queries_match_linear_scan() {
+    let (scene, _) = grid();
+    let center = Vec3::new(4.5, 0.0, 4.5);
+
+    let sphere = scene.spatial().query_sphere(center, 2.0);
+    assert_eq!(set(sphere), brute_force(&scene, |p| p.distance(center) <= 2.0));
+
+    let aabb = Aabb::new(Vec3::new(1.0, -1.0, 2.0), Vec3::new(3.0, 1.0, 8.0));
+    let inside = scene.spatial().query_aabb(&aabb);
+    assert_eq!(set(inside), brute_force(&scene, |p| aabb.contains_point(p)));
+}
+
+#[test]
+fn nearest_k_is_sorted_by_distance() {
+    let (scene, _) = grid();
+    let point = Vec3::new(2.2, 0.0, 7.1);
+
+    let nearest = scene.spatial().nearest_k(point, 3);
+    let positions: Vec<Vec3> =
+        nearest.iter().map(|id| scene.query(*id).unwrap().transform.translation).collect();
+
+    assert_eq!(positions[0], Vec3::new(2.0, 0.0, 7.0));
+    assert_eq!(positions.len(), 3);
+    assert!(positions.windows(2).all(|w| w[0].distance(point) <= w[1].distance(point)));
+    assert_eq!(scene.spatial().nearest_k(point, 500).len(), 100);
+}
+
+#[test]
+fn follows_moved_added_and_disabled_objects() {
+    let (mut scene, ids) = grid();
+    let far = Vec3::new(100.0, 0.0, 100.0);
+
+    scene.objects.get_mut(&ids[0]).unwrap().transform.translation = far;
+    scene.update(1.0);
+    assert_eq!(scene.spatial().query_sphere(far, 1.0), [ids[0]]);
+
+    scene.objects.get_mut(&ids[0]).unwrap().disable();
+    let added = scene.add_object(object_at("added", far));
+    scene.update(1.0);
+    assert_eq!(scene.spatial().query_sphere(far, 1.0), [added]);
+    assert_eq!(scene.objects_near(added, 1.0), []);
+}
+
+#[test]
+fn bounds_component_is_indexed() {
+    let mut scene = Scene::new("test");
+    let wall = scene.add_object(
+        object_at("wall", Vec3::new(0.0, 0.0, 10.0))
+            .with_component(Bounds::from_size(Vec3::new(20.0, 2.0, 1.0))),
+    );
+    scene.update_spatial_index();
+
+    assert_eq!(scene.spatial().query_sphere(Vec3::new(9.0, 0.0, 9.0), 1.0), [wall]);
+    assert_eq!(
+        scene.spatial().bounds(wall),
+        Some(Aabb::new(Vec3::new(-10.0, -1.0, 9.5), Vec3::new(10.0, 1.0, 10.5)))
+    );
+}
+
+#[test]
+fn renderer_bounds_are_indexed_without_bounds_component() {
+    let mut scene = Scene::new("test");
+    let mut cube = object_at("cube", Vec3::new(5.0, 0.0, 0.0));
+    cube.transform.scale = Vec3::splat(2.0);
+    let cube = scene.add_object(cube.with_component(Renderer::new(MockRenderable::new())));
+    scene.update_spatial_index();
+
+    assert_eq!(
+        scene.spatial().bounds(cube),
+        Some(Aabb::new(Vec3::new(4.0, -1.0, -1.0), Vec3::new(6.0, 1.0, 1.0)))
+    );
+    assert_eq!(scene.spatial().query_sphere(Vec3::new(6.5, 0.0, 0.0), 0.6), [cube]);
+}
+
+#[test]
+fn frustum_query_returns_visible_objects() {
+    let (scene, _) = grid();
+    let view = Mat4::look_at_rh(Vec3::new(0.0, 1.0, -5.0), Vec3::new(0.0, 1.0, 0.0), Vec3::Y);
+    let projection = Mat4::perspective_rh_gl(0.5, 1.0, 0.1, 100.0);
+    let frustum = Frustum::from_view_projection(projection * view);
+
+    let visible = scene.spatial().query_frustum(&frustum);
+    assert!(!visible.is_empty());
+    assert_eq!(set(visible), brute_force(&scene, |p| frustum.contains_point(p)));
+}
@@ -0,0 +1,112 @@
+use ciri::{
+    math::Vec2,
+    scenes::{GameObject, GameObjectId, Scene},
+    sprites::{SpriteAnimator, SpriteRegion},
+};
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+fn animated(scene: &mut Scene, animator: SpriteAnimator) -> GameObjectId {
+    scene.add_object(GameObject::new("animated").with_component(animator))
+}
+
+fn animator(scene: &Scene, id: GameObjectId) -> &SpriteAnimator {
+    scene.objects[&id].get_component::<SpriteAnimator>().unwrap()
+}
+
+#[test]
+fn grid_regions_go_row_by_row() {
+    let regions = SpriteRegion::grid(64, 34, 4, 2);
+
+    assert_eq!(regions.len(), 8);
+    assert_eq!(regions[0], SpriteRegion::new(0, 0, 16, 17));
+    assert_eq!(regions[3], SpriteRegion::new(48, 0, 16, 17));
+    assert_eq!(regions[4], SpriteRegion::new(0, 17, 16, 17));
+    assert!(SpriteRegion::grid(64, 64, 0, 2).is_empty());
+}
+
+#[test]
+fn regions_map_to_texture_coordinates() {
+    let (min, max) = SpriteRegion::new(16, 32, 16, 32).uv_bounds(64, 128);
+
+    assert_eq!(min, Vec2::new(0.25, 0.25));
+    assert_eq!(max, Vec2::new(0.5, 0.5));
+}
+
+#[test]
+fn looping_animations_start_over() {
+    let mut scene = Scene::new("test");
+    let id = animated(&mut scene, SpriteAnimator::new([3, 4, 5], 4.0));
+    assert_eq!(animator(&scene, id).frame(), Some(3));
+
+    let mut frames = Vec::new();
+    for _ in 0..4 {
+        scene.update(0.25);
+        frames.push(animator(&scene, id).frame().unwrap());
+    }
+    assert_eq!(frames, vec![4, 5, 3, 4]);
+    assert!(animator(&scene, id).is_playing());
+}
+
+#[test]
+fn animations_played_once_stop_at_their_last_frame() {
+    let finished = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&finished);
+
+    let mut scene = Scene::new("test");
+    let id = animated(
+        &mut scene,
+        SpriteAnimator::new(0..3, 4.0).once().with_finish_event(move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }),
+    );
+
+    scene.update(0.5);
+    assert_eq!(animator(&scene, id).position(), 2);
+    assert_eq!(finished.load(Ordering::Relaxed), 0);
+
+    scene.update(0.5);
+    scene.update(0.5);
+    assert_eq!(animator(&scene, id).position(), 2);
+    assert!(!animator(&scene, id).is_playing());
+    assert_eq!(finished.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn events_are_called_when_their_frame_is_shown() {
+    let first = Arc::new(AtomicUsize::new(0));
+    let last = Arc::new(AtomicUsize::new(0));
+    let (first_counter, last_counter) = (Arc::clone(&first), Arc::clone(&last));
+
+    let mut scene = Scene::new("test");
+    animated(
+        &mut scene,
+        SpriteAnimator::new(0..3, 4.0)
+            .with_event(0, move |_| {
+                first_counter.fetch_add(1, Ordering::Relaxed);
+            })
+            .with_event(2, move |_| {
+                last_counter.fetch_add(1, Ordering::Relaxed);
+            }),
+    );
+
+    // A long frame shows every frame on the way.
+    scene.update(1.0);
+    assert_eq!(first.load(Ordering::Relaxed), 2);
+    assert_eq!(last.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn paused_animations_wait_to_be_played() {
+    let mut scene = Scene::new("test");
+    let id = animated(&mut scene, SpriteAnimator::new(0..3, 4.0).paused());
+
+    scene.update(0.5);
+    assert_eq!(animator(&scene, id).position(), 0);
+
+    scene.objects.get_mut(&id).unwrap().get_component_mut::<SpriteAnimator>().unwrap().play();
+    scene.update(0.5);
+    assert_eq!(animator(&scene, id).position(), 2);
+}
@@ -0,0 +1,209 @@
+use ciri::{
+    math::{Transform, Vec3},
+    scenes::{
+        GameObject, GameObjectId, Scene, Time,
+        components::{Component, UpdateContext, Updateable},
+        systems::{IntoSystemConfig, Query, ResMut, SceneCommands, Stage, With, Without},
+    },
+};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+#[derive(Debug, Clone, Component)]
+struct Enemy {
+    speed: f32,
+}
+
+#[derive(Debug, Clone, Component)]
+struct Player;
+
+#[derive(Debug, Clone, Default, Component)]
+struct Log(Vec<&'static str>);
+
+#[derive(Debug, Clone, Component)]
+#[component(updateable)]
+struct Marker;
+
+impl Updateable for Marker {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        if let Some(log) = ctx.get_component_mut::<Log>() {
+            log.0.push("component");
+        }
+    }
+}
+
+fn move_enemies(mut enemies: Query<(&mut Transform, &Enemy)>, time: Time) {
+    for (transform, enemy) in enemies.iter_mut() {
+        transform.translation.x += enemy.speed * time.delta();
+    }
+}
+
+#[expect(clippy::needless_pass_by_value, reason = "systems take their parameters by value")]
+fn count_players(players: Query<&Player>, others: Query<GameObjectId, Without<Player>>) {
+    assert_eq!(players.iter().count(), 1);
+    assert_eq!(others.iter().count(), 2);
+}
+
+fn log_first(mut logs: Query<&mut Log>) {
+    logs.iter_mut().for_each(|log| log.0.push("first"));
+}
+
+fn log_second(mut logs: Query<&mut Log>) {
+    logs.iter_mut().for_each(|log| log.0.push("second"));
+}
+
+fn log_pre_update(mut logs: Query<&mut Log>) {
+    logs.iter_mut().for_each(|log| log.0.push("pre"));
+}
+
+#[expect(clippy::needless_pass_by_value, reason = "systems take their parameters by value")]
+fn despawn_enemies(enemies: Query<GameObjectId, With<Enemy>>, mut commands: SceneCommands) {
+    for id in enemies.iter() {
+        commands.despawn(id);
+    }
+}
+
+fn log_object(log: &GameObject) -> Vec<&'static str> {
+    log.get_component::<Log>().unwrap().0.clone()
+}
+
+fn setup() -> (Scene, GameObjectId) {
+    let mut scene = Scene::new("test");
+    scene.add_object(GameObject::new("player").with_component(Player));
+    scene.add_object(GameObject::new("fast").with_component(Enemy { speed: 2.0 }));
+    let log = scene.add_object(GameObject::new("log").with_component(Log::default()));
+    (scene, log)
+}
+
+#[test]
+fn queries_move_objects() {
+    let (mut scene, _) = setup();
+    scene.add_system(move_enemies).add_system(count_players);
+
+    scene.update(0.5);
+
+    let enemy = scene.objects().values().find(|object| object.name == "fast").unwrap();
+    assert_eq!(enemy.transform.translation, Vec3::new(1.0, 0.0, 0.0));
+}
+
+#[test]
+fn respects_stage_and_ordering() {
+    let mut scene = Scene::new("test");
+    let log = scene
+        .add_object(GameObject::new("log").with_component(Log::default()).with_component(Marker));
+    scene
+        .add_system(log_second.after(log_first))
+        .add_system(log_first)
+        .add_system(log_pre_update.in_stage(Stage::PreUpdate));
+
+    scene.update(1.0);
+
+    assert_eq!(log_object(scene.query(log).unwrap()), ["pre", "component", "first", "second"]);
+}
+
+#[test]
+fn systems_can_be_disabled() {
+    let (mut scene, log) = setup();
+    scene.add_system(log_first).add_system(log_second.disabled());
+    assert_eq!(scene.is_system_enabled(log_second), Some(false));
+
+    scene.update(1.0);
+    assert!(scene.set_system_enabled(log_second, true));
+    assert!(scene.set_system_enabled(log_first, false));
+    scene.update(1.0);
+    assert!(scene.remove_system(log_second));
+    scene.update(1.0);
+
+    assert_eq!(log_object(scene.query(log).unwrap()), ["first", "second"]);
+    assert_eq!(scene.is_system_enabled(log_second), None);
+}
+
+#[test]
+fn commands_are_applied_after_stage() {
+    let (mut scene, _) = setup();
+    scene.add_system(despawn_enemies);
+
+    scene.update(1.0);
+
+    assert_eq!(scene.objects().len(), 2);
+}
+
+#[test]
+fn closures_keep_state() {
+    let (mut scene, _) = setup();
+    let mut runs = 0;
+    scene.add_system(move |players: Query<&Player>| {
+        runs += 1;
+        assert_eq!(players.iter().count(), 1);
+        assert!(runs <= 2);
+    });
+
+    scene.update(1.0);
+    scene.update(1.0);
+}
+
+#[test]
+#[should_panic(expected = "cyclic ordering")]
+fn cyclic_ordering_panics() {
+    let (mut scene, _) = setup();
+    scene.add_system(log_first.after(log_second)).add_system(log_second.after(log_first));
+    scene.update(1.0);
+}
+
+#[test]
+#[should_panic(expected = "conflicts with another query")]
+fn conflicting_queries_panic() {
+    let (mut scene, _) = setup();
+    scene.add_system(|_: Query<&mut Log>, _: Query<&Player>| {});
+    scene.update(1.0);
+}
+
+#[test]
+fn function_pointers_are_separate_systems() {
+    let (mut scene, log) = setup();
+    let first: fn(Query<&mut Log>) = log_first;
+    let second: fn(Query<&mut Log>) = log_second;
+    scene.add_system(first);
+    let id = scene.register_system(second);
+
+    scene.update(1.0);
+    assert!(scene.remove_system(id));
+    assert!(!scene.remove_system(second));
+    scene.update(1.0);
+
+    assert_eq!(log_object(scene.query(log).unwrap()), ["first", "second", "first"]);
+}
+
+#[test]
+fn queries_iterate_in_id_order() {
+    let (mut scene, _) = setup();
+    for index in 0..32 {
+        scene.add_object(
+            GameObject::new(format!("enemy {index}")).with_component(Enemy { speed: 1.0 }),
+        );
+    }
+    scene.insert_resource(Vec::<GameObjectId>::new());
+    scene.add_system(
+        |enemies: Query<GameObjectId, With<Enemy>>, mut seen: ResMut<Vec<GameObjectId>>| {
+            seen.extend(enemies.iter());
+        },
+    );
+
+    scene.update(1.0);
+
+    let seen = scene.resource::<Vec<GameObjectId>>().unwrap();
+    assert_eq!(seen.len(), 33);
+    assert!(seen.is_sorted_by_key(GameObjectId::index));
+}
+
+#[test]
+fn panicking_systems_leave_objects_and_resources_in_the_scene() {
+    let (mut scene, _) = setup();
+    scene.insert_resource(1_u32);
+    scene.add_system(|_: Query<&Player>, _: ResMut<u32>| panic!("system failed"));
+
+    let result = catch_unwind(AssertUnwindSafe(|| scene.update(1.0)));
+
+    assert!(result.is_err());
+    assert_eq!(scene.objects().len(), 3);
+    assert_eq!(scene.resource::<u32>(), Some(&1));
+}
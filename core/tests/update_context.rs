@@ -0,0 +1,259 @@
+use ciri::{
+    impl_component,
+    math::Vec3,
+    scenes::{
+        GameObject, Scene,
+        components::{Component, UpdateContext, Updateable},
+    },
+};
+use std::{
+    any::Any,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+#[derive(Clone, Default)]
+struct Counter {
+    count: u32,
+}
+
+impl_component!(Counter);
+
+#[derive(Clone)]
+struct Mover {
+    speed: f32,
+}
+
+impl_component!(Mover, updateable);
+
+impl Updateable for Mover {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        let distance = self.speed * ctx.delta_time();
+        ctx.transform_mut().translation.x += distance;
+
+        if let Some(counter) = ctx.get_component_mut::<Counter>() {
+            counter.count += 1;
+        }
+    }
+}
+
+#[derive(Clone)]
+struct SelfDestruct;
+
+impl_component!(SelfDestruct, updateable);
+
+impl Updateable for SelfDestruct {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        assert!(ctx.get_component::<SelfDestruct>().is_none());
+        assert!(ctx.has_component::<SelfDestruct>());
+        ctx.remove_component::<SelfDestruct>();
+    }
+}
+
+#[derive(Clone)]
+struct Spawner;
+
+impl_component!(Spawner, updateable);
+
+impl Updateable for Spawner {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        ctx.commands().spawn(GameObject::new("spawned"));
+        ctx.remove_self();
+    }
+}
+
+#[derive(Clone)]
+struct Despawner;
+
+impl_component!(Despawner, updateable);
+
+impl Updateable for Despawner {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        let id = ctx.id().expect("scene objects have an id");
+        ctx.commands().despawn(id);
+    }
+}
+
+#[derive(Clone)]
+struct Cloner;
+
+impl_component!(Cloner, updateable);
+
+impl Updateable for Cloner {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        let copy = ctx.game_object().clone();
+        assert!(!format!("{copy:?}").contains("Detached"));
+        ctx.commands().spawn(copy);
+        ctx.remove_self();
+    }
+}
+
+#[derive(Clone)]
+struct Follower {
+    seen: usize,
+}
+
+impl_component!(Follower, updateable);
+
+impl Updateable for Follower {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        let own = ctx.id();
+        self.seen = ctx.objects().filter(|(id, _)| Some(**id) != own).count();
+
+        if let Some((_, target)) = ctx.objects().find(|(_, object)| object.name == "target") {
+            let translation = target.transform.translation;
+            ctx.transform_mut().translation = translation;
+        }
+    }
+}
+
+#[derive(Clone)]
+struct First;
+
+impl_component!(First, updateable);
+
+impl Updateable for First {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        ctx.transform_mut().translation.y = 1.0;
+    }
+
+    fn update_priority(&self) -> i32 {
+        -1
+    }
+}
+
+#[derive(Clone)]
+struct Second;
+
+impl_component!(Second, updateable);
+
+impl Updateable for Second {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        ctx.transform_mut().translation.y *= 2.0;
+    }
+}
+
+#[derive(Clone)]
+struct Recorder {
+    index: usize,
+    order: Arc<Mutex<Vec<usize>>>,
+}
+
+impl_component!(Recorder, updateable);
+
+impl Updateable for Recorder {
+    fn update(&mut self, _: &mut UpdateContext) {
+        self.order.lock().unwrap().push(self.index);
+    }
+}
+
+#[test]
+fn updates_transform_and_sibling_components() {
+    let mut scene = Scene::new("test");
+    let id = scene.add_object(
+        GameObject::new("mover")
+            .with_component(Counter::default())
+            .with_component(Mover { speed: 2.0 }),
+    );
+
+    scene.update(0.5);
+    scene.update(0.5);
+
+    let object = scene.query(id).unwrap();
+    assert_eq!(object.transform.translation, Vec3::new(2.0, 0.0, 0.0));
+    assert_eq!(object.get_component::<Counter>().unwrap().count, 2);
+    assert!(object.has_component::<Mover>());
+    assert_eq!(scene.time.frame_count(), 2);
+}
+
+#[test]
+fn updates_objects_in_the_order_they_were_added() {
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let mut scene = Scene::new("test");
+    for index in 0..16 {
+        let recorder = Recorder { index, order: Arc::clone(&order) };
+        scene.add_object(GameObject::new("recorder").with_component(recorder));
+    }
+
+    scene.update(1.0);
+
+    assert_eq!(*order.lock().unwrap(), (0..16).collect::<Vec<_>>());
+}
+
+#[test]
+fn component_can_remove_itself() {
+    let mut scene = Scene::new("test");
+    let id = scene.add_object(GameObject::new("object").with_component(SelfDestruct));
+
+    scene.update(1.0);
+
+    assert!(!scene.query(id).unwrap().has_component::<SelfDestruct>());
+    scene.update(1.0);
+}
+
+#[test]
+fn commands_are_applied_after_update() {
+    let mut scene = Scene::new("test");
+    scene.add_object(GameObject::new("spawner").with_component(Spawner));
+    let doomed = scene.add_object(GameObject::new("doomed").with_component(Despawner));
+
+    scene.update(1.0);
+
+    assert!(scene.query(doomed).is_none());
+    assert_eq!(scene.objects().len(), 2);
+    assert!(scene.objects().values().any(|object| object.name == "spawned"));
+}
+
+#[test]
+fn clones_during_update_leave_out_the_updated_component() {
+    let mut scene = Scene::new("test");
+    scene.add_object(
+        GameObject::new("original").with_component(Counter::default()).with_component(Cloner),
+    );
+
+    scene.update(1.0);
+
+    assert_eq!(scene.objects().len(), 2);
+    for object in scene.objects().values() {
+        assert!(object.has_component::<Counter>());
+        assert!(!object.has_component::<Cloner>());
+    }
+}
+
+#[test]
+fn reads_other_objects() {
+    let mut scene = Scene::new("test");
+    let mut target = GameObject::new("target");
+    target.transform.translation = Vec3::new(1.0, 2.0, 3.0);
+    scene.add_object(target);
+    let id = scene.add_object(GameObject::new("follower").with_component(Follower { seen: 0 }));
+
+    scene.update(1.0);
+
+    let follower = scene.query(id).unwrap();
+    assert_eq!(follower.transform.translation, Vec3::new(1.0, 2.0, 3.0));
+    assert_eq!(follower.get_component::<Follower>().unwrap().seen, 1);
+}
+
+#[test]
+fn respects_update_priority() {
+    let mut scene = Scene::new("test");
+    let id =
+        scene.add_object(GameObject::new("object").with_component(Second).with_component(First));
+
+    scene.update(1.0);
+
+    assert_eq!(scene.query(id).unwrap().transform.translation, Vec3::new(0.0, 2.0, 0.0));
+}
+
+#[test]
+fn skips_inactive_objects() {
+    let mut scene = Scene::new("test");
+    let mut object = GameObject::new("inactive").with_component(Mover { speed: 1.0 });
+    object.disable();
+    let id = scene.add_object(object);
+
+    scene.update(1.0);
+
+    assert_eq!(scene.query(id).unwrap().transform.translation, Vec3::ZERO);
+}
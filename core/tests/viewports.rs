@@ -0,0 +1,62 @@
+use ciri::{
+    camera::{CameraBuilder, ViewportRect, manager::CameraManager},
+    scenes::LayerMask,
+};
+use three_d::{ClearState, Viewer, Viewport};
+
+const SCREEN: Viewport = Viewport { x: 0, y: 0, width: 801, height: 600 };
+
+#[test]
+fn rects_cover_the_screen_without_gaps() {
+    let left = ViewportRect::LEFT_HALF.to_viewport(SCREEN);
+    let right = ViewportRect::RIGHT_HALF.to_viewport(SCREEN);
+
+    assert_eq!(left.x + left.width.cast_signed(), right.x);
+    assert_eq!(left.width + right.width, SCREEN.width);
+    assert_eq!(ViewportRect::FULL.to_viewport(SCREEN), SCREEN);
+
+    let minimap = ViewportRect::new(0.75, 0.75, 0.25, 0.25).to_viewport(SCREEN);
+    assert_eq!(minimap, Viewport { x: 601, y: 450, width: 200, height: 150 });
+}
+
+#[test]
+fn split_screen_cameras_render_in_order() {
+    let mut cameras = CameraManager::new();
+    let main = cameras.add_camera(CameraBuilder::new().build(SCREEN));
+    let hidden = cameras.add_camera(CameraBuilder::new().build(SCREEN));
+    let minimap = cameras.add_camera(
+        CameraBuilder::new()
+            .viewport_rect(ViewportRect::new(0.75, 0.75, 0.25, 0.25))
+            .render_order(10)
+            .clear_state(ClearState::depth(1.0))
+            .culling_mask(LayerMask::layer(1))
+            .build(SCREEN),
+    );
+    let background = cameras.add_camera(CameraBuilder::new().render_order(-1).build(SCREEN));
+
+    let ids = |cameras: &CameraManager| -> Vec<_> {
+        cameras.rendered_cameras().into_iter().map(|(id, _)| id).collect()
+    };
+    assert_eq!(ids(&cameras), [main, minimap]);
+
+    cameras.set_active_camera(background);
+    assert_eq!(ids(&cameras), [background, minimap]);
+
+    let minimap = cameras.get_camera(minimap).unwrap();
+    assert_eq!(minimap.viewport(), Viewport { x: 601, y: 450, width: 200, height: 150 });
+    assert_eq!(minimap.clear_state, ClearState::depth(1.0));
+    assert!(!ids(&cameras).contains(&hidden));
+}
+
+#[test]
+fn viewports_follow_the_screen() {
+    let mut cameras = CameraManager::new();
+    let left = cameras
+        .add_camera(CameraBuilder::new().viewport_rect(ViewportRect::LEFT_HALF).build(SCREEN));
+    let fixed_viewport = Viewport { x: 10, y: 10, width: 100, height: 100 };
+    let fixed = cameras.add_camera(CameraBuilder::new().auto_viewport(false).build(fixed_viewport));
+
+    cameras.update_viewports(Viewport::new_at_origo(1000, 500));
+    assert_eq!(cameras.get_camera(left).unwrap().viewport(), Viewport::new_at_origo(500, 500));
+    assert_eq!(cameras.get_camera(fixed).unwrap().viewport(), fixed_viewport);
+}
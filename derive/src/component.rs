@@ -0,0 +1,100 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Result, Token, Type, parenthesized, punctuated::Punctuated};
+
+#[derive(Default)]
+struct ComponentOptions {
+    updateable: bool,
+    lifecycle: bool,
+    requires: Vec<Type>,
+    requires_default: Vec<Type>,
+    conflicts: Vec<Type>,
+}
+
+impl ComponentOptions {
+    fn parse(input: &DeriveInput) -> Result<Self> {
+        let mut options = Self::default();
+
+        for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("component")) {
+            attr.parse_nested_meta(|meta| {
+                let list = if meta.path.is_ident("updateable") {
+                    options.updateable = true;
+                    return Ok(());
+                } else if meta.path.is_ident("lifecycle") {
+                    options.lifecycle = true;
+                    return Ok(());
+                } else if meta.path.is_ident("requires") {
+                    &mut options.requires
+                } else if meta.path.is_ident("requires_default") {
+                    &mut options.requires_default
+                } else if meta.path.is_ident("conflicts") {
+                    &mut options.conflicts
+                } else {
+                    return Err(meta.error("unknown component option"));
+                };
+
+                let content;
+                parenthesized!(content in meta.input);
+                list.extend(Punctuated::<Type, Token![,]>::parse_terminated(&content)?);
+                Ok(())
+            })?;
+        }
+
+        Ok(options)
+    }
+}
+
+pub fn expand(input: &DeriveInput) -> Result<TokenStream> {
+    let options = ComponentOptions::parse(input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let components = quote!(::ciri::scenes::components);
+
+    let updateable = options.updateable.then(|| {
+        quote! {
+            fn as_updateable(&mut self) -> ::std::option::Option<&mut dyn #components::Updateable> {
+                ::std::option::Option::Some(self)
+            }
+        }
+    });
+
+    let lifecycle = options.lifecycle.then(|| {
+        quote! {
+            fn as_lifecycle(
+                &mut self,
+            ) -> ::std::option::Option<&mut dyn #components::ComponentLifecycle> {
+                ::std::option::Option::Some(self)
+            }
+        }
+    });
+
+    let requires = &options.requires;
+    let requires_default = &options.requires_default;
+    let conflicts = &options.conflicts;
+
+    Ok(quote! {
+        impl #impl_generics #components::Component for #name #ty_generics #where_clause {
+            fn as_any(&self) -> &dyn ::std::any::Any {
+                self
+            }
+
+            fn as_any_mut(&mut self) -> &mut dyn ::std::any::Any {
+                self
+            }
+
+            fn clone_component(&self) -> ::std::boxed::Box<dyn #components::Component> {
+                ::std::boxed::Box::new(::std::clone::Clone::clone(self))
+            }
+
+            fn get_requirements(&self) -> #components::ComponentRequirements {
+                #components::ComponentRequirements::new()
+                    #(.require::<#requires>())*
+                    #(.require_default::<#requires_default>())*
+                    #(.conflict::<#conflicts>())*
+            }
+
+            #updateable
+            #lifecycle
+        }
+    })
+}
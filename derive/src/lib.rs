@@ -0,0 +1,52 @@
+mod component;
+mod scene;
+
+use proc_macro::TokenStream;
+use syn::{DeriveInput, parse_macro_input};
+
+/// Implements `Component` for a `Clone + Debug` type.
+///
+/// Capabilities and requirements are declared with the `component` attribute:
+///
+/// ```ignore
+/// #[derive(Debug, Clone, Component)]
+/// #[component(updateable, lifecycle, requires(Renderer), requires_default(Health), conflicts(Ghost))]
+/// struct Player;
+/// ```
+#[proc_macro_derive(Component, attributes(component))]
+pub fn derive_component(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    component::expand(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Implements `SceneAuto` for a struct holding a `Scene`.
+///
+/// The scene field is either named `scene` or marked with `#[scene]`. Fields marked with
+/// `#[asset("path")]` are loaded before the scene is set up, and can be reloaded by name when
+/// their file changes. An option can follow the path:
+///
+/// - `optional` loads into an `Option<T>`, which is `None` if the file is missing.
+/// - `many` loads every file of a directory or glob, like `"textures/*.png"`, into a `Vec<T>`.
+/// - `fallback` uses the placeholder of the type, or `fallback = function`, if loading fails.
+///
+/// Until they are loaded, assets without an option hold their default value, and `fallback`
+/// assets their fallback. Types without a default value, like `CpuModel`, need an option.
+///
+/// ```ignore
+/// #[derive(Scene)]
+/// #[scene(name = "Game")]
+/// struct Game {
+///     scene: Scene,
+///     #[asset("assets/environment.hdr")]
+///     skybox: Texture2D,
+///     #[asset("assets/level.glb", fallback)]
+///     level: CpuModel,
+///     #[asset("assets/props/*.obj", many)]
+///     props: Vec<CpuModel>,
+/// }
+/// ```
+#[proc_macro_derive(Scene, attributes(scene, asset))]
+pub fn derive_scene(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    scene::expand(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
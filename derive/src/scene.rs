@@ -0,0 +1,214 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    Data, DeriveInput, Error, Fields, Ident, LitStr, Path, Result, Token, Type, parse::ParseStream,
+    spanned::Spanned,
+};
+
+enum AssetKind {
+    Required,
+    /// `Option<T>`, `None` if the file is missing.
+    Optional,
+    /// `Vec<T>` of every file matched by a directory or glob.
+    Many,
+    /// Falls back to the function, or to the placeholder, if loading fails.
+    Fallback(Option<Path>),
+}
+
+struct Asset<'a> {
+    field: &'a Ident,
+    ty: &'a Type,
+    path: LitStr,
+    kind: AssetKind,
+}
+
+impl Asset<'_> {
+    fn parse<'a>(field: &'a Ident, ty: &'a Type, input: ParseStream) -> Result<Asset<'a>> {
+        let path = input.parse()?;
+        let mut kind = AssetKind::Required;
+
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            if !matches!(kind, AssetKind::Required) {
+                return Err(input.error("an asset takes at most one option"));
+            }
+
+            let option: Ident = input.parse()?;
+            kind = if option == "optional" {
+                AssetKind::Optional
+            } else if option == "many" {
+                AssetKind::Many
+            } else if option == "fallback" {
+                let function = if input.parse::<Option<Token![=]>>()?.is_some() {
+                    Some(input.parse()?)
+                } else {
+                    None
+                };
+                AssetKind::Fallback(function)
+            } else {
+                return Err(Error::new(option.span(), "unknown asset option"));
+            };
+        }
+
+        Ok(Asset { field, ty, path, kind })
+    }
+
+    /// The options in the form the `__asset_*` macros of `ciri` take them.
+    fn options(&self) -> TokenStream {
+        match &self.kind {
+            AssetKind::Required => TokenStream::new(),
+            AssetKind::Optional => quote!(; optional),
+            AssetKind::Many => quote!(; many),
+            AssetKind::Fallback(None) => quote!(; fallback),
+            AssetKind::Fallback(Some(function)) => quote!(; fallback = #function),
+        }
+    }
+
+    fn load(&self) -> TokenStream {
+        let (path, options) = (&self.path, self.options());
+        quote!(::ciri::__asset_load!(assets, #path #options))
+    }
+
+    fn reload(&self) -> TokenStream {
+        let (path, options) = (&self.path, self.options());
+        quote!(::ciri::__asset_reload!(assets, #path #options))
+    }
+
+    fn initial(&self) -> TokenStream {
+        let (ty, options) = (self.ty, self.options());
+        quote!(::ciri::__asset_initial!(#ty #options))
+    }
+}
+
+pub fn expand(input: &DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(input.span(), "Scene can only be derived for structs"));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new(
+            input.span(),
+            "Scene can only be derived for structs with named fields",
+        ));
+    };
+
+    let mut scene_name = LitStr::new(&name.to_string(), name.span());
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("scene")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                scene_name = meta.value()?.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("unknown scene option"))
+            }
+        })?;
+    }
+
+    let mut scene_field = None;
+    let mut assets = Vec::new();
+
+    for field in &fields.named {
+        let ident = field.ident.as_ref().expect("named fields have an identifier");
+
+        for attr in &field.attrs {
+            if attr.path().is_ident("scene") {
+                attr.meta.require_path_only()?;
+                scene_field = Some(ident);
+            } else if attr.path().is_ident("asset") {
+                assets.push(
+                    attr.parse_args_with(|input: ParseStream| {
+                        Asset::parse(ident, &field.ty, input)
+                    })?,
+                );
+            }
+        }
+    }
+
+    let scene_field = scene_field
+        .or_else(|| fields.named.iter().filter_map(|f| f.ident.as_ref()).find(|i| *i == "scene"))
+        .ok_or_else(|| {
+            Error::new(
+                input.span(),
+                "missing scene field, name it `scene` or mark it with #[scene]",
+            )
+        })?;
+
+    let other_fields = fields
+        .named
+        .iter()
+        .filter_map(|f| f.ident.as_ref())
+        .filter(|i| *i != scene_field && !assets.iter().any(|asset| asset.field == *i));
+    let asset_fields = assets.iter().map(|asset| asset.field).collect::<Vec<_>>();
+    let asset_paths = assets.iter().map(|asset| &asset.path).collect::<Vec<_>>();
+    let asset_names = assets.iter().map(|asset| asset.field.to_string()).collect::<Vec<_>>();
+    let asset_loads = assets.iter().map(Asset::load);
+    let asset_reloads = assets.iter().map(Asset::reload);
+    let asset_initials = assets.iter().map(Asset::initial);
+
+    Ok(quote! {
+        impl #impl_generics ::ciri::scenes::SceneAuto for #name #ty_generics #where_clause {
+            fn name(&self) -> &'static str {
+                #scene_name
+            }
+
+            fn scene(&mut self) -> &mut ::ciri::scenes::Scene {
+                &mut self.#scene_field
+            }
+
+            fn load_assets(
+                &mut self,
+            ) -> ::ciri::scenes::ResultFuture<'_, ::ciri::__private::anyhow::Result<()>> {
+                let assets = ::std::clone::Clone::clone(self.#scene_field.assets());
+                ::std::boxed::Box::pin(async move {
+                    #(
+                        self.#asset_fields = #asset_loads;
+                    )*
+
+                    ::std::result::Result::Ok(())
+                })
+            }
+
+            fn asset_paths(&self) -> &'static [(&'static str, &'static str)] {
+                &[#((#asset_names, #asset_paths)),*]
+            }
+
+            fn reload_asset<'a>(
+                &'a mut self,
+                name: &'a str,
+            ) -> ::ciri::scenes::ResultFuture<'a, ::ciri::__private::anyhow::Result<bool>> {
+                let assets = ::std::clone::Clone::clone(self.#scene_field.assets());
+                ::std::boxed::Box::pin(async move {
+                    #(
+                        if name == #asset_names {
+                            self.#asset_fields = #asset_reloads;
+                            return ::std::result::Result::Ok(true);
+                        }
+                    )*
+
+                    ::std::result::Result::Ok(false)
+                })
+            }
+
+            fn once_loaded(&self) -> bool {
+                self.#scene_field.is_loaded()
+            }
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Creates the scene with every other field set to its default value.
+            pub fn build() -> Self {
+                Self {
+                    #scene_field: ::ciri::scenes::Scene::new(#scene_name),
+                    #(#asset_fields: #asset_initials,)*
+                    #(#other_fields: ::std::default::Default::default(),)*
+                }
+            }
+        }
+    })
+}
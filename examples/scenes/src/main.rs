@@ -4,32 +4,31 @@ use crate::rotator::Rotator;
 use anyhow::Result;
 use ciri::{
     engine::Engine,
-    impl_scene,
-    lights::{AmbientLight, DirectionalLight},
+    lights::{DirectionalLight, Environment, Sky},
     logger::init_logger,
     math::Vec3,
     model::Model,
-    scenes::{
-        GameObject, ResultFuture, Scene, SceneAuto, SceneTrait, UpdateResult, components::Renderer,
-    },
+    scenes::{GameObject, GameObjectId, Scene, SceneTrait, UpdateResult},
 };
-use ciri_math::{Transform, vector};
 use log::error;
-use three_d::{
-    Context, CpuMaterial, CpuMesh, FrameInput, FrameOutput, Geometry, Gm, Mesh, PhysicalMaterial,
-    Skybox, SurfaceSettings, Window, WindowSettings,
-};
-use three_d_asset::{
-    Srgba, Texture2D,
-    io::{RawAssets, load_async},
-};
+use three_d::{Context, FrameOutput, Window, WindowSettings};
+use three_d_asset::Texture2D;
 
 #[derive(Default)]
 pub struct GameData {
     pub num: usize,
+    pub environment: Option<GameObjectId>,
+}
+
+#[derive(Scene)]
+#[scene(name = "Game")]
+pub struct Game {
+    pub scene: Scene,
+    pub data: GameData,
+    #[asset("examples/assets/environment.hdr")]
+    pub skybox: Texture2D,
 }
 
-impl_scene!("Game", Game, GameData, (skybox, "examples/assets/environment.hdr" => Texture2D));
 impl SceneTrait for Game {
     fn update(&mut self) -> UpdateResult {
         Ok(FrameOutput::default())
@@ -37,23 +36,32 @@ impl SceneTrait for Game {
 
     fn setup_sync(&mut self, ctx: Context) -> Result<()> {
         self.scene.setup_orbit_camera();
+        self.add_environment(&ctx);
+        self.scene.add_object(GameObject::new("rotator").with_component(Rotator::new(0.5)));
 
-        let skybox = Skybox::new_from_equirectangular(&ctx, &self.skybox);
-        self.scene.add_light(
-            AmbientLight::builder()
-                .color(Srgba::WHITE)
-                .environment(&skybox.texture())
-                .intensity(1.0)
-                .build(&ctx),
-        );
+        Ok(())
+    }
 
-        self.scene.add_object(GameObject::new("environment").with_component(Renderer::new(skybox)));
-        self.scene.add_object(GameObject::new("rotator").with_component(Rotator::new(0.5)));
+    fn on_asset_reloaded(&mut self, name: &str, ctx: Context) -> Result<()> {
+        if name == "skybox" {
+            if let Some(environment) = self.data.environment.take() {
+                self.scene.remove_object(environment);
+            }
+            self.add_environment(&ctx);
+        }
 
         Ok(())
     }
 }
 
+impl Game {
+    fn add_environment(&mut self, ctx: &Context) {
+        let environment = Environment::new(ctx, &Sky::Image(self.skybox.clone()));
+        let environment = GameObject::new("environment").with_component(environment);
+        self.data.environment = Some(self.scene.add_object(environment));
+    }
+}
+
 fn main() -> anyhow::Result<()> {
     init_logger()?;
     let window = Window::new(WindowSettings {
@@ -64,13 +72,14 @@ fn main() -> anyhow::Result<()> {
     let ctx = window.gl();
 
     let mut engine = Engine::new(ctx);
+    engine.scenes.set_hot_reload(cfg!(debug_assertions));
 
     engine.scenes.register(Game::build());
     engine.scenes.set_active::<Game>()?;
 
     window.render_loop(move |input| {
         engine.update(input).unwrap_or_else(|e| {
-            error!("Error: {}", e);
+            error!("Error: {e}");
             FrameOutput::default()
         })
     });
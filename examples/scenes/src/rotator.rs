@@ -1,13 +1,7 @@
-use ciri::{
-    impl_component,
-    scenes::{
-        GameObject,
-        components::{Component, Updateable},
-    },
-};
-use std::{any::Any, fmt::Debug};
+use ciri::scenes::components::{Component, UpdateContext, Updateable};
 
-#[derive(Clone)]
+#[derive(Debug, Clone, Component)]
+#[component(updateable)]
 pub struct Rotator {
     rotation_speed: f32,
 }
@@ -18,11 +12,9 @@ impl Rotator {
     }
 }
 
-impl_component!(Rotator, updateable);
-
 impl Updateable for Rotator {
-    fn update(&mut self, delta_time: f32, game_object: &mut GameObject) {
-        game_object.transform.rotate_y(self.rotation_speed * delta_time);
-        println!("{:#?}", game_object.transform.rotation);
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        let angle = self.rotation_speed * ctx.delta_time();
+        ctx.transform_mut().rotate_y(angle);
     }
 }
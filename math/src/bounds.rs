@@ -0,0 +1,226 @@
+use crate::Transform;
+use glam::{Mat4, Vec3, Vec4};
+
+/// An axis-aligned bounding box.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min: min.min(max), max: min.max(max) }
+    }
+
+    pub fn from_center(center: Vec3, half_extents: Vec3) -> Self {
+        Self::new(center - half_extents, center + half_extents)
+    }
+
+    /// A box of zero size around the point.
+    pub fn from_point(point: Vec3) -> Self {
+        Self { min: point, max: point }
+    }
+
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn half_extents(&self) -> Vec3 {
+        (self.max - self.min) * 0.5
+    }
+
+    pub fn size(&self) -> Vec3 {
+        self.max - self.min
+    }
+
+    /// Smallest box containing both boxes.
+    #[must_use]
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Self { min: self.min.min(other.min), max: self.max.max(other.max) }
+    }
+
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min.cmple(other.max).all() && self.max.cmpge(other.min).all()
+    }
+
+    /// Squared distance from the point to the closest point of the box, zero inside.
+    pub fn distance_squared(&self, point: Vec3) -> f32 {
+        point.clamp(self.min, self.max).distance_squared(point)
+    }
+
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        self.distance_squared(sphere.center) <= sphere.radius * sphere.radius
+    }
+
+    pub fn corners(&self) -> [Vec3; 8] {
+        let (min, max) = (self.min, self.max);
+        [
+            min,
+            Vec3::new(max.x, min.y, min.z),
+            Vec3::new(min.x, max.y, min.z),
+            Vec3::new(max.x, max.y, min.z),
+            Vec3::new(min.x, min.y, max.z),
+            Vec3::new(max.x, min.y, max.z),
+            Vec3::new(min.x, max.y, max.z),
+            max,
+        ]
+    }
+
+    /// Box around the corners of this box after applying the transform.
+    #[must_use]
+    pub fn transformed(&self, transform: &Transform) -> Aabb {
+        let matrix = transform.to_matrix();
+        let center = matrix.transform_point3(self.center());
+        let half = self.half_extents();
+
+        // Each axis of the result spans the absolute projection of the rotated, scaled half
+        // extents.
+        let extents = matrix.x_axis.truncate().abs() * half.x
+            + matrix.y_axis.truncate().abs() * half.y
+            + matrix.z_axis.truncate().abs() * half.z;
+
+        Self::from_center(center, extents)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Sphere {
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+impl Sphere {
+    pub fn new(center: Vec3, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        self.center.distance_squared(point) <= self.radius * self.radius
+    }
+}
+
+/// The six planes bounding the volume visible to a camera.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Frustum {
+    /// Planes as `(normal, distance)` with normals pointing inside.
+    pub planes: [Vec4; 6],
+}
+
+impl Frustum {
+    /// Extracts the planes from a `projection * view` matrix.
+    pub fn from_view_projection(matrix: Mat4) -> Self {
+        let rows = [matrix.row(0), matrix.row(1), matrix.row(2), matrix.row(3)];
+        let planes = [
+            rows[3] + rows[0],
+            rows[3] - rows[0],
+            rows[3] + rows[1],
+            rows[3] - rows[1],
+            rows[3] + rows[2],
+            rows[3] - rows[2],
+        ];
+
+        Self { planes: planes.map(|plane| plane / plane.truncate().length()) }
+    }
+
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        self.planes.iter().all(|plane| plane.truncate().dot(point) + plane.w >= 0.0)
+    }
+
+    /// Whether the box is at least partly inside. Boxes near the corners of the frustum may be
+    /// reported as intersecting while outside.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        self.planes.iter().all(|plane| {
+            let normal = plane.truncate();
+            // The corner furthest along the normal.
+            let corner = Vec3::select(normal.cmpge(Vec3::ZERO), aabb.max, aabb.min);
+            normal.dot(corner) + plane.w >= 0.0
+        })
+    }
+
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.truncate().dot(sphere.center) + plane.w >= -sphere.radius)
+    }
+}
+
+/// A half-line starting at `origin`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Ray {
+    pub origin: Vec3,
+    /// Normalized direction of the ray.
+    pub direction: Vec3,
+}
+
+impl Ray {
+    pub fn new(origin: Vec3, direction: Vec3) -> Self {
+        Self { origin, direction: direction.normalize() }
+    }
+
+    /// Point at `distance` along the ray.
+    pub fn at(&self, distance: f32) -> Vec3 {
+        self.origin + self.direction * distance
+    }
+
+    /// Distance to the box and the normal of the face hit. A ray starting inside the box hits it
+    /// at distance zero, facing the ray.
+    pub fn intersect_aabb(&self, aabb: &Aabb) -> Option<(f32, Vec3)> {
+        let inverse = self.direction.recip();
+        let near = (aabb.min - self.origin) * inverse;
+        let far = (aabb.max - self.origin) * inverse;
+        let entry = near.min(far);
+        let exit = near.max(far).min_element();
+        let distance = entry.max_element();
+
+        if exit < distance.max(0.0) {
+            return None;
+        }
+
+        if distance < 0.0 {
+            return Some((0.0, -self.direction));
+        }
+
+        // The face hit is on the axis the ray enters the box last.
+        let axis = if entry.x >= entry.y && entry.x >= entry.z {
+            Vec3::X
+        } else if entry.y >= entry.z {
+            Vec3::Y
+        } else {
+            Vec3::Z
+        };
+
+        Some((distance, -axis * self.direction.dot(axis).signum()))
+    }
+
+    /// Distance to the triangle and its normal facing the ray, hitting both sides.
+    #[expect(clippy::many_single_char_names, reason = "names follow Möller–Trumbore")]
+    pub fn intersect_triangle(&self, [a, b, c]: [Vec3; 3]) -> Option<(f32, Vec3)> {
+        let (edge1, edge2) = (b - a, c - a);
+        let p = self.direction.cross(edge2);
+        let determinant = edge1.dot(p);
+        if determinant.abs() < f32::EPSILON {
+            return None;
+        }
+
+        let to_origin = self.origin - a;
+        let u = to_origin.dot(p) / determinant;
+        let q = to_origin.cross(edge1);
+        let v = self.direction.dot(q) / determinant;
+        if u < 0.0 || v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+
+        let distance = edge2.dot(q) / determinant;
+        if distance < 0.0 {
+            return None;
+        }
+
+        let normal = edge1.cross(edge2).normalize();
+        Some((distance, if normal.dot(self.direction) > 0.0 { -normal } else { normal }))
+    }
+}
@@ -1,7 +1,9 @@
+mod bounds;
 mod transform;
 mod vectors;
 
 pub use glam::f32::*;
+pub use bounds::*;
 use three_d::{Matrix4, Vector3};
 pub use transform::*;
 pub use vectors::*;
@@ -14,6 +16,10 @@ pub fn from_glam_vec(vec: Vec3) -> Vector3<f32> {
     Vector3::new(vec.x, vec.y, vec.z)
 }
 
+pub fn to_glam_mat4(mat: Matrix4<f32>) -> Mat4 {
+    Mat4::from_cols_array_2d(mat.as_ref())
+}
+
 pub fn from_glam_mat4(mat: Mat4) -> Matrix4<f32> {
     let cols = &mat.to_cols_array_2d();
     Matrix4::from_cols(cols[0].into(), cols[1].into(), cols[2].into(), cols[3].into(),)